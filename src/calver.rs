@@ -0,0 +1,307 @@
+//! Calendar versioning, as described at <https://calver.org>. A version scheme
+//! is a pattern such as `YYYY.MM.MICRO`, and bumping a version moves its date
+//! components to today, or increments its counter if it was already released today.
+
+use std::fmt::{self, Display};
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, Context};
+
+use crate::error::Error;
+
+/// The pattern used when none is given.
+pub const DEFAULT_PATTERN: &str = "YYYY.MM.MICRO";
+
+const SEPARATORS: [char; 2] = ['.', '-'];
+
+/// One component of a calendar version pattern.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Token {
+    /// The full year, as in `2024`.
+    FullYear,
+    /// The year since 2000, as in `24` or `6`.
+    ShortYear,
+    /// The year since 2000, zero-padded, as in `06`.
+    PaddedYear,
+    /// The month, as in `1` or `11`.
+    Month,
+    /// The month, zero-padded, as in `01`.
+    PaddedMonth,
+    /// The day of the month, as in `1` or `31`.
+    Day,
+    /// The day of the month, zero-padded, as in `01`.
+    PaddedDay,
+    /// A counter that starts at 0 and increments for each release on the same date.
+    Micro,
+}
+
+impl Token {
+    fn render(self, date: &Date) -> String {
+        match self {
+            Token::FullYear => date.year.to_string(),
+            Token::ShortYear => (date.year - 2000).to_string(),
+            Token::PaddedYear => format!("{:02}", date.year - 2000),
+            Token::Month => date.month.to_string(),
+            Token::PaddedMonth => format!("{:02}", date.month),
+            Token::Day => date.day.to_string(),
+            Token::PaddedDay => format!("{:02}", date.day),
+            Token::Micro => unreachable!("the counter is not a date component"),
+        }
+    }
+}
+
+impl FromStr for Token {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "YYYY" => Ok(Token::FullYear),
+            "YY" => Ok(Token::ShortYear),
+            "0Y" => Ok(Token::PaddedYear),
+            "MM" => Ok(Token::Month),
+            "0M" => Ok(Token::PaddedMonth),
+            "DD" => Ok(Token::Day),
+            "0D" => Ok(Token::PaddedDay),
+            "MICRO" | "PATCH" => Ok(Token::Micro),
+            _ => Err(anyhow!(
+                "`{input}` is not a calver component; expected one of YYYY, YY, 0Y, MM, 0M, DD, 0D, or MICRO"
+            )),
+        }
+    }
+}
+
+/// A calendar version pattern, such as `YYYY.0M.MICRO`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Pattern {
+    tokens: Vec<Token>,
+    separators: Vec<char>,
+    text: String,
+}
+
+impl FromStr for Pattern {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let tokens = input
+            .split(SEPARATORS)
+            .map(Token::from_str)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+        if tokens.iter().filter(|t| **t == Token::Micro).count() > 1 {
+            return Err(anyhow!("The pattern `{input}` has more than one counter."));
+        }
+        if !tokens.iter().any(|t| *t != Token::Micro) {
+            return Err(anyhow!("The pattern `{input}` has no date components."));
+        }
+        Ok(Self {
+            tokens,
+            separators: input.chars().filter(|c| SEPARATORS.contains(c)).collect(),
+            text: input.to_string(),
+        })
+    }
+}
+
+impl Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+impl Pattern {
+    /// Render a version from this pattern for the given date and counter.
+    fn render(&self, date: &Date, micro: u64) -> String {
+        let mut output = String::new();
+        for (idx, token) in self.tokens.iter().enumerate() {
+            match token {
+                Token::Micro => output.push_str(&micro.to_string()),
+                token => output.push_str(&token.render(date)),
+            }
+            if let Some(sep) = self.separators.get(idx) {
+                output.push(*sep);
+            }
+        }
+        output
+    }
+
+    /// Split a version into its date components and its counter.
+    fn parse(&self, version: &str) -> anyhow::Result<(Vec<u64>, Option<u64>)> {
+        let fields: Vec<&str> = version.split(SEPARATORS).collect();
+        if fields.len() != self.tokens.len() {
+            return Err(Error::invalid(version, format!("{version} does not match the pattern {self}")).into());
+        }
+        let mut date = Vec::new();
+        let mut micro = None;
+        for (token, field) in self.tokens.iter().zip(fields) {
+            let value: u64 = field.parse().map_err(|_| {
+                Error::invalid(
+                    version,
+                    format!("`{field}` in {version} is not a number, so it does not match {self}"),
+                )
+            })?;
+            match token {
+                Token::Micro => micro = Some(value),
+                _ => date.push(value),
+            }
+        }
+        Ok((date, micro))
+    }
+
+    /// The date components of this pattern for the given date, as numbers.
+    fn date_values(&self, date: &Date) -> Vec<u64> {
+        self.tokens
+            .iter()
+            .filter(|t| **t != Token::Micro)
+            .map(|t| t.render(date).parse().unwrap_or_default())
+            .collect()
+    }
+}
+
+/// A calendar date, in UTC.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Date {
+    /// The year, as in `2024`.
+    pub year: i64,
+    /// The month, from 1 to 12.
+    pub month: u32,
+    /// The day of the month, from 1 to 31.
+    pub day: u32,
+}
+
+impl Date {
+    /// Today's date in UTC.
+    pub fn today() -> anyhow::Result<Self> {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .context("the system clock is set before 1970")?
+            .as_secs();
+        Ok(Self::from_days((seconds / 86_400) as i64))
+    }
+
+    /// The date the given number of days after 1970-01-01, using Howard Hinnant's
+    /// `civil_from_days` algorithm.
+    pub(crate) fn from_days(days: i64) -> Self {
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let doe = z.rem_euclid(146_097);
+        let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+        let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+        let mp = (5 * doy + 2) / 153;
+        let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+        let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+        let year = yoe + era * 400 + i64::from(month <= 2);
+        Self { year, month, day }
+    }
+}
+
+impl FromStr for Date {
+    type Err = anyhow::Error;
+
+    /// Parse a date written as `YYYY-MM-DD`.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let invalid = || Error::invalid(input, format!("`{input}` is not a date written as YYYY-MM-DD"));
+        let mut parts = input.splitn(3, '-');
+        let mut next = || parts.next().ok_or_else(invalid);
+        let year = next()?.parse().map_err(|_| invalid())?;
+        let month = next()?.parse().map_err(|_| invalid())?;
+        let day = next()?.parse().map_err(|_| invalid())?;
+        if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+            return Err(invalid().into());
+        }
+        Ok(Self { year, month, day })
+    }
+}
+
+/// Bump a calendar version to the given date. If the previous version is from an
+/// earlier date, the date components move to today and the counter starts over at
+/// 0. If it's from today, the counter increments; a pattern without a counter can
+/// only be released once per date. An empty previous version starts a new series.
+pub fn bump(previous: &str, pattern: &Pattern, today: &Date) -> anyhow::Result<String> {
+    let previous = previous.trim();
+    if today.year < 2000
+        && pattern
+            .tokens
+            .iter()
+            .any(|t| matches!(t, Token::ShortYear | Token::PaddedYear))
+    {
+        return Err(anyhow!("Short years can't represent dates before 2000."));
+    }
+    if previous.is_empty() {
+        return Ok(pattern.render(today, 0));
+    }
+    let (date, micro) = pattern.parse(previous)?;
+    match pattern.date_values(today).cmp(&date) {
+        std::cmp::Ordering::Greater => Ok(pattern.render(today, 0)),
+        std::cmp::Ordering::Equal => match micro {
+            Some(micro) => Ok(pattern.render(today, micro + 1)),
+            None => Err(anyhow!(
+                "{previous} was already released today, and the pattern {pattern} has no counter to increment."
+            )),
+        },
+        std::cmp::Ordering::Less => Err(anyhow!("{previous} is dated later than today.")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(input: &str) -> Date {
+        input.parse().expect("test data must be a valid date")
+    }
+
+    fn pattern(input: &str) -> Pattern {
+        input.parse().expect("test data must be a valid pattern")
+    }
+
+    #[test]
+    fn days_to_dates() {
+        assert_eq!(Date::from_days(0), date("1970-01-01"));
+        assert_eq!(Date::from_days(19_782), date("2024-02-29"));
+        assert_eq!(Date::from_days(20_089), date("2025-01-01"));
+    }
+
+    #[test]
+    fn patterns() {
+        pattern("YYYY.0M.0D");
+        pattern("YY-MM-MICRO");
+        "YYYY.QQ"
+            .parse::<Pattern>()
+            .expect_err("we expected unknown components to be rejected");
+        "MICRO"
+            .parse::<Pattern>()
+            .expect_err("we expected a pattern with no date to be rejected");
+        "YYYY.MICRO.PATCH"
+            .parse::<Pattern>()
+            .expect_err("we expected two counters to be rejected");
+    }
+
+    #[test]
+    fn bumping() {
+        let today = date("2024-03-05");
+        let scheme = pattern(DEFAULT_PATTERN);
+        assert_eq!(
+            bump("2024.3.0", &scheme, &today).expect("we expected a bump"),
+            "2024.3.1"
+        );
+        assert_eq!(
+            bump("2024.2.7", &scheme, &today).expect("we expected a bump"),
+            "2024.3.0"
+        );
+        assert_eq!(bump("", &scheme, &today).expect("we expected a bump"), "2024.3.0");
+        bump("2024.4.0", &scheme, &today).expect_err("we expected a future version to be an error");
+        bump("2024.3", &scheme, &today).expect_err("we expected a mismatched version to be an error");
+
+        let scheme = pattern("0Y.0M.0D");
+        assert_eq!(
+            bump("24.02.29", &scheme, &today).expect("we expected a bump"),
+            "24.03.05"
+        );
+        bump("24.03.05", &scheme, &today).expect_err("we expected a second release today to be an error");
+
+        let scheme = pattern("YYYY-0M-MICRO");
+        assert_eq!(
+            bump("2024-03-9", &scheme, &today).expect("we expected a bump"),
+            "2024-03-10"
+        );
+    }
+}
@@ -0,0 +1,203 @@
+//! Pending changes recorded one file at a time, in the style of
+//! [changesets](https://github.com/changesets/changesets). Each file says how big a
+//! bump its change calls for and describes it for the release notes:
+//!
+//! ```text
+//! ---
+//! bump: minor
+//! ---
+//!
+//! Tags can be signed.
+//! ```
+//!
+//! Releasing makes the largest bump any pending change calls for and uses the
+//! files up.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context};
+
+use crate::Level;
+
+/// Where change files go unless we're told otherwise.
+pub const DEFAULT_DIR: &str = ".changes";
+
+/// One pending change.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Change {
+    /// The bump the change calls for.
+    pub level: Level,
+    /// What changed, in Markdown.
+    pub message: String,
+}
+
+impl Change {
+    /// Read the text of a change file.
+    pub fn parse(text: &str) -> anyhow::Result<Self> {
+        let rest = text
+            .trim_start()
+            .strip_prefix("---")
+            .ok_or_else(|| anyhow!("a change file must start with a `---` header"))?;
+        let (header, message) = rest
+            .split_once("\n---")
+            .ok_or_else(|| anyhow!("a change file's header must end with `---`"))?;
+        let level = header
+            .lines()
+            .filter_map(|line| line.split_once(':'))
+            .find(|(key, _)| key.trim() == "bump")
+            .map(|(_, level)| level.trim().trim_matches('"').parse::<Level>())
+            .ok_or_else(|| anyhow!("a change file's header must say `bump: major`, `minor`, or `patch`"))??;
+        Ok(Self {
+            level,
+            message: message.trim().to_string(),
+        })
+    }
+
+    /// The text of the change's file.
+    pub fn render(&self) -> String {
+        format!("---\nbump: {}\n---\n\n{}\n", self.level, self.message.trim())
+    }
+
+    /// A file name made from the first few words of the message.
+    fn file_stem(&self) -> String {
+        let words: Vec<String> = self
+            .message
+            .split(|c: char| !c.is_ascii_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .take(6)
+            .map(str::to_ascii_lowercase)
+            .collect();
+        if words.is_empty() {
+            "change".to_string()
+        } else {
+            words.join("-")
+        }
+    }
+}
+
+/// Write a change to a new file in the directory, making the directory if need be.
+/// Returns the path of the file.
+pub fn add(dir: &Path, change: &Change) -> anyhow::Result<PathBuf> {
+    fs::create_dir_all(dir).with_context(|| format!("unable to make {}", dir.display()))?;
+    let stem = change.file_stem();
+    let path = (1..)
+        .map(|n| match n {
+            1 => dir.join(format!("{stem}.md")),
+            n => dir.join(format!("{stem}-{n}.md")),
+        })
+        .find(|path| !path.exists())
+        .unwrap_or_default();
+    fs::write(&path, change.render()).with_context(|| format!("unable to write {}", path.display()))?;
+    Ok(path)
+}
+
+/// Every change file in the directory, in order by name. Markdown files that don't
+/// start with a `---` header, such as a `README.md` explaining the directory, aren't
+/// change files. A directory that doesn't exist has none.
+pub fn pending(dir: &Path) -> anyhow::Result<Vec<(PathBuf, Change)>> {
+    if !dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .with_context(|| format!("unable to read {}", dir.display()))?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<Result<_, _>>()?;
+    paths.retain(|path| path.extension().is_some_and(|ext| ext == "md"));
+    paths.sort();
+    let mut changes = Vec::new();
+    for path in paths {
+        let text = fs::read_to_string(&path).with_context(|| format!("unable to read {}", path.display()))?;
+        if !text.trim_start().starts_with("---") {
+            continue;
+        }
+        let change = Change::parse(&text).with_context(|| path.display().to_string())?;
+        changes.push((path, change));
+    }
+    Ok(changes)
+}
+
+/// The largest bump any of the changes calls for.
+pub fn level(changes: &[Change]) -> Option<Level> {
+    changes.iter().map(|change| change.level).max()
+}
+
+/// The changes as Markdown, one `###` section for each size of bump, largest first.
+pub fn notes(changes: &[Change]) -> String {
+    let sections: Vec<String> = [
+        (Level::Major, "Major changes"),
+        (Level::Minor, "Minor changes"),
+        (Level::Patch, "Patch changes"),
+    ]
+    .into_iter()
+    .filter_map(|(level, title)| {
+        let entries: String = changes
+            .iter()
+            .filter(|change| change.level == level)
+            .map(|change| format!("- {}\n", change.message.trim().replace('\n', "\n  ")))
+            .collect();
+        (!entries.is_empty()).then(|| format!("### {title}\n\n{entries}"))
+    })
+    .collect();
+    sections.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reading_changes() {
+        let change =
+            Change::parse("---\nbump: minor\n---\n\nTags can be signed.\n").expect("we expected the change to parse");
+        assert_eq!(
+            change,
+            Change {
+                level: Level::Minor,
+                message: "Tags can be signed.".to_string()
+            }
+        );
+        assert_eq!(
+            Change::parse(&change.render()).expect("we expected the change to parse"),
+            change
+        );
+        Change::parse("bump: minor\n\nNo header.").expect_err("we expected a missing header to be an error");
+        Change::parse("---\nbump: huge\n---\n").expect_err("we expected a bad bump to be an error");
+    }
+
+    #[test]
+    fn adding_and_gathering_changes() {
+        let dir = tempfile::tempdir().expect("we expected to be able to make a temp dir");
+        let dir = dir.path().join(DEFAULT_DIR);
+        assert!(pending(&dir)
+            .expect("we expected a missing directory to be empty")
+            .is_empty());
+        let fix = Change {
+            level: Level::Patch,
+            message: "Fix the parser.".to_string(),
+        };
+        let first = add(&dir, &fix).expect("we expected to add the change");
+        let second = add(&dir, &fix).expect("we expected to add the change");
+        assert_eq!(first.file_name().and_then(|n| n.to_str()), Some("fix-the-parser.md"));
+        assert_eq!(second.file_name().and_then(|n| n.to_str()), Some("fix-the-parser-2.md"));
+        let feature = Change {
+            level: Level::Minor,
+            message: "Add signing.\nWith SSH keys too.".to_string(),
+        };
+        add(&dir, &feature).expect("we expected to add the change");
+        fs::write(dir.join("README.md"), "# Changes\n\nRun `semver-bump change add`.\n")
+            .expect("we expected to write the readme");
+
+        let changes: Vec<Change> = pending(&dir)
+            .expect("we expected to read the changes")
+            .into_iter()
+            .map(|(_, change)| change)
+            .collect();
+        assert_eq!(changes.len(), 3);
+        assert_eq!(level(&changes), Some(Level::Minor));
+        assert_eq!(
+            notes(&changes),
+            "### Minor changes\n\n- Add signing.\n  With SSH keys too.\n\n### Patch changes\n\n- Fix the parser.\n- Fix the parser.\n"
+        );
+    }
+}
@@ -0,0 +1,339 @@
+//! Handing the results of a bump to CI systems through the channels they
+//! provide for passing values between steps.
+
+use std::env;
+use std::fmt::{self, Display};
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context};
+
+/// A CI system we know how to hand variables to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum System {
+    /// GitHub Actions, which reads step outputs from the file in `$GITHUB_OUTPUT`.
+    GitHub,
+    /// GitLab CI, which reads variables from a dotenv report.
+    GitLab,
+    /// Azure Pipelines, which reads `##vso[task.setvariable ...]` logging commands.
+    Azure,
+    /// TeamCity, which reads `##teamcity[setParameter ...]` service messages.
+    TeamCity,
+}
+
+impl Display for System {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            System::GitHub => "github",
+            System::GitLab => "gitlab",
+            System::Azure => "azure",
+            System::TeamCity => "teamcity",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl FromStr for System {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "github" => Ok(System::GitHub),
+            "gitlab" => Ok(System::GitLab),
+            "azure" => Ok(System::Azure),
+            "teamcity" => Ok(System::TeamCity),
+            _ => Err(anyhow!(
+                "`{input}` is not a CI system; expected auto, github, gitlab, azure, or teamcity"
+            )),
+        }
+    }
+}
+
+impl System {
+    /// The CI system we're running in, going by the variables each one sets.
+    pub fn detect() -> Option<Self> {
+        Self::detect_from(|name| env::var(name).ok())
+    }
+
+    fn detect_from(var: impl Fn(&str) -> Option<String>) -> Option<Self> {
+        let is_true = |name: &str| var(name).is_some_and(|value| value.eq_ignore_ascii_case("true"));
+        if is_true("GITHUB_ACTIONS") {
+            Some(System::GitHub)
+        } else if is_true("GITLAB_CI") {
+            Some(System::GitLab)
+        } else if is_true("TF_BUILD") {
+            Some(System::Azure)
+        } else if var("TEAMCITY_VERSION").is_some() {
+            Some(System::TeamCity)
+        } else {
+            None
+        }
+    }
+
+    /// The lines to print to set each of these variables. Systems that read
+    /// variables from files have none.
+    pub fn commands(&self, outputs: &[(&str, String)]) -> Vec<String> {
+        outputs
+            .iter()
+            .filter_map(|(key, value)| match self {
+                System::GitHub | System::GitLab => None,
+                System::Azure => {
+                    let value = value.replace('%', "%AZP25").replace('\r', "%0D").replace('\n', "%0A");
+                    Some(format!("##vso[task.setvariable variable={key};isOutput=true]{value}"))
+                }
+                System::TeamCity => {
+                    let value = teamcity_escape(value);
+                    Some(format!(
+                        "##teamcity[setParameter name='{}' value='{value}']",
+                        teamcity_escape(key)
+                    ))
+                }
+            })
+            .collect()
+    }
+}
+
+/// The number of this build or pipeline run in the CI system we're running in. Jenkins
+/// and everything else we don't detect are asked for `BUILD_NUMBER`.
+pub fn build_number() -> Option<String> {
+    build_number_from(|name| env::var(name).ok())
+}
+
+fn build_number_from(var: impl Fn(&str) -> Option<String>) -> Option<String> {
+    let name = match System::detect_from(&var) {
+        Some(System::GitHub) => "GITHUB_RUN_NUMBER",
+        Some(System::GitLab) => "CI_PIPELINE_IID",
+        Some(System::Azure) => "BUILD_BUILDID",
+        Some(System::TeamCity) | None => "BUILD_NUMBER",
+    };
+    var(name).filter(|number| !number.trim().is_empty())
+}
+
+/// The branch the CI system is building, for a checkout with a detached HEAD. For a
+/// pull request or merge request, it's the branch being merged.
+pub fn branch() -> Option<String> {
+    branch_from(|name| env::var(name).ok())
+}
+
+fn branch_from(var: impl Fn(&str) -> Option<String>) -> Option<String> {
+    let var = |name: &str| var(name).filter(|value| !value.trim().is_empty());
+    match System::detect_from(var)? {
+        System::GitHub => var("GITHUB_HEAD_REF").or_else(|| var("GITHUB_REF_NAME")),
+        System::GitLab => var("CI_COMMIT_BRANCH").or_else(|| var("CI_MERGE_REQUEST_SOURCE_BRANCH_NAME")),
+        System::Azure => var("SYSTEM_PULLREQUEST_SOURCEBRANCH")
+            .or_else(|| var("BUILD_SOURCEBRANCH"))
+            .map(|branch| branch.trim_start_matches("refs/heads/").to_string()),
+        System::TeamCity => None,
+    }
+}
+
+/// Which CI system `--ci` asked for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Choice {
+    /// Whichever system we're running in, if any.
+    Auto,
+    /// This one in particular.
+    System(System),
+}
+
+impl Choice {
+    /// The system to hand variables to, or `None` when we're not running in one we know.
+    pub fn resolve(self) -> Option<System> {
+        match self {
+            Choice::Auto => System::detect(),
+            Choice::System(system) => Some(system),
+        }
+    }
+}
+
+impl FromStr for Choice {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "auto" => Ok(Choice::Auto),
+            _ => input.parse().map(Choice::System),
+        }
+    }
+}
+
+/// Escape a value for a TeamCity service message.
+fn teamcity_escape(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        match c {
+            '|' | '\'' | '[' | ']' => {
+                escaped.push('|');
+                escaped.push(c);
+            }
+            '\n' => escaped.push_str("|n"),
+            '\r' => escaped.push_str("|r"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Append `key=value` lines to the file GitHub Actions reads step outputs from.
+pub fn github_output(outputs: &[(&str, String)]) -> anyhow::Result<()> {
+    let path = env::var_os("GITHUB_OUTPUT")
+        .ok_or_else(|| anyhow!("GITHUB_OUTPUT is not set; are we running in a GitHub Actions step?"))?;
+    append_outputs(Path::new(&path), outputs)
+}
+
+/// Append Markdown to the job summary GitHub Actions shows on the run's page, from the
+/// file named by `$GITHUB_STEP_SUMMARY`.
+pub fn github_step_summary(markdown: &str) -> anyhow::Result<()> {
+    let path = env::var_os("GITHUB_STEP_SUMMARY")
+        .ok_or_else(|| anyhow!("GITHUB_STEP_SUMMARY is not set; are we running in a GitHub Actions step?"))?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .with_context(|| format!("unable to open {} for appending", Path::new(&path).display()))?;
+    writeln!(file, "{markdown}")?;
+    Ok(())
+}
+
+/// A two-column Markdown table of these rows, with the values as code.
+pub fn summary_table(rows: &[(&str, String)]) -> String {
+    let mut table = String::from("| | |\n| --- | --- |\n");
+    for (label, value) in rows {
+        table.push_str(&format!("| {label} | `{value}` |\n"));
+    }
+    table
+}
+
+/// Append `KEY=value` lines to a dotenv file, which GitLab CI turns into variables for
+/// later jobs when the job lists it under `artifacts:reports:dotenv`.
+pub fn gitlab_dotenv(path: &Path, outputs: &[(&str, String)]) -> anyhow::Result<()> {
+    append_outputs(path, outputs)
+}
+
+/// Append `key=value` lines to the given file.
+fn append_outputs(path: &Path, outputs: &[(&str, String)]) -> anyhow::Result<()> {
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)
+        .with_context(|| format!("unable to open {} for appending", path.display()))?;
+    for (key, value) in outputs {
+        writeln!(file, "{key}={value}")?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn appending_outputs() {
+        let dir = tempfile::tempdir().expect("we expected to be able to make a temp dir");
+        let path = dir.path().join("output");
+        fs::write(&path, "earlier=step\n").expect("we expected to be able to write test data");
+        append_outputs(
+            &path,
+            &[("version", "1.2.4".to_string()), ("bump", "patch".to_string())],
+        )
+        .expect("we expected appending to work");
+        let written = fs::read_to_string(&path).expect("we expected to be able to read the file");
+        assert_eq!(written, "earlier=step\nversion=1.2.4\nbump=patch\n");
+    }
+
+    #[test]
+    fn summarizing() {
+        let rows = [("New version", "1.3.0".to_string()), ("Bump", "minor".to_string())];
+        assert_eq!(
+            summary_table(&rows),
+            "| | |\n| --- | --- |\n| New version | `1.3.0` |\n| Bump | `minor` |\n"
+        );
+    }
+
+    #[test]
+    fn detecting_systems() {
+        let detect = |vars: &[(&str, &str)]| {
+            System::detect_from(|name| {
+                vars.iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_string())
+            })
+        };
+        assert_eq!(detect(&[("GITHUB_ACTIONS", "true")]), Some(System::GitHub));
+        assert_eq!(detect(&[("GITLAB_CI", "true")]), Some(System::GitLab));
+        assert_eq!(detect(&[("TF_BUILD", "True")]), Some(System::Azure));
+        assert_eq!(detect(&[("TEAMCITY_VERSION", "2024.03")]), Some(System::TeamCity));
+        assert_eq!(detect(&[("CI", "true")]), None);
+        assert_eq!(detect(&[]), None);
+    }
+
+    #[test]
+    fn branches() {
+        let branch = |vars: &[(&str, &str)]| {
+            branch_from(|name| {
+                vars.iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_string())
+            })
+        };
+        let push = [
+            ("GITHUB_ACTIONS", "true"),
+            ("GITHUB_HEAD_REF", ""),
+            ("GITHUB_REF_NAME", "main"),
+        ];
+        assert_eq!(branch(&push).as_deref(), Some("main"));
+        let pull = [
+            ("GITHUB_ACTIONS", "true"),
+            ("GITHUB_HEAD_REF", "feature/x"),
+            ("GITHUB_REF_NAME", "42/merge"),
+        ];
+        assert_eq!(branch(&pull).as_deref(), Some("feature/x"));
+        let azure = [("TF_BUILD", "True"), ("BUILD_SOURCEBRANCH", "refs/heads/release/1.4")];
+        assert_eq!(branch(&azure).as_deref(), Some("release/1.4"));
+        assert_eq!(branch(&[("GITHUB_REF_NAME", "main")]), None);
+    }
+
+    #[test]
+    fn build_numbers() {
+        let number = |vars: &[(&str, &str)]| {
+            build_number_from(|name| {
+                vars.iter()
+                    .find(|(key, _)| *key == name)
+                    .map(|(_, value)| value.to_string())
+            })
+        };
+        let github = [
+            ("GITHUB_ACTIONS", "true"),
+            ("GITHUB_RUN_NUMBER", "4821"),
+            ("BUILD_NUMBER", "7"),
+        ];
+        assert_eq!(number(&github).as_deref(), Some("4821"));
+        let gitlab = [("GITLAB_CI", "true"), ("CI_PIPELINE_IID", "93")];
+        assert_eq!(number(&gitlab).as_deref(), Some("93"));
+        assert_eq!(number(&[("BUILD_NUMBER", "7")]).as_deref(), Some("7"));
+        assert_eq!(number(&[("GITHUB_ACTIONS", "true")]), None);
+    }
+
+    #[test]
+    fn printing_commands() {
+        let outputs = [("version", "1.2.4".to_string()), ("note", "it's [50%]".to_string())];
+        assert_eq!(
+            System::Azure.commands(&outputs),
+            [
+                "##vso[task.setvariable variable=version;isOutput=true]1.2.4",
+                "##vso[task.setvariable variable=note;isOutput=true]it's [50%AZP25]",
+            ]
+        );
+        assert_eq!(
+            System::TeamCity.commands(&outputs),
+            [
+                "##teamcity[setParameter name='version' value='1.2.4']",
+                "##teamcity[setParameter name='note' value='it|'s |[50%|]']",
+            ]
+        );
+    }
+}
@@ -0,0 +1,306 @@
+//! The optional `.semver-bump.toml` config file, which sets defaults for a
+//! repository so they don't have to be repeated in every workflow file.
+//! Flags given on the command line always win over the config file.
+//!
+//! ```toml
+//! tag_prefix = "v"
+//! separator = "-"
+//! pad = 3
+//! continue_counter = true
+//! channels = ["alpha", "beta", "rc"]
+//! files = ["Cargo.toml", "VERSION", { file = "README.md", search = "semver-bump@{version}" }]
+//! format = "{prefix}{version}"
+//! max = "0"
+//! zerover = true
+//! sign = true
+//! labels = { "semver:major" = "major", "enhancement" = "minor", "bug" = "patch" }
+//! rules = { feat = "minor", fix = "patch", perf = "patch" }
+//! pre_bump = ["cargo test --quiet"]
+//! post_bump = ["cargo update --workspace --quiet"]
+//!
+//! [branches]
+//! main = "stable"
+//! develop = "beta"
+//! "release/*" = "rc"
+//! ```
+//!
+//! `rules` may also name a preset, as in `rules = "semantic-release"`.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+use serde::Deserialize;
+
+use crate::conventional::{Preset, Rules};
+use crate::error::Error;
+use crate::manifest::{self, Manifest, SearchReplace};
+
+/// The name of the config file we look for.
+pub const CONFIG_FILE: &str = ".semver-bump.toml";
+
+/// Defaults read from a config file. Every setting is optional.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// The prefix to put in front of the version when naming git tags.
+    pub tag_prefix: Option<String>,
+    /// The separator to put between a pre-release identifier and a newly added counter.
+    pub separator: Option<char>,
+    /// The width to zero-pad pre-release counters to, as in `alpha-001`.
+    pub pad: Option<u8>,
+    /// Whether pre-release counters carry over to a new base version in the same channel.
+    #[serde(default)]
+    pub continue_counter: bool,
+    /// The pre-release channels, in order from least to most mature.
+    pub channels: Option<Vec<String>>,
+    /// The template to print new versions with, as in `{major}.{minor}`.
+    pub format: Option<String>,
+    /// The highest version a bump may produce, as in `0` to stay on 0.x.
+    pub max: Option<String>,
+    /// Whether to treat 0.x versions the way cargo does, where a breaking change bumps the minor version.
+    #[serde(default)]
+    pub zerover: bool,
+    /// Whether to sign the tags and commits the tool makes.
+    #[serde(default)]
+    pub sign: bool,
+    /// The pull request labels `auto --from-pr-labels` looks for, and the bump each calls for.
+    #[serde(default)]
+    pub labels: BTreeMap<String, String>,
+    /// The rules `auto` judges commits by.
+    pub rules: Option<RulesSetting>,
+    /// The pre-release channel `auto` releases into from each branch, or `stable` for
+    /// a plain release. Names may contain a single `*` wildcard, as in `release/*`.
+    #[serde(default)]
+    pub branches: BTreeMap<String, String>,
+    /// Commands to run before a bump writes anything; if one fails, nothing is written.
+    #[serde(default)]
+    pub pre_bump: Vec<String>,
+    /// Commands to run once a bump has written its files, before anything is committed or
+    /// tagged; if one fails, the files are put back.
+    #[serde(default)]
+    pub post_bump: Vec<String>,
+    /// The files to read the version from and write the new version to, relative
+    /// to the directory holding the config file.
+    #[serde(default)]
+    pub files: Vec<Target>,
+    /// The directory the config file was found in.
+    #[serde(skip)]
+    pub root: PathBuf,
+}
+
+/// The config file's `rules` setting.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum RulesSetting {
+    /// The name of a preset, as in `semantic-release`.
+    Preset(String),
+    /// The bump each commit type calls for, with breaking changes marked as the
+    /// conventional commits rules mark them.
+    Types(BTreeMap<String, String>),
+}
+
+impl RulesSetting {
+    /// The rules this setting describes.
+    pub fn rules(&self) -> anyhow::Result<Rules> {
+        match self {
+            RulesSetting::Preset(name) => Ok(name.parse::<Preset>()?.rules()),
+            RulesSetting::Types(types) => {
+                let types = types
+                    .iter()
+                    .map(|(kind, level)| Ok((kind.to_ascii_lowercase(), level.parse()?)))
+                    .collect::<anyhow::Result<_>>()?;
+                Ok(Rules {
+                    types,
+                    ..Rules::default()
+                })
+            }
+        }
+    }
+}
+
+/// A file listed in the config file's `files` setting.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+#[serde(untagged, deny_unknown_fields)]
+pub enum Target {
+    /// A manifest or plain version file, given by its path.
+    Path(PathBuf),
+    /// Any other file with the version embedded in it, found by a search string
+    /// containing `{version}`, as in `{ file = "README.md", search = "semver-bump@{version}" }`.
+    /// Every match is rewritten with the replacement, which defaults to the search string.
+    Search {
+        /// The path to the file.
+        file: PathBuf,
+        /// The text that surrounds the version, with `{version}` standing in for it.
+        search: String,
+        /// What to write in place of each match, if not the search string.
+        replace: Option<String>,
+    },
+}
+
+impl Target {
+    /// Read the file, picking a manifest handler by file name for a plain path.
+    pub fn open(&self) -> anyhow::Result<Box<dyn Manifest>> {
+        match self {
+            Target::Path(path) => manifest::open_any(path),
+            Target::Search { file, search, replace } => {
+                Ok(Box::new(SearchReplace::open(file, search, replace.as_deref())?))
+            }
+        }
+    }
+}
+
+impl Config {
+    /// Read the config file at the given path.
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path).with_context(|| format!("unable to read {}", path.display()))?;
+        let mut config: Config =
+            toml::from_str(&text).map_err(|e| Error::config(format!("{}: {e}", path.display())))?;
+        config.root = match path.parent() {
+            Some(p) if !p.as_os_str().is_empty() => p.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+        Ok(config)
+    }
+
+    /// Look for a config file in the given directory and its parents, stopping at the
+    /// root of the git repository. Returns the default config if there isn't one.
+    pub fn discover(start: impl AsRef<Path>) -> anyhow::Result<Self> {
+        for dir in start.as_ref().ancestors() {
+            let candidate = dir.join(CONFIG_FILE);
+            if candidate.is_file() {
+                return Self::load(candidate);
+            }
+            if dir.join(".git").exists() {
+                break;
+            }
+        }
+        Ok(Self::default())
+    }
+
+    /// The pre-release channel the `branches` table gives this branch, or `None` for a
+    /// stable release or a branch the table doesn't mention. An exact name beats a
+    /// pattern, and a longer pattern beats a shorter one.
+    pub fn channel(&self, branch: &str) -> Option<&str> {
+        let matching = |pattern: &str| match pattern.split_once('*') {
+            None => pattern == branch,
+            Some((prefix, suffix)) => {
+                branch.len() >= prefix.len() + suffix.len() && branch.starts_with(prefix) && branch.ends_with(suffix)
+            }
+        };
+        let (_, channel) = self
+            .branches
+            .iter()
+            .filter(|(pattern, _)| matching(pattern))
+            .max_by_key(|(pattern, _)| (!pattern.contains('*'), pattern.len()))?;
+        Some(channel.as_str()).filter(|channel| *channel != "stable")
+    }
+
+    /// The files to update, with paths resolved relative to the config file.
+    pub fn targets(&self) -> Vec<Target> {
+        self.files
+            .iter()
+            .map(|target| match target {
+                Target::Path(path) => Target::Path(self.root.join(path)),
+                Target::Search { file, search, replace } => Target::Search {
+                    file: self.root.join(file),
+                    search: search.clone(),
+                    replace: replace.clone(),
+                },
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn loading_config() {
+        let dir = tempfile::tempdir().expect("we expected to be able to make a temp dir");
+        let path = dir.path().join(CONFIG_FILE);
+        fs::write(
+            &path,
+            "tag_prefix = \"release-\"\nseparator = \"-\"\nchannels = [\"dev\", \"rc\"]\nfiles = [\"VERSION\", { file = \"README.md\", search = \"v{version}\" }]\n",
+        )
+        .expect("we expected to be able to write test data");
+        let config = Config::load(&path).expect("we expected the config to load");
+        assert_eq!(config.tag_prefix.as_deref(), Some("release-"));
+        assert_eq!(config.separator, Some('-'));
+        assert_eq!(config.channels, Some(vec!["dev".to_string(), "rc".to_string()]));
+        assert_eq!(
+            config.targets(),
+            vec![
+                Target::Path(dir.path().join("VERSION")),
+                Target::Search {
+                    file: dir.path().join("README.md"),
+                    search: "v{version}".to_string(),
+                    replace: None,
+                }
+            ]
+        );
+
+        fs::write(&path, "tag_prefx = \"v\"\n").expect("we expected to be able to write test data");
+        Config::load(&path).expect_err("we expected a misspelled setting to be an error");
+        fs::write(&path, "files = [{ file = \"README.md\", serach = \"{version}\" }]\n")
+            .expect("we expected to be able to write test data");
+        Config::load(&path).expect_err("we expected a misspelled target setting to be an error");
+
+        fs::write(&path, "rules = { feat = \"minor\", perf = \"patch\" }\n")
+            .expect("we expected to be able to write test data");
+        let config = Config::load(&path).expect("we expected the config to load");
+        let rules = config
+            .rules
+            .expect("we expected rules")
+            .rules()
+            .expect("we expected valid rules");
+        assert_eq!(rules.classify("perf: faster"), Some(crate::Level::Patch));
+        assert_eq!(rules.classify("fix: bug"), None);
+        fs::write(&path, "rules = \"semantic-release\"\n").expect("we expected to be able to write test data");
+        let config = Config::load(&path).expect("we expected the config to load");
+        assert_eq!(
+            config
+                .rules
+                .expect("we expected rules")
+                .rules()
+                .expect("we expected valid rules"),
+            Rules::semantic_release()
+        );
+    }
+
+    #[test]
+    fn branch_channels() {
+        let config: Config = toml::from_str(
+            "[branches]\nmain = \"stable\"\ndevelop = \"beta\"\n\"release/*\" = \"rc\"\n\"release/*-lts\" = \"stable\"\n\"release/next\" = \"alpha\"\n",
+        )
+        .expect("we expected the config to parse");
+        assert_eq!(config.channel("main"), None);
+        assert_eq!(config.channel("develop"), Some("beta"));
+        assert_eq!(config.channel("release/1.4"), Some("rc"));
+        assert_eq!(config.channel("release/1.4-lts"), None);
+        assert_eq!(config.channel("release/next"), Some("alpha"));
+        assert_eq!(config.channel("feature/x"), None);
+    }
+
+    #[test]
+    fn discovering_config() {
+        let dir = tempfile::tempdir().expect("we expected to be able to make a temp dir");
+        let nested = dir.path().join("repo").join("sub");
+        fs::create_dir_all(nested.join(".git")).expect("we expected to be able to make directories");
+        // A config above the repository root must not be picked up.
+        fs::write(dir.path().join(CONFIG_FILE), "tag_prefix = \"outside\"\n")
+            .expect("we expected to be able to write test data");
+        let config = Config::discover(&nested).expect("we expected discovery to work");
+        assert_eq!(config, Config::default());
+
+        fs::write(nested.join(CONFIG_FILE), "tag_prefix = \"inside\"\n")
+            .expect("we expected to be able to write test data");
+        let config = Config::discover(&nested).expect("we expected discovery to work");
+        assert_eq!(config.tag_prefix.as_deref(), Some("inside"));
+        assert_eq!(config.root, nested);
+    }
+}
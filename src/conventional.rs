@@ -0,0 +1,250 @@
+//! Deciding how big a bump is called for by reading commit messages written
+//! in the [conventional commits](https://www.conventionalcommits.org/) style.
+//! A `feat` calls for a minor bump, a `fix` for a patch bump, and anything
+//! flagged as breaking, with a `!` or a `BREAKING CHANGE` footer, for a major bump.
+//! Other [`Rules`] can say otherwise, such as the ones semantic-release follows.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use anyhow::anyhow;
+
+use crate::Level;
+
+/// The parts of a conventional commit header we care about.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Header {
+    /// The commit type, such as `feat` or `fix`, lowercased.
+    pub kind: String,
+    /// The optional scope in parentheses after the type.
+    pub scope: Option<String>,
+    /// Whether the header was marked breaking with a `!`.
+    pub breaking: bool,
+}
+
+/// Parse the first line of a commit message as a conventional commit header.
+/// Returns `None` if the message doesn't follow the convention.
+pub fn parse_header(message: &str) -> Option<Header> {
+    let first = message.lines().next()?.trim();
+    let (prefix, _description) = first.split_once(':')?;
+    let (prefix, breaking) = match prefix.strip_suffix('!') {
+        Some(p) => (p, true),
+        None => (prefix, false),
+    };
+    let (kind, scope) = match prefix.split_once('(') {
+        Some((kind, rest)) => (kind, Some(rest.strip_suffix(')')?.to_string())),
+        None => (prefix, None),
+    };
+    if kind.is_empty() || !kind.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return None;
+    }
+    Some(Header {
+        kind: kind.to_ascii_lowercase(),
+        scope,
+        breaking,
+    })
+}
+
+/// Check the message body for a `BREAKING CHANGE:` footer.
+pub(crate) fn has_breaking_footer(message: &str) -> bool {
+    has_footer(message, &["BREAKING CHANGE", "BREAKING-CHANGE"])
+}
+
+/// Check the message body for a footer with one of these keys.
+fn has_footer(message: &str, keys: &[&str]) -> bool {
+    message.lines().skip(1).any(|line| {
+        keys.iter()
+            .any(|key| line.strip_prefix(key).is_some_and(|rest| rest.starts_with(':')))
+    })
+}
+
+/// The rules a set of commit messages is judged by.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rules {
+    /// The bump each commit type calls for. Other types call for none. A commit that
+    /// git made with `git revert`, whose subject starts `Revert "`, has the type `revert`.
+    pub types: BTreeMap<String, Level>,
+    /// The footer keys that mark a breaking change.
+    pub breaking_footers: Vec<String>,
+    /// Whether a `!` after the type marks a breaking change. If not, a header with one
+    /// doesn't follow the convention at all.
+    pub bang: bool,
+    /// Markers that leave a commit out when its message contains one.
+    pub skip_markers: Vec<String>,
+}
+
+impl Default for Rules {
+    /// The conventional commits rules: `feat` and `fix`, with breaking changes marked
+    /// by `!` or a `BREAKING CHANGE` footer.
+    fn default() -> Self {
+        Self {
+            types: BTreeMap::from([("feat".to_string(), Level::Minor), ("fix".to_string(), Level::Patch)]),
+            breaking_footers: vec!["BREAKING CHANGE".to_string(), "BREAKING-CHANGE".to_string()],
+            bang: true,
+            skip_markers: Vec::new(),
+        }
+    }
+}
+
+impl Rules {
+    /// The decisions semantic-release makes out of the box, with its Angular preset: a
+    /// `perf` or a revert calls for a patch bump as well, breaking changes are marked by
+    /// `BREAKING CHANGE` or `BREAKING CHANGES` footers but not by `!`, and commits that
+    /// say `[skip release]` or `[release skip]` don't count.
+    pub fn semantic_release() -> Self {
+        let types = [
+            ("feat", Level::Minor),
+            ("fix", Level::Patch),
+            ("perf", Level::Patch),
+            ("revert", Level::Patch),
+        ];
+        Self {
+            types: types
+                .into_iter()
+                .map(|(kind, level)| (kind.to_string(), level))
+                .collect(),
+            breaking_footers: vec!["BREAKING CHANGE".to_string(), "BREAKING CHANGES".to_string()],
+            bang: false,
+            skip_markers: vec!["[skip release]".to_string(), "[release skip]".to_string()],
+        }
+    }
+
+    /// Decide what level of bump a single commit message calls for, if any.
+    pub fn classify(&self, message: &str) -> Option<Level> {
+        if self.skip_markers.iter().any(|marker| message.contains(marker.as_str())) {
+            return None;
+        }
+        let footers: Vec<&str> = self.breaking_footers.iter().map(String::as_str).collect();
+        if has_footer(message, &footers) {
+            return Some(Level::Major);
+        }
+        let kind = if message.starts_with("Revert \"") {
+            "revert".to_string()
+        } else {
+            let header = parse_header(message).filter(|h| self.bang || !h.breaking)?;
+            if header.breaking {
+                return Some(Level::Major);
+            }
+            header.kind
+        };
+        self.types.get(&kind).copied()
+    }
+
+    /// Decide what level of bump a collection of commit messages calls for:
+    /// the largest bump any one of them calls for.
+    pub fn analyze<S: AsRef<str>>(&self, messages: &[S]) -> Option<Level> {
+        messages.iter().filter_map(|m| self.classify(m.as_ref())).max()
+    }
+}
+
+/// The named sets of rules `--rules` can pick.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Preset {
+    /// The conventional commits rules.
+    #[default]
+    Conventional,
+    /// The rules semantic-release follows by default.
+    SemanticRelease,
+}
+
+impl Preset {
+    /// The rules this preset stands for.
+    pub fn rules(self) -> Rules {
+        match self {
+            Preset::Conventional => Rules::default(),
+            Preset::SemanticRelease => Rules::semantic_release(),
+        }
+    }
+}
+
+impl Display for Preset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Preset::Conventional => "conventional",
+            Preset::SemanticRelease => "semantic-release",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl FromStr for Preset {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "conventional" => Ok(Preset::Conventional),
+            "semantic-release" => Ok(Preset::SemanticRelease),
+            _ => Err(anyhow!(
+                "`{input}` is not a set of rules; expected conventional or semantic-release"
+            )),
+        }
+    }
+}
+
+/// Decide what level of bump a single commit message calls for, if any, by the
+/// conventional commits rules.
+pub fn classify(message: &str) -> Option<Level> {
+    Rules::default().classify(message)
+}
+
+/// Decide what level of bump a collection of commit messages calls for by the
+/// conventional commits rules: the largest bump any one of them calls for.
+pub fn analyze<S: AsRef<str>>(messages: &[S]) -> Option<Level> {
+    Rules::default().analyze(messages)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn header_parsing() {
+        let header = parse_header("feat(parser)!: add a thing").expect("we expected a valid header");
+        assert_eq!(header.kind, "feat");
+        assert_eq!(header.scope.as_deref(), Some("parser"));
+        assert!(header.breaking);
+        let header = parse_header("Fix: a thing").expect("we expected a valid header");
+        assert_eq!(header.kind, "fix");
+        assert!(header.scope.is_none());
+        assert!(parse_header("Merge branch 'main'").is_none());
+        assert!(parse_header("see http://example.com").is_none());
+        assert!(parse_header("feat(oops: no close paren").is_none());
+    }
+
+    #[test]
+    fn commit_classification() {
+        assert_eq!(classify("feat: new"), Some(Level::Minor));
+        assert_eq!(classify("fix(cli): bug"), Some(Level::Patch));
+        assert_eq!(classify("refactor!: drop api"), Some(Level::Major));
+        assert_eq!(classify("fix: x\n\nBREAKING CHANGE: removes y"), Some(Level::Major));
+        assert_eq!(classify("chore: deps"), None);
+        assert_eq!(classify("whatever"), None);
+    }
+
+    #[test]
+    fn commit_analysis() {
+        assert_eq!(analyze(&["chore: a", "fix: b", "feat: c"]), Some(Level::Minor));
+        assert_eq!(analyze(&["fix: b", "feat!: c"]), Some(Level::Major));
+        assert_eq!(analyze(&["docs: a", "chore: b"]), None);
+        assert_eq!(analyze::<&str>(&[]), None);
+    }
+
+    #[test]
+    fn semantic_release_rules() {
+        let rules = Rules::semantic_release();
+        assert_eq!(rules.classify("perf: faster"), Some(Level::Patch));
+        assert_eq!(
+            rules.classify("Revert \"feat: new\"\n\nThis reverts commit 1a2b3c4."),
+            Some(Level::Patch)
+        );
+        assert_eq!(
+            rules.classify("docs: x\n\nBREAKING CHANGES: the docs moved"),
+            Some(Level::Major)
+        );
+        assert_eq!(rules.classify("feat!: drop it"), None);
+        assert_eq!(rules.classify("feat: new [skip release]"), None);
+        assert_eq!(Rules::default().classify("perf: faster"), None);
+        assert_eq!(Rules::default().classify("feat!: drop it"), Some(Level::Major));
+    }
+}
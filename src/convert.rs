@@ -0,0 +1,140 @@
+//! Spelling a semver version the way another packaging ecosystem does, for
+//! pipelines that publish one release to several of them:
+//!
+//! - `pep440`: Python's PEP 440, as in `1.2.3rc1`; see [`crate::pep440::from_semver`].
+//! - `deb`: a Debian upstream version, as in `1.2.3~rc1`. A `~` sorts before
+//!   anything, even the end of the version, so pre-releases come before their
+//!   release just as they do in semver. Build metadata follows a `+`, which sorts
+//!   after the release.
+//! - `rpm`: an RPM version, as in `1.2.3~rc1`, with the same `~` for pre-releases.
+//!   Build metadata follows a `^`, which sorts after the release but before
+//!   anything that comes after it.
+//!
+//! In the Debian and RPM spellings, a number is joined to the word before it, so
+//! `rc.1` becomes `rc1`, and any `-` inside an identifier becomes a `.`, since
+//! neither format allows one there. Both compare runs of letters and runs of digits
+//! piece by piece, so the order matches semver's.
+
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use anyhow::anyhow;
+use semver::Version;
+
+/// The ecosystems a version can be converted for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Ecosystem {
+    /// Python packages.
+    Pep440,
+    /// Debian packages.
+    Deb,
+    /// RPM packages.
+    Rpm,
+}
+
+impl Display for Ecosystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Ecosystem::Pep440 => "pep440",
+            Ecosystem::Deb => "deb",
+            Ecosystem::Rpm => "rpm",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl FromStr for Ecosystem {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "pep440" | "python" => Ok(Ecosystem::Pep440),
+            "deb" | "debian" => Ok(Ecosystem::Deb),
+            "rpm" => Ok(Ecosystem::Rpm),
+            _ => Err(anyhow!("{input} is not an ecosystem; expected pep440, deb, or rpm")),
+        }
+    }
+}
+
+/// The version as the ecosystem spells it.
+pub fn convert(version: &Version, ecosystem: Ecosystem) -> anyhow::Result<String> {
+    let build_separator = match ecosystem {
+        Ecosystem::Pep440 => return crate::pep440::from_semver(version),
+        Ecosystem::Deb => '+',
+        Ecosystem::Rpm => '^',
+    };
+    let mut output = format!("{}.{}.{}", version.major, version.minor, version.patch);
+    if !version.pre.is_empty() {
+        output.push('~');
+        output.push_str(&packed(version.pre.as_str()));
+    }
+    if !version.build.is_empty() {
+        output.push(build_separator);
+        output.push_str(&packed(version.build.as_str()));
+    }
+    Ok(output)
+}
+
+/// Join each number to the word before it and turn hyphens into dots, as in
+/// `rc.1` to `rc1` and `alpha-001` to `alpha.001`.
+fn packed(identifiers: &str) -> String {
+    let parts: Vec<String> = identifiers.split('.').map(|part| part.replace('-', ".")).collect();
+    let mut output = String::new();
+    for (i, part) in parts.iter().enumerate() {
+        let joins = i > 0
+            && part.starts_with(|c: char| c.is_ascii_digit())
+            && parts[i - 1].ends_with(|c: char| c.is_ascii_alphabetic());
+        if i > 0 && !joins {
+            output.push('.');
+        }
+        output.push_str(part);
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn converted(version: &str, to: &str) -> anyhow::Result<String> {
+        let version = Version::parse(version).expect("test data must be valid semver");
+        convert(&version, to.parse().expect("we expected a known ecosystem"))
+    }
+
+    #[test]
+    fn converting_versions() {
+        let cases = [
+            ("1.2.3", "1.2.3", "1.2.3", "1.2.3"),
+            ("1.2.3-rc.1", "1.2.3rc1", "1.2.3~rc1", "1.2.3~rc1"),
+            (
+                "1.2.3-beta.1.dev.2",
+                "1.2.3b1.dev2",
+                "1.2.3~beta1.dev2",
+                "1.2.3~beta1.dev2",
+            ),
+            ("1.2.3+build.5", "1.2.3+build.5", "1.2.3+build5", "1.2.3^build5"),
+            ("1.2.3-alpha-001", "1.2.3a1", "1.2.3~alpha.001", "1.2.3~alpha.001"),
+            ("1.2.3-1.2", "", "1.2.3~1.2", "1.2.3~1.2"),
+        ];
+        for (input, pep440, deb, rpm) in cases {
+            assert_eq!(
+                converted(input, "pep440").unwrap_or_default(),
+                pep440,
+                "converting {input} for pep440"
+            );
+            assert_eq!(
+                converted(input, "deb").expect("we expected a conversion"),
+                deb,
+                "converting {input} for deb"
+            );
+            assert_eq!(
+                converted(input, "rpm").expect("we expected a conversion"),
+                rpm,
+                "converting {input} for rpm"
+            );
+        }
+        "npm"
+            .parse::<Ecosystem>()
+            .expect_err("we expected an unknown ecosystem to be an error");
+    }
+}
@@ -0,0 +1,158 @@
+//! Reading the output of `git describe --tags`, such as `v1.2.3-14-g1a2b3c4-dirty`,
+//! which names the nearest tag, how many commits HEAD is past it, the abbreviated
+//! hash of HEAD, and whether the working tree has uncommitted changes.
+
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use anyhow::anyhow;
+use semver::{BuildMetadata, Prerelease, Version};
+
+/// Where the commit distance goes when a description becomes a version. The hash
+/// and `dirty` marker always go in the build metadata, since they don't order.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Style {
+    /// Everything goes in the build metadata, as in `1.2.3+14.g1a2b3c4`, so the
+    /// version has the same precedence as the tag.
+    #[default]
+    Build,
+    /// The distance is appended to the pre-release, as in `1.3.0-rc.1.14+g1a2b3c4`,
+    /// so later commits sort higher. A release tag gets a pre-release of just the
+    /// distance, which sorts before the release itself.
+    Pre,
+}
+
+impl Display for Style {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Style::Build => "build",
+            Style::Pre => "pre",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl FromStr for Style {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "build" => Ok(Style::Build),
+            "pre" => Ok(Style::Pre),
+            _ => Err(anyhow!("`{input}` is not a describe style; expected build or pre")),
+        }
+    }
+}
+
+/// The parts of a `git describe` string.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Description<'a> {
+    /// The tag, as in `v1.2.3`.
+    pub tag: &'a str,
+    /// How many commits HEAD is past the tag; 0 if HEAD is the tagged commit.
+    pub distance: u64,
+    /// The abbreviated hash of HEAD without its `g`, if HEAD isn't the tagged commit.
+    pub sha: Option<&'a str>,
+    /// Whether the working tree had uncommitted changes.
+    pub dirty: bool,
+}
+
+impl<'a> Description<'a> {
+    /// Split a `git describe` string into its parts. A bare tag, with or without
+    /// `-dirty`, is a description of the tagged commit itself.
+    pub fn parse(input: &'a str) -> Self {
+        let input = input.trim();
+        let (rest, dirty) = match input.strip_suffix("-dirty") {
+            Some(rest) => (rest, true),
+            None => (input, false),
+        };
+        let described = rest.rsplit_once("-g").and_then(|(front, sha)| {
+            let (tag, distance) = front.rsplit_once('-')?;
+            let is_sha = !sha.is_empty() && sha.chars().all(|c| c.is_ascii_hexdigit());
+            let distance = distance.parse().ok().filter(|_| is_sha && !tag.is_empty())?;
+            Some((tag, distance, sha))
+        });
+        match described {
+            Some((tag, distance, sha)) => Self {
+                tag,
+                distance,
+                sha: Some(sha),
+                dirty,
+            },
+            None => Self {
+                tag: rest,
+                distance: 0,
+                sha: None,
+                dirty,
+            },
+        }
+    }
+
+    /// Record the distance, hash, and dirty marker on the tag's version.
+    pub fn apply(&self, version: &mut Version, style: Style) -> anyhow::Result<()> {
+        let mut build: Vec<String> = Vec::new();
+        if !version.build.is_empty() {
+            build.push(version.build.to_string());
+        }
+        if self.distance > 0 {
+            match style {
+                Style::Build => build.push(self.distance.to_string()),
+                Style::Pre if version.pre.is_empty() => version.pre = Prerelease::new(&self.distance.to_string())?,
+                Style::Pre => version.pre = Prerelease::new(&format!("{}.{}", version.pre, self.distance))?,
+            }
+        }
+        build.extend(self.sha.map(|sha| format!("g{sha}")));
+        if self.dirty {
+            build.push("dirty".to_string());
+        }
+        version.build = BuildMetadata::new(&build.join("."))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsing_descriptions() {
+        let described = Description::parse("v1.2.3-rc.1-14-g1a2b3c4-dirty");
+        assert_eq!(
+            described,
+            Description {
+                tag: "v1.2.3-rc.1",
+                distance: 14,
+                sha: Some("1a2b3c4"),
+                dirty: true
+            }
+        );
+        let exact = Description::parse("v1.2.3-dirty\n");
+        assert_eq!(
+            (exact.tag, exact.distance, exact.sha, exact.dirty),
+            ("v1.2.3", 0, None, true)
+        );
+        // A pre-release that merely looks like a hash isn't mistaken for one.
+        assert_eq!(Description::parse("1.2.3-gamma").tag, "1.2.3-gamma");
+    }
+
+    #[test]
+    fn applying_descriptions() {
+        let described = Description::parse("v1.2.3-rc.1-14-g1a2b3c4-dirty");
+        let cases = [
+            (Style::Build, "1.2.3-rc.1+14.g1a2b3c4.dirty"),
+            (Style::Pre, "1.2.3-rc.1.14+g1a2b3c4.dirty"),
+        ];
+        for (style, expected) in cases {
+            let mut version = Version::parse("1.2.3-rc.1").expect("test data must be valid semver");
+            described
+                .apply(&mut version, style)
+                .expect("we expected the description to apply");
+            assert_eq!(version.to_string(), expected);
+        }
+        let mut version = Version::new(1, 2, 3);
+        Description::parse("1.2.3-2-gabcdef0")
+            .apply(&mut version, Style::Pre)
+            .expect("we expected the description to apply");
+        assert_eq!(version.to_string(), "1.2.3-2+gabcdef0");
+    }
+}
@@ -0,0 +1,63 @@
+//! The floating tags container images are conventionally published under, so a
+//! release of `1.2.3` is also pulled as `1.2`, `1`, and `latest`. The rules follow
+//! docker/metadata-action's: a `0` major tag is never made, since 0.x promises
+//! nothing from one minor to the next; a pre-release gets no floating version
+//! tags, only its channel, as in `rc`; and the `+` of build metadata, which tags
+//! can't hold, becomes a `-`.
+
+use semver::Version;
+
+/// The tags to publish an image of this version under, most specific first.
+/// `prefix` goes in front of each version tag, and `latest` says whether a
+/// release should move the `latest` tag.
+pub fn tags(version: &Version, prefix: &str, latest: bool) -> Vec<String> {
+    let mut tags = vec![format!("{prefix}{version}").replace('+', "-")];
+    if version.pre.is_empty() {
+        tags.push(format!("{prefix}{}.{}.{}", version.major, version.minor, version.patch));
+        tags.push(format!("{prefix}{}.{}", version.major, version.minor));
+        if version.major > 0 {
+            tags.push(format!("{prefix}{}", version.major));
+        }
+        if latest {
+            tags.push("latest".to_string());
+        }
+    } else {
+        let channel = crate::strip_counter(crate::undated(version.pre.as_str()));
+        if !channel.is_empty() {
+            tags.push(channel.to_string());
+        }
+    }
+    tags.dedup();
+    tags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tagged(version: &str, prefix: &str, latest: bool) -> Vec<String> {
+        tags(
+            &Version::parse(version).expect("test data must be valid semver"),
+            prefix,
+            latest,
+        )
+    }
+
+    #[test]
+    fn floating_tags() {
+        assert_eq!(tagged("1.2.3", "", true), ["1.2.3", "1.2", "1", "latest"]);
+        assert_eq!(tagged("1.2.3", "v", false), ["v1.2.3", "v1.2", "v1"]);
+        assert_eq!(tagged("0.4.1", "", true), ["0.4.1", "0.4", "latest"]);
+        assert_eq!(
+            tagged("1.2.3+build.7", "", true),
+            ["1.2.3-build.7", "1.2.3", "1.2", "1", "latest"]
+        );
+        assert_eq!(tagged("2.0.0-rc.2", "", true), ["2.0.0-rc.2", "rc"]);
+        assert_eq!(
+            tagged("2.0.0-nightly.20240519.1", "", true),
+            ["2.0.0-nightly.20240519.1", "nightly"]
+        );
+        assert_eq!(tagged("2.0.0-beta12", "", true), ["2.0.0-beta12", "beta"]);
+        assert_eq!(tagged("2.0.0-7", "", true), ["2.0.0-7"]);
+    }
+}
@@ -0,0 +1,90 @@
+//! Epochs, as in the `1!` of `1!2.3.4`. Python's PEP 440 and Debian both let a
+//! package start its version numbers over by bumping an epoch in front of them:
+//! any version in a later epoch comes after every version in an earlier one, so
+//! `1!1.0.0` is newer than `2024.5.0`. A version without one is in epoch 0.
+//!
+//! Semver has no epochs, so a bump carries the epoch along with the prefix.
+
+use std::cmp::Ordering;
+
+use anyhow::anyhow;
+use semver::Version;
+
+use crate::error::Error;
+
+/// Split a leading epoch off the input. Returns the epoch with its `!`, or an empty
+/// string if there isn't one, and the rest.
+pub fn split(input: &str) -> anyhow::Result<(&str, &str)> {
+    let trimmed = input.trim();
+    let Some((number, _)) = trimmed.split_once('!') else {
+        return Ok(("", trimmed));
+    };
+    if number.is_empty() || !number.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(Error::invalid(
+            trimmed,
+            format!("`{number}!` is not an epoch; expected a number, as in `1!`"),
+        )
+        .into());
+    }
+    number
+        .parse::<u64>()
+        .map_err(|_| Error::invalid(trimmed, format!("the epoch `{number}` is too large")))?;
+    Ok(trimmed.split_at(number.len() + 1))
+}
+
+/// The number of an epoch returned by [`split`], which is 0 if there wasn't one.
+pub fn number(epoch: &str) -> u64 {
+    epoch.trim_end_matches('!').parse().unwrap_or(0)
+}
+
+/// The epoch after this one, with its `!`.
+pub fn next(epoch: &str) -> anyhow::Result<String> {
+    let next = number(epoch)
+        .checked_add(1)
+        .ok_or_else(|| anyhow!("the epoch {epoch} is too large to bump"))?;
+    Ok(format!("{next}!"))
+}
+
+/// Parse a version that may have an epoch and a leading `v`, dropping the prefix.
+pub fn parse(input: &str) -> anyhow::Result<(u64, Version)> {
+    let (epoch, rest) = split(input)?;
+    let (_, version) = crate::parse_prefixed(rest)?;
+    Ok((number(epoch), version))
+}
+
+/// Compare two versions by epoch, then by semver precedence.
+pub fn cmp_precedence(left: &(u64, Version), right: &(u64, Version)) -> Ordering {
+    left.0.cmp(&right.0).then_with(|| left.1.cmp_precedence(&right.1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn epochs() {
+        assert_eq!(split("1!2.3.4").expect("we expected an epoch"), ("1!", "2.3.4"));
+        assert_eq!(split(" 12!v2.3.4\n").expect("we expected an epoch"), ("12!", "v2.3.4"));
+        assert_eq!(split("2.3.4").expect("we expected no epoch"), ("", "2.3.4"));
+        split("x!2.3.4").expect_err("we expected a non-numeric epoch to be an error");
+        split("!2.3.4").expect_err("we expected an empty epoch to be an error");
+        split("99999999999999999999!2.3.4").expect_err("we expected a huge epoch to be an error");
+
+        assert_eq!(number(""), 0);
+        assert_eq!(number("3!"), 3);
+        assert_eq!(next("").expect("we expected the epoch to bump"), "1!");
+        assert_eq!(next("3!").expect("we expected the epoch to bump"), "4!");
+
+        let parsed = |input: &str| parse(input).expect("we expected a version with an epoch");
+        assert_eq!(parsed("1!v1.0.0"), (1, Version::new(1, 0, 0)));
+        assert_eq!(cmp_precedence(&parsed("2024.5.0"), &parsed("1!1.0.0")), Ordering::Less);
+        assert_eq!(
+            cmp_precedence(&parsed("0!1.2.3+build"), &parsed("1.2.3")),
+            Ordering::Equal
+        );
+        assert_eq!(
+            cmp_precedence(&parsed("1!1.3.0"), &parsed("1!1.2.9")),
+            Ordering::Greater
+        );
+    }
+}
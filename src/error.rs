@@ -0,0 +1,222 @@
+//! Telling failures apart, for scripts that have to react differently to a version
+//! that doesn't parse and to a network that's down. Errors are still anyhow errors
+//! everywhere; where we know what kind of failure something is when it happens, we
+//! say so with an [`Error`], and [`classify`] looks through an error's causes for
+//! the first one whose kind it can tell.
+//!
+//! Each kind ends the program with its own exit status, from [`Kind::exit_code`]:
+//!
+//! - 0: success
+//! - 1: a check that says no, such as `matches` or `lint-tags`, or an `other` failure
+//! - 2: a `parse` failure, or command-line arguments clap couldn't make sense of
+//! - 3: a `constraint` failure
+//! - 4: an `io` failure
+//! - 5: a `network` failure
+//! - 6: a `git` failure
+//! - 7: a `config` failure
+//!
+//! `compare` and `diff` report their answers with 10 and 11 and with 20 through 24,
+//! out of the way of all of these.
+
+use std::fmt::{self, Display};
+
+/// The classes of failure.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    /// Input that isn't a version or requirement, or isn't one we can read.
+    Parse,
+    /// A version that breaks a rule it was given, such as `--require-greater`, `--max`,
+    /// or a tag that must not already exist.
+    Constraint,
+    /// Reading or writing a file, or running another program.
+    Io,
+    /// Talking to a registry or forge API.
+    Network,
+    /// A git command that failed, such as outside a repository.
+    Git,
+    /// A config file that isn't valid.
+    Config,
+    /// Anything else.
+    Other,
+}
+
+impl Kind {
+    /// The exit status a failure of this kind ends the program with.
+    pub fn exit_code(&self) -> u8 {
+        match self {
+            Kind::Parse => 2,
+            Kind::Constraint => 3,
+            Kind::Io => 4,
+            Kind::Network => 5,
+            Kind::Git => 6,
+            Kind::Config => 7,
+            Kind::Other => 1,
+        }
+    }
+}
+
+impl Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Kind::Parse => "parse",
+            Kind::Constraint => "constraint",
+            Kind::Io => "io",
+            Kind::Network => "network",
+            Kind::Git => "git",
+            Kind::Config => "config",
+            Kind::Other => "other",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// A failure of a known kind, with the input that caused it and a hint about what to
+/// do instead, where we have them.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Error {
+    pub kind: Kind,
+    pub message: String,
+    pub input: Option<String>,
+    pub hint: Option<String>,
+}
+
+impl Error {
+    fn new(kind: Kind, message: impl Display) -> Self {
+        Self {
+            kind,
+            message: message.to_string(),
+            input: None,
+            hint: None,
+        }
+    }
+
+    /// Input that isn't a valid version. The hint is the detailed reason from
+    /// [`crate::validate::validate`] for the input after any leading `v`, when it
+    /// finds one.
+    pub fn parse(input: &str, message: impl Display) -> Self {
+        let unprefixed = input.strip_prefix(['v', 'V']).unwrap_or(input);
+        Self {
+            hint: crate::validate::validate(unprefixed)
+                .err()
+                .map(|problem| problem.to_string()),
+            ..Self::invalid(input, message)
+        }
+    }
+
+    /// Input that can't be read, where semver's rules aren't the ones that matter, as
+    /// with a calendar version. There's no hint.
+    pub fn invalid(input: &str, message: impl Display) -> Self {
+        Self {
+            input: Some(input.to_string()),
+            ..Self::new(Kind::Parse, message)
+        }
+    }
+
+    /// A version that breaks a rule it was given.
+    pub fn constraint(message: impl Display) -> Self {
+        Self::new(Kind::Constraint, message)
+    }
+
+    /// A file that couldn't be read or written, or another program that failed.
+    pub fn io(message: impl Display) -> Self {
+        Self::new(Kind::Io, message)
+    }
+
+    /// A request to an API that never got an answer, or that it refused.
+    pub fn network(message: impl Display) -> Self {
+        Self::new(Kind::Network, message)
+    }
+
+    /// A git command that failed.
+    pub fn git(message: impl Display) -> Self {
+        Self::new(Kind::Git, message)
+    }
+
+    /// A config file that isn't valid.
+    pub fn config(message: impl Display) -> Self {
+        Self::new(Kind::Config, message)
+    }
+
+    /// Add a hint about what to do instead.
+    pub fn with_hint(self, hint: impl Display) -> Self {
+        Self {
+            hint: Some(hint.to_string()),
+            ..self
+        }
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for Error {}
+
+/// Work out what kind of failure an error is from the first of its causes that
+/// tells us. The message is the whole chain of causes, as in `outer: inner`.
+pub fn classify(error: &anyhow::Error) -> Error {
+    let message = format!("{error:#}");
+    for cause in error.chain() {
+        if let Some(known) = cause.downcast_ref::<Error>() {
+            return Error {
+                message,
+                ..known.clone()
+            };
+        }
+        let kind = if cause.is::<semver::Error>() || cause.is::<crate::validate::Problem>() {
+            Kind::Parse
+        } else if cause.is::<std::io::Error>() {
+            Kind::Io
+        } else {
+            continue;
+        };
+        return Error::new(kind, message);
+    }
+    Error::new(Kind::Other, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn classifying() {
+        let parse = crate::parse_prefixed("v1.02.3").expect_err("we expected a leading zero to be an error");
+        let classified = classify(&parse);
+        assert_eq!(classified.kind, Kind::Parse);
+        assert_eq!(classified.input.as_deref(), Some("v1.02.3"));
+        assert_eq!(
+            classified.hint.as_deref(),
+            Some("the number `02` has a leading zero, which semver forbids")
+        );
+
+        let io = anyhow::Error::from(std::fs::read("/no/such/file").expect_err("we expected no such file"))
+            .context("unable to read the manifest");
+        let classified = classify(&io);
+        assert_eq!(classified.kind, Kind::Io);
+        assert!(classified.message.starts_with("unable to read the manifest: "));
+
+        let constraint = anyhow::Error::from(Error::constraint("too big").with_hint("lower it")).context("bumping");
+        let classified = classify(&constraint);
+        assert_eq!(classified.kind, Kind::Constraint);
+        assert_eq!(classified.message, "bumping: too big");
+        assert_eq!(classified.hint.as_deref(), Some("lower it"));
+
+        assert_eq!(classify(&anyhow::anyhow!("something else")).kind, Kind::Other);
+        assert_eq!(classify(&Error::git("git tag failed").into()).kind, Kind::Git);
+
+        let codes = [
+            Kind::Parse,
+            Kind::Constraint,
+            Kind::Io,
+            Kind::Network,
+            Kind::Git,
+            Kind::Config,
+            Kind::Other,
+        ]
+        .map(|kind| kind.exit_code());
+        assert_eq!(codes, [2, 3, 4, 5, 6, 7, 1]);
+    }
+}
@@ -0,0 +1,142 @@
+//! Spelling out what a version means: its parts, and where it falls among the
+//! versions around it under semver's precedence rules. Meant for people who don't
+//! have those rules memorized.
+
+use std::fmt::Write;
+
+use semver::{BuildMetadata, Prerelease, Version};
+
+/// Describe each part of the version and how it orders against its neighbors.
+pub fn explain(version: &Version) -> String {
+    let mut text = String::new();
+    let release = Version::new(version.major, version.minor, version.patch);
+    // Writing to a String can't fail.
+    let _ = writeln!(text, "{version}");
+    let _ = writeln!(text, "  major        {}", version.major);
+    let _ = writeln!(text, "  minor        {}", version.minor);
+    let _ = writeln!(text, "  patch        {}", version.patch);
+    if !version.pre.is_empty() {
+        let identifiers: Vec<String> = version.pre.split('.').map(describe_identifier).collect();
+        let _ = writeln!(text, "  pre-release  {} ({})", version.pre, identifiers.join(", "));
+    }
+    if !version.build.is_empty() {
+        let _ = writeln!(text, "  build        {}", version.build);
+    }
+    text.push('\n');
+
+    if version.pre.is_empty() {
+        let _ = writeln!(
+            text,
+            "This is a release. It comes after every pre-release of {release}, such as {release}-rc.1, and before {}.",
+            Version::new(version.major, version.minor, version.patch + 1)
+        );
+    } else {
+        let _ = writeln!(
+            text,
+            "This is a pre-release of {release}, so it comes before {release} itself but after {}.",
+            before(&release)
+        );
+        let _ = writeln!(
+            text,
+            "Pre-releases of the same version are ordered by their identifiers, one at a time from the left: \
+             numbers by value, text in ASCII order, numbers before text, and a longer list after a shorter one \
+             it starts with."
+        );
+        if let Some((earlier, later)) = neighbors(version) {
+            let _ = writeln!(text, "So it comes after {earlier} and before {later}.");
+        }
+    }
+    if !version.build.is_empty() {
+        let mut bare = version.clone();
+        bare.build = BuildMetadata::EMPTY;
+        let _ = writeln!(
+            text,
+            "Build metadata is ignored when ordering versions, so this has the same precedence as {bare}."
+        );
+    }
+    if version.major == 0 {
+        let _ = writeln!(
+            text,
+            "A major version of 0 is for initial development: anything may change at any time. By convention, \
+             a minor bump is breaking and a patch bump is anything else."
+        );
+    }
+    text
+}
+
+fn describe_identifier(identifier: &str) -> String {
+    if identifier.bytes().all(|b| b.is_ascii_digit()) {
+        format!("{identifier} is a number")
+    } else {
+        format!("{identifier} is text")
+    }
+}
+
+/// The release just before this one, described in words where it can't be named.
+fn before(release: &Version) -> String {
+    match (release.major, release.minor, release.patch) {
+        (_, _, patch) if patch > 0 => Version::new(release.major, release.minor, patch - 1).to_string(),
+        (major, minor, _) if minor > 0 => format!("every {major}.{}.x release", minor - 1),
+        (major, _, _) if major > 0 => format!("every {}.x release", major - 1),
+        _ => "nothing".to_string(),
+    }
+}
+
+/// The pre-releases around this one when its last identifier is a counter.
+fn neighbors(version: &Version) -> Option<(Version, Version)> {
+    let pre = version.pre.as_str();
+    let (stem, counter) = match pre.rsplit_once('.') {
+        Some((stem, counter)) => (format!("{stem}."), counter),
+        None => (String::new(), pre),
+    };
+    let counter: u64 = counter.parse().ok()?;
+    let with_counter = |counter: u64| {
+        let mut next = version.clone();
+        next.build = BuildMetadata::EMPTY;
+        next.pre = Prerelease::new(&format!("{stem}{counter}")).ok()?;
+        Some(next)
+    };
+    let earlier = match counter.checked_sub(1) {
+        Some(counter) => with_counter(counter)?,
+        None if !stem.is_empty() => {
+            let mut earlier = version.clone();
+            earlier.build = BuildMetadata::EMPTY;
+            earlier.pre = Prerelease::new(stem.trim_end_matches('.')).ok()?;
+            earlier
+        }
+        None => return None,
+    };
+    Some((earlier, with_counter(counter + 1)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn explained(version: &str) -> String {
+        explain(&Version::parse(version).expect("we expected a valid version"))
+    }
+
+    #[test]
+    fn explaining_versions() {
+        let text = explained("1.4.0-rc.2+build.5");
+        assert!(text.starts_with(
+            "1.4.0-rc.2+build.5\n  major        1\n  minor        4\n  patch        0\n  pre-release  rc.2 (rc is text, 2 is a number)\n  build        build.5\n\n"
+        ));
+        assert!(text.contains("comes before 1.4.0 itself but after every 1.3.x release."));
+        assert!(text.contains("So it comes after 1.4.0-rc.1 and before 1.4.0-rc.3."));
+        assert!(text.contains("same precedence as 1.4.0-rc.2."));
+        assert!(!text.contains("initial development"));
+
+        let text = explained("0.3.1");
+        assert!(text.contains("It comes after every pre-release of 0.3.1, such as 0.3.1-rc.1, and before 0.3.2."));
+        assert!(text.contains("initial development"));
+        assert!(!text.contains("pre-release  "));
+
+        assert!(explained("2.0.0-beta.0").contains("So it comes after 2.0.0-beta and before 2.0.0-beta.1."));
+        assert!(explained("2.0.0-alpha").contains("after every 1.x release."));
+        assert!(!explained("2.0.0-alpha").contains("So it comes"));
+        assert!(explained("1.2.3-1").contains("after 1.2.2.\n"));
+        assert!(!explained("1.2.3-0").contains("So it comes"));
+    }
+}
@@ -0,0 +1,148 @@
+//! Four-part versions in the .NET and Windows style, as in `1.2.3.4`, where the
+//! fourth number is a revision. Semver has no place for a fourth number, so while
+//! it's bumped the revision is carried as the first identifier of the build
+//! metadata: `1.2.3.4` is read as `1.2.3+4`. That makes it count up with a `build`
+//! bump, and drop back to 0 with a major, minor, or patch bump.
+
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use anyhow::anyhow;
+use semver::{BuildMetadata, Version};
+
+use crate::error::Error;
+
+/// How to print a four-part version.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Style {
+    /// As four numbers again, as in `1.2.3.4`.
+    #[default]
+    Parts,
+    /// With the revision folded into the build metadata, as in `1.2.3+4`.
+    Build,
+}
+
+impl Display for Style {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Style::Parts => write!(f, "parts"),
+            Style::Build => write!(f, "build"),
+        }
+    }
+}
+
+impl FromStr for Style {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "parts" => Ok(Style::Parts),
+            "build" => Ok(Style::Build),
+            _ => Err(anyhow!("{input} is not a four-part style; expected parts or build")),
+        }
+    }
+}
+
+/// Read a version of three or four numbers, with a leading `v` allowed and any
+/// pre-release or build metadata after it. Returns the prefix and the version,
+/// with the revision at the front of the build metadata.
+pub fn parse(input: &str) -> anyhow::Result<(String, Version)> {
+    let trimmed = input.trim();
+    let (prefix, rest) = match trimmed.strip_prefix(['v', 'V']) {
+        Some(rest) => (trimmed[..1].to_string(), rest),
+        None => (String::new(), trimmed),
+    };
+    let numbers_end = rest.find(['-', '+']).unwrap_or(rest.len());
+    let (numbers, suffix) = rest.split_at(numbers_end);
+    let parts: Vec<&str> = numbers.split('.').collect();
+    let (release, revision) = match parts.as_slice() {
+        [major, minor, patch, revision] => (format!("{major}.{minor}.{patch}"), Some(*revision)),
+        [_, _, _] => (numbers.to_string(), None),
+        _ => {
+            return Err(Error::invalid(
+                trimmed,
+                format!("`{trimmed}` is not a four-part version; expected MAJOR.MINOR.PATCH.REVISION"),
+            )
+            .into())
+        }
+    };
+    let mut version = Version::parse(&format!("{release}{suffix}"))
+        .map_err(|e| Error::invalid(trimmed, format!("`{trimmed}` is not a four-part version: {e}")))?;
+    if let Some(revision) = revision {
+        revision
+            .parse::<u64>()
+            .map_err(|_| Error::invalid(trimmed, format!("the revision in `{trimmed}` is not a number")))?;
+        let build = match version.build.as_str() {
+            "" => revision.to_string(),
+            rest => format!("{revision}.{rest}"),
+        };
+        version.build = BuildMetadata::new(&build)?;
+    }
+    Ok((prefix, version))
+}
+
+/// Split the revision off the front of the build metadata, or 0 if it doesn't start
+/// with a number.
+fn revision(version: &Version) -> (u64, &str) {
+    let build = version.build.as_str();
+    let (first, rest) = build.split_once('.').unwrap_or((build, ""));
+    match first.parse::<u64>() {
+        Ok(revision) => (revision, rest),
+        Err(_) => (0, build),
+    }
+}
+
+/// Print a version read by [`parse`] in the given style.
+pub fn render(version: &Version, style: Style) -> String {
+    let (revision, rest) = revision(version);
+    let mut output = match style {
+        Style::Parts => format!("{}.{}.{}.{revision}", version.major, version.minor, version.patch),
+        Style::Build => format!("{}.{}.{}", version.major, version.minor, version.patch),
+    };
+    if !version.pre.is_empty() {
+        output.push('-');
+        output.push_str(version.pre.as_str());
+    }
+    match style {
+        Style::Parts if !rest.is_empty() => output.push_str(&format!("+{rest}")),
+        Style::Parts => {}
+        Style::Build if rest.is_empty() => output.push_str(&format!("+{revision}")),
+        Style::Build => output.push_str(&format!("+{revision}.{rest}")),
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn four_parts() {
+        let (prefix, version) = parse("v1.2.3.4").expect("we expected a four-part version");
+        assert_eq!(prefix, "v");
+        assert_eq!(version.to_string(), "1.2.3+4");
+        assert_eq!(render(&version, Style::Parts), "1.2.3.4");
+        assert_eq!(render(&version, Style::Build), "1.2.3+4");
+
+        let (_, version) = parse("1.2.3.4-beta.1+abc").expect("we expected a four-part version");
+        assert_eq!(version.to_string(), "1.2.3-beta.1+4.abc");
+        assert_eq!(render(&version, Style::Parts), "1.2.3.4-beta.1+abc");
+
+        let (_, version) = parse("1.2.3").expect("we expected three parts to be enough");
+        assert_eq!(render(&version, Style::Parts), "1.2.3.0");
+        assert_eq!(render(&version, Style::Build), "1.2.3+0");
+
+        let bumped = crate::bump(
+            &parse("1.2.3.4").expect("we expected a four-part version").1,
+            &crate::BumpKind::Build(String::new()),
+        )
+        .expect("we expected the revision to count up");
+        assert_eq!(render(&bumped, Style::Parts), "1.2.3.5");
+        let bumped = crate::bump(&bumped, &crate::BumpKind::Minor).expect("we expected the bump to work");
+        assert_eq!(render(&bumped, Style::Parts), "1.3.0.0");
+
+        parse("1.2").expect_err("we expected two parts to be an error");
+        parse("1.2.3.4.5").expect_err("we expected five parts to be an error");
+        parse("1.2.3.x").expect_err("we expected a non-numeric revision to be an error");
+    }
+}
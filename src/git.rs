@@ -0,0 +1,379 @@
+//! The handful of git operations we need, performed by running the user's own
+//! `git` so that their configuration applies.
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::Context;
+
+use crate::error::Error;
+
+/// A git repository on disk.
+#[derive(Clone, Debug)]
+pub struct Git {
+    dir: PathBuf,
+}
+
+impl Git {
+    /// Operate on the repository containing the given directory.
+    pub fn new(dir: impl AsRef<Path>) -> Self {
+        Self {
+            dir: dir.as_ref().to_path_buf(),
+        }
+    }
+
+    /// Run git with the given arguments, returning its trimmed stdout.
+    pub fn run<S: AsRef<str>>(&self, args: &[S]) -> anyhow::Result<String> {
+        let args: Vec<&str> = args.iter().map(|a| a.as_ref()).collect();
+        let output = Command::new("git")
+            .args(&args)
+            .current_dir(&self.dir)
+            .output()
+            .context("unable to run git; is it installed?")?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(Error::git(format!("git {} failed: {}", args.join(" "), stderr.trim())).into());
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+
+    /// The full messages of every commit reachable from HEAD but not from the given ref.
+    pub fn commit_messages(&self, since: &str) -> anyhow::Result<Vec<String>> {
+        self.commit_messages_between(since, "HEAD")
+    }
+
+    /// The full messages of every commit reachable from `to` but not from `from`, newest first.
+    pub fn commit_messages_between(&self, from: &str, to: &str) -> anyhow::Result<Vec<String>> {
+        self.commit_messages_touching(from, to, &[])
+    }
+
+    /// Like [`Git::commit_messages_between`], but only for commits that change something
+    /// under one of these paths. No paths means every commit.
+    pub fn commit_messages_touching(&self, from: &str, to: &str, paths: &[PathBuf]) -> anyhow::Result<Vec<String>> {
+        let range = format!("{from}..{to}");
+        let mut args = vec!["log".to_string(), "--format=%B%x00".to_string(), range];
+        if !paths.is_empty() {
+            args.push("--".to_string());
+            args.extend(paths.iter().map(|path| path.to_string_lossy().into_owned()));
+        }
+        let log = self.run(&args)?;
+        let messages = log
+            .split('\0')
+            .map(|m| m.trim().to_string())
+            .filter(|m| !m.is_empty())
+            .collect();
+        Ok(messages)
+    }
+
+    /// The most recent tag reachable from HEAD whose name matches the glob.
+    pub fn latest_tag(&self, pattern: &str) -> anyhow::Result<String> {
+        self.run(&["describe", "--tags", "--abbrev=0", "--match", pattern])
+            .with_context(|| format!("No tag matching {pattern} is reachable from HEAD."))
+    }
+
+    /// The best common ancestor of HEAD and the given ref, as a full hash.
+    pub fn merge_base(&self, other: &str) -> anyhow::Result<String> {
+        self.run(&["merge-base", "HEAD", other])
+    }
+
+    /// The top-level directory of the working tree.
+    pub fn toplevel(&self) -> anyhow::Result<PathBuf> {
+        self.run(&["rev-parse", "--show-toplevel"]).map(PathBuf::from)
+    }
+
+    /// The name of the branch HEAD is on.
+    pub fn current_branch(&self) -> anyhow::Result<String> {
+        self.run(&["symbolic-ref", "--quiet", "--short", "HEAD"])
+            .context("HEAD is not on a branch, so there is no branch to push.")
+    }
+
+    /// Push these refspecs to the remote, all or nothing.
+    pub fn push(&self, remote: &str, refspecs: &[String]) -> anyhow::Result<()> {
+        let mut args = vec!["push", "--quiet", "--atomic", remote];
+        args.extend(refspecs.iter().map(String::as_str));
+        self.run(&args)?;
+        Ok(())
+    }
+
+    /// The abbreviated hash of the HEAD commit.
+    pub fn short_sha(&self) -> anyhow::Result<String> {
+        self.run(&["rev-parse", "--short", "HEAD"])
+    }
+
+    /// The number of commits reachable from HEAD.
+    pub fn commit_count(&self) -> anyhow::Result<u64> {
+        let count = self.run(&["rev-list", "--count", "HEAD"])?;
+        count
+            .parse()
+            .with_context(|| format!("git rev-list printed `{count}` instead of a count"))
+    }
+
+    /// Whether the working tree has uncommitted changes, ignoring untracked files.
+    pub fn is_dirty(&self) -> anyhow::Result<bool> {
+        let status = self.run(&["status", "--porcelain", "--untracked-files=no"])?;
+        Ok(!status.is_empty())
+    }
+
+    /// Whether the index has changes staged for the next commit.
+    pub fn has_staged_changes(&self) -> anyhow::Result<bool> {
+        let output = Command::new("git")
+            .args(["diff", "--cached", "--quiet"])
+            .current_dir(&self.dir)
+            .output()
+            .context("unable to run git; is it installed?")?;
+        match output.status.code() {
+            Some(0) => Ok(false),
+            Some(1) => Ok(true),
+            _ => Err(Error::git(format!(
+                "git diff --cached failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ))
+            .into()),
+        }
+    }
+
+    /// Stage exactly these files and commit them with the given message, signing the
+    /// commit with the user's configured GPG or SSH key if asked to.
+    pub fn commit_files(&self, paths: &[&Path], message: &str, sign: bool) -> anyhow::Result<()> {
+        let mut add = vec!["add".to_string(), "--".to_string()];
+        add.extend(paths.iter().map(|p| p.to_string_lossy().into_owned()));
+        self.run(&add)?;
+        let mut commit = vec!["commit", "--quiet", "--message", message];
+        if sign {
+            commit.push("--gpg-sign");
+        }
+        self.run(&commit)?;
+        Ok(())
+    }
+
+    /// Whether git tracks this file.
+    pub fn is_tracked(&self, path: &Path) -> anyhow::Result<bool> {
+        let status = Command::new("git")
+            .args(["ls-files", "--error-unmatch", "--"])
+            .arg(path)
+            .current_dir(&self.dir)
+            .output()
+            .context("unable to run git; is it installed?")?
+            .status;
+        Ok(status.success())
+    }
+
+    /// The names of every tag in the repository, oldest first.
+    pub fn tags(&self) -> anyhow::Result<Vec<String>> {
+        let tags = self.run(&["tag", "--list", "--sort=creatordate"])?;
+        Ok(tags.lines().map(str::to_string).collect())
+    }
+
+    /// The names of the tags pointing at HEAD.
+    pub fn tags_at_head(&self) -> anyhow::Result<Vec<String>> {
+        let tags = self.run(&["tag", "--points-at", "HEAD"])?;
+        Ok(tags.lines().map(str::to_string).collect())
+    }
+
+    /// The names of every tag on the remote, which need not have been fetched.
+    pub fn remote_tags(&self, remote: &str) -> anyhow::Result<Vec<String>> {
+        let refs = self.run(&["ls-remote", "--tags", "--refs", remote])?;
+        let tags = refs
+            .lines()
+            .filter_map(|line| line.split_once("refs/tags/"))
+            .map(|(_, name)| name.to_string())
+            .collect();
+        Ok(tags)
+    }
+
+    /// Check whether a tag with this name exists.
+    pub fn tag_exists(&self, name: &str) -> anyhow::Result<bool> {
+        let refname = format!("refs/tags/{name}");
+        let output = Command::new("git")
+            .args(["rev-parse", "--quiet", "--verify", refname.as_str()])
+            .current_dir(&self.dir)
+            .output()
+            .context("unable to run git; is it installed?")?;
+        Ok(output.status.success())
+    }
+
+    /// Create an annotated tag pointing at HEAD, refusing to replace an existing tag. A
+    /// signed tag uses whichever GPG or SSH key git is configured to sign with.
+    pub fn create_tag(&self, name: &str, message: &str, sign: bool) -> anyhow::Result<()> {
+        if self.tag_exists(name)? {
+            return Err(Error::constraint(format!("The tag {name} already exists.")).into());
+        }
+        let kind = if sign { "--sign" } else { "--annotate" };
+        self.run(&["tag", kind, name, "--message", message])?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Make a scratch repository with a single commit in it.
+    pub(crate) fn scratch_repo() -> (tempfile::TempDir, Git) {
+        let dir = tempfile::tempdir().expect("we expected to be able to make a temp dir");
+        let git = Git::new(dir.path());
+        git.run(&["init", "--quiet"]).expect("we expected git init to work");
+        git.run(&["config", "user.name", "Test Runner"])
+            .expect("we expected git config to work");
+        git.run(&["config", "user.email", "test@example.com"])
+            .expect("we expected git config to work");
+        git.run(&["config", "commit.gpgsign", "false"])
+            .expect("we expected git config to work");
+        git.run(&["commit", "--quiet", "--allow-empty", "--message", "initial"])
+            .expect("we expected the initial commit to work");
+        (dir, git)
+    }
+
+    #[test]
+    fn tagging() {
+        let (_dir, git) = scratch_repo();
+        assert!(!git.tag_exists("v1.0.0").expect("we expected tag lookup to work"));
+        git.create_tag("v1.0.0", "v1.0.0", false)
+            .expect("we expected tagging to work");
+        assert!(git.tag_exists("v1.0.0").expect("we expected tag lookup to work"));
+        assert_eq!(
+            git.tags_at_head().expect("we expected to list the tags"),
+            vec!["v1.0.0".to_string()]
+        );
+        git.create_tag("v1.0.0", "v1.0.0", false)
+            .expect_err("we expected re-tagging to be an error");
+    }
+
+    #[test]
+    fn signing() {
+        let (dir, git) = scratch_repo();
+        // A signing program that always fails shows that git was asked to sign at all.
+        git.run(&["config", "gpg.program", "false"])
+            .expect("we expected git config to work");
+        git.create_tag("v1.0.0", "v1.0.0", true)
+            .expect_err("we expected signing with a broken signer to fail");
+        std::fs::write(dir.path().join("VERSION"), "1.0.0\n").expect("we expected to write a file");
+        git.commit_files(&[Path::new("VERSION")], "Bump version to 1.0.0", true)
+            .expect_err("we expected signing with a broken signer to fail");
+        git.create_tag("v1.0.0", "v1.0.0", false)
+            .expect("we expected an unsigned tag to work without a signer");
+    }
+
+    #[test]
+    fn describing_head() {
+        let (dir, git) = scratch_repo();
+        let sha = git.short_sha().expect("we expected to read the hash");
+        assert!(sha.len() >= 7 && sha.chars().all(|c| c.is_ascii_hexdigit()));
+        assert_eq!(git.commit_count().expect("we expected to count commits"), 1);
+        assert!(!git.is_dirty().expect("we expected to read the status"));
+        std::fs::write(dir.path().join("VERSION"), "1.0.0\n").expect("we expected to write a file");
+        assert!(!git.is_dirty().expect("we expected to read the status"));
+        git.run(&["add", "VERSION"]).expect("we expected git add to work");
+        assert!(git.is_dirty().expect("we expected to read the status"));
+    }
+
+    #[test]
+    fn committing_files() {
+        let (dir, git) = scratch_repo();
+        std::fs::write(dir.path().join("VERSION"), "1.0.0\n").expect("we expected to write a file");
+        std::fs::write(dir.path().join("notes.txt"), "unrelated\n").expect("we expected to write a file");
+        assert!(!git.has_staged_changes().expect("we expected to read the index"));
+        git.commit_files(&[Path::new("VERSION")], "Bump version to 1.0.0", false)
+            .expect("we expected the commit to work");
+        assert_eq!(git.commit_count().expect("we expected to count commits"), 2);
+        let files = git
+            .run(&["show", "--name-only", "--format=%s", "HEAD"])
+            .expect("we expected git show to work");
+        assert_eq!(files, "Bump version to 1.0.0\n\nVERSION");
+        assert!(git
+            .is_tracked(Path::new("VERSION"))
+            .expect("we expected to read the index"));
+        assert!(!git
+            .is_tracked(Path::new("notes.txt"))
+            .expect("we expected to read the index"));
+        git.run(&["add", "notes.txt"]).expect("we expected git add to work");
+        assert!(git.has_staged_changes().expect("we expected to read the index"));
+    }
+
+    #[test]
+    fn scoping_history() {
+        let (dir, git) = scratch_repo();
+        git.create_tag("api-v1.0.0", "api-v1.0.0", false)
+            .expect("we expected tagging to work");
+        std::fs::create_dir(dir.path().join("api")).expect("we expected to make a directory");
+        std::fs::write(dir.path().join("api/lib.rs"), "").expect("we expected to write a file");
+        git.commit_files(&[Path::new("api/lib.rs")], "feat(api): add it", false)
+            .expect("we expected the commit to work");
+        std::fs::write(dir.path().join("README.md"), "").expect("we expected to write a file");
+        git.commit_files(&[Path::new("README.md")], "docs: explain it", false)
+            .expect("we expected the commit to work");
+        let since = git.latest_tag("api-v*").expect("we expected to find the tag");
+        assert_eq!(since, "api-v1.0.0");
+        let missing = git.latest_tag("web-v*").expect_err("we expected no web tag");
+        assert_eq!(crate::error::classify(&missing).kind, crate::error::Kind::Git);
+        let all = git
+            .commit_messages_touching(&since, "HEAD", &[])
+            .expect("we expected git log to work");
+        assert_eq!(all, ["docs: explain it", "feat(api): add it"]);
+        let api = git
+            .commit_messages_touching(&since, "HEAD", &[PathBuf::from("api")])
+            .expect("we expected git log to work");
+        assert_eq!(api, ["feat(api): add it"]);
+        let base = git.merge_base(&since).expect("we expected to find the merge base");
+        assert_eq!(
+            base,
+            git.run(&["rev-parse", "api-v1.0.0^{commit}"])
+                .expect("we expected rev-parse to work")
+        );
+    }
+
+    #[test]
+    fn pushing() {
+        let (_dir, git) = scratch_repo();
+        let remote = tempfile::tempdir().expect("we expected to be able to make a temp dir");
+        Git::new(remote.path())
+            .run(&["init", "--quiet", "--bare"])
+            .expect("we expected git init to work");
+        let url = remote.path().to_string_lossy().into_owned();
+        git.create_tag("v1.0.0", "v1.0.0", false)
+            .expect("we expected tagging to work");
+        let branch = git.current_branch().expect("we expected HEAD to be on a branch");
+        git.push(
+            &url,
+            &[format!("HEAD:refs/heads/{branch}"), "refs/tags/v1.0.0".to_string()],
+        )
+        .expect("we expected the push to work");
+        let refs = git
+            .run(&["ls-remote", "--refs", url.as_str()])
+            .expect("we expected ls-remote to work");
+        assert!(refs.contains(&format!("refs/heads/{branch}")));
+        assert!(refs.contains("refs/tags/v1.0.0"));
+        let tags = git.remote_tags(&url).expect("we expected to list the remote's tags");
+        assert_eq!(tags, vec!["v1.0.0".to_string()]);
+        git.run(&["tag", "--delete", "v1.0.0"])
+            .expect("we expected to delete the tag");
+        assert!(!git.tag_exists("v1.0.0").expect("we expected tag lookup to work"));
+        git.run(&["checkout", "--quiet", "--detach"])
+            .expect("we expected checkout to work");
+        let detached = git
+            .current_branch()
+            .expect_err("we expected a detached HEAD to have no branch");
+        assert_eq!(crate::error::classify(&detached).kind, crate::error::Kind::Git);
+    }
+
+    #[test]
+    fn reading_commit_messages() {
+        let (_dir, git) = scratch_repo();
+        git.run(&["tag", "start"]).expect("we expected tagging to work");
+        git.run(&[
+            "commit",
+            "--quiet",
+            "--allow-empty",
+            "--message",
+            "fix: one\n\nwith a body",
+        ])
+        .expect("we expected the commit to work");
+        git.run(&["commit", "--quiet", "--allow-empty", "--message", "feat: two"])
+            .expect("we expected the commit to work");
+        let messages = git.commit_messages("start").expect("we expected git log to work");
+        assert_eq!(
+            messages,
+            vec!["feat: two".to_string(), "fix: one\n\nwith a body".to_string()]
+        );
+    }
+}
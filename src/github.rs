@@ -0,0 +1,250 @@
+//! Listing tags, reading pull request labels, and creating releases through the
+//! GitHub REST API.
+
+use std::env;
+
+use anyhow::anyhow;
+
+use crate::error::Error;
+use crate::http;
+
+/// A GitHub repository we can make API calls about.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Repository {
+    /// The base URL of the API, which differs on GitHub Enterprise Server.
+    api: String,
+    /// The repository's `owner/name`.
+    slug: String,
+    token: Option<String>,
+}
+
+/// A release to create for an existing tag.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Release {
+    /// The tag the release is for.
+    pub tag: String,
+    /// The release notes, or `None` to have GitHub generate them.
+    pub body: Option<String>,
+    /// Whether to save the release as a draft instead of publishing it.
+    pub draft: bool,
+    /// Whether to mark the release as a pre-release.
+    pub prerelease: bool,
+}
+
+impl Release {
+    /// The body of the request that creates the release.
+    fn request(&self) -> serde_json::Value {
+        let mut request = serde_json::json!({
+            "tag_name": self.tag,
+            "name": self.tag,
+            "draft": self.draft,
+            "prerelease": self.prerelease,
+        });
+        match self.body.as_ref() {
+            Some(body) => request["body"] = body.clone().into(),
+            None => request["generate_release_notes"] = true.into(),
+        }
+        request
+    }
+}
+
+impl Repository {
+    /// Use the given `owner/name`, or else the `GITHUB_REPOSITORY` that GitHub Actions
+    /// sets, with the token in `GITHUB_TOKEN` or `GH_TOKEN` if there is one. The API
+    /// is at `GITHUB_API_URL` if that's set.
+    pub fn from_env(slug: Option<&str>) -> anyhow::Result<Self> {
+        let slug = match slug {
+            Some(slug) => slug.to_string(),
+            None => env::var("GITHUB_REPOSITORY")
+                .map_err(|_| anyhow!("GITHUB_REPOSITORY is not set; pass --github-repo OWNER/NAME."))?,
+        };
+        if slug.split('/').count() != 2 || slug.split('/').any(str::is_empty) {
+            return Err(anyhow!("`{slug}` is not a repository; expected OWNER/NAME."));
+        }
+        let token = env::var("GITHUB_TOKEN").or_else(|_| env::var("GH_TOKEN")).ok();
+        let api = env::var("GITHUB_API_URL").unwrap_or_else(|_| "https://api.github.com".to_string());
+        Ok(Self {
+            api: api.trim_end_matches('/').to_string(),
+            slug,
+            token,
+        })
+    }
+
+    /// The repository's `owner/name`.
+    pub fn slug(&self) -> &str {
+        &self.slug
+    }
+
+    /// Refuse to go on without a token, for calls that change the repository.
+    pub fn require_token(&self) -> anyhow::Result<()> {
+        match self.token {
+            Some(_) => Ok(()),
+            None => Err(anyhow!(
+                "Set GITHUB_TOKEN or GH_TOKEN to a token that can create releases."
+            )),
+        }
+    }
+
+    /// The headers every call sends, with the token if there is one.
+    fn headers(&self) -> Vec<(&'static str, String)> {
+        let mut headers = vec![
+            ("Accept", "application/vnd.github+json".to_string()),
+            ("X-GitHub-Api-Version", "2022-11-28".to_string()),
+        ];
+        if let Some(token) = self.token.as_ref() {
+            headers.push(("Authorization", format!("Bearer {token}")));
+        }
+        headers
+    }
+
+    /// GET a path under the repository and return the JSON list it sends, describing
+    /// what we asked for in any error.
+    fn get_list(&self, path: &str, what: &str) -> anyhow::Result<Vec<serde_json::Value>> {
+        let headers = self.headers();
+        let headers: Vec<(&str, &str)> = headers.iter().map(|(k, v)| (*k, v.as_str())).collect();
+        let url = format!("{}/repos/{}/{path}", self.api, self.slug);
+        let response = http::get(&url, &headers)?;
+        if !response.is_success() {
+            return Err(Error::network(format!(
+                "GitHub refused to list {what} of {} ({}): {}",
+                self.slug,
+                response.status,
+                refusal(&response)
+            ))
+            .into());
+        }
+        match response.json()? {
+            serde_json::Value::Array(items) => Ok(items),
+            _ => Err(anyhow!("GitHub did not send a list of {what}")),
+        }
+    }
+
+    /// The names of every tag in the repository.
+    pub fn tags(&self) -> anyhow::Result<Vec<String>> {
+        let mut tags = Vec::new();
+        for page in 1.. {
+            let names = self.get_list(&format!("tags?per_page=100&page={page}"), "the tags")?;
+            tags.extend(names.iter().filter_map(|t| t["name"].as_str()).map(str::to_string));
+            if names.len() < 100 {
+                break;
+            }
+        }
+        Ok(tags)
+    }
+
+    /// The labels on a pull request.
+    pub fn labels(&self, number: u64) -> anyhow::Result<Vec<String>> {
+        let labels = self.get_list(&format!("issues/{number}/labels?per_page=100"), "the labels")?;
+        Ok(label_names(&labels))
+    }
+
+    /// The number and labels of the merged pull request that brought in this commit, if
+    /// it came from one.
+    pub fn merged_pull_request(&self, sha: &str) -> anyhow::Result<Option<(u64, Vec<String>)>> {
+        let pulls = self.get_list(&format!("commits/{sha}/pulls"), "the pull requests")?;
+        Ok(merged_pull(&pulls))
+    }
+
+    /// Create a release, returning the address of its page.
+    pub fn create_release(&self, release: &Release) -> anyhow::Result<String> {
+        self.require_token()?;
+        let url = format!("{}/repos/{}/releases", self.api, self.slug);
+        let headers = self.headers();
+        let headers: Vec<(&str, &str)> = headers.iter().map(|(k, v)| (*k, v.as_str())).collect();
+        let response = http::post_json(&url, &headers, &release.request())?;
+        if !response.is_success() {
+            return Err(Error::network(format!(
+                "GitHub refused to create the release for {} ({}): {}",
+                release.tag,
+                response.status,
+                refusal(&response)
+            ))
+            .into());
+        }
+        let json = response.json()?;
+        Ok(json["html_url"].as_str().unwrap_or_default().to_string())
+    }
+}
+
+/// The message GitHub sent with a refusal, which may not be JSON at all when it comes
+/// from a proxy in front of the API.
+fn refusal(response: &http::Response) -> String {
+    let json = response.json().unwrap_or_default();
+    json["message"].as_str().unwrap_or("no message").to_string()
+}
+
+/// The number of the pull request the workflow was triggered for, from the event
+/// payload in `GITHUB_EVENT_PATH`, or `None` for events about something else.
+pub fn event_pull_request() -> anyhow::Result<Option<u64>> {
+    let Some(path) = env::var_os("GITHUB_EVENT_PATH") else {
+        return Ok(None);
+    };
+    let event = std::fs::read_to_string(&path)?;
+    let event: serde_json::Value = serde_json::from_str(&event)?;
+    Ok(event["pull_request"]["number"].as_u64())
+}
+
+fn label_names(labels: &[serde_json::Value]) -> Vec<String> {
+    labels
+        .iter()
+        .filter_map(|label| label["name"].as_str())
+        .map(str::to_string)
+        .collect()
+}
+
+/// The first merged pull request in a list, with its labels.
+fn merged_pull(pulls: &[serde_json::Value]) -> Option<(u64, Vec<String>)> {
+    let pull = pulls.iter().find(|pull| pull["merged_at"].is_string())?;
+    let labels = pull["labels"].as_array().map(Vec::as_slice).unwrap_or_default();
+    Some((pull["number"].as_u64()?, label_names(labels)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn refusals() {
+        let response = |body: &str| http::Response {
+            status: 403,
+            headers: Vec::new(),
+            body: body.to_string(),
+        };
+        assert_eq!(
+            refusal(&response(r#"{"message": "API rate limit exceeded"}"#)),
+            "API rate limit exceeded"
+        );
+        assert_eq!(refusal(&response("<html>Bad gateway</html>")), "no message");
+    }
+
+    #[test]
+    fn release_requests() {
+        let release = Release {
+            tag: "v1.3.0-rc.1".to_string(),
+            body: Some("### Features\n".to_string()),
+            draft: false,
+            prerelease: true,
+        };
+        let request = release.request();
+        assert_eq!(request["tag_name"], "v1.3.0-rc.1");
+        assert_eq!(request["body"], "### Features\n");
+        assert_eq!(request["prerelease"], true);
+        assert!(request.get("generate_release_notes").is_none());
+        let request = Release { body: None, ..release }.request();
+        assert_eq!(request["generate_release_notes"], true);
+    }
+
+    #[test]
+    fn finding_merged_pulls() {
+        let pulls = serde_json::json!([
+            {"number": 41, "merged_at": null, "labels": [{"name": "semver:major"}]},
+            {"number": 42, "merged_at": "2024-03-09T12:00:00Z", "labels": [{"name": "semver:minor"}, {"name": "docs"}]},
+        ]);
+        let pulls = pulls.as_array().expect("test data must be a list");
+        assert_eq!(
+            merged_pull(pulls),
+            Some((42, vec!["semver:minor".to_string(), "docs".to_string()]))
+        );
+        assert_eq!(merged_pull(&pulls[..1]), None);
+    }
+}
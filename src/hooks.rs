@@ -0,0 +1,92 @@
+//! Running the commands the config file lists as `pre_bump` and `post_bump` hooks.
+//! Each runs in a shell, in order, with the bump described in its environment:
+//!
+//! - `SEMVER_BUMP_PREVIOUS`: the previous version, or nothing if it wasn't read
+//! - `SEMVER_BUMP_VERSION`: the new version
+//! - `SEMVER_BUMP_KIND`: the kind of bump, as in `minor`
+//! - `SEMVER_BUMP_TAG`: the tag that will be created, or nothing without `--tag`
+//!
+//! The first command to fail stops the rest. Anything the commands print goes to
+//! stderr, so that stdout still holds just the new version.
+
+use std::process::{Command, Stdio};
+
+use anyhow::Context;
+
+use crate::error::Error;
+
+/// The bump the hooks are told about.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Bump {
+    /// The previous version, if it was read.
+    pub previous: Option<String>,
+    /// The new version.
+    pub version: String,
+    /// The kind of bump made.
+    pub kind: String,
+    /// The tag that will be created, if any.
+    pub tag: Option<String>,
+}
+
+impl Bump {
+    /// The environment variables describing the bump.
+    fn env(&self) -> [(&'static str, &str); 4] {
+        [
+            ("SEMVER_BUMP_PREVIOUS", self.previous.as_deref().unwrap_or_default()),
+            ("SEMVER_BUMP_VERSION", &self.version),
+            ("SEMVER_BUMP_KIND", &self.kind),
+            ("SEMVER_BUMP_TAG", self.tag.as_deref().unwrap_or_default()),
+        ]
+    }
+}
+
+/// Run each of the hook's commands in turn, stopping at the first that fails. The
+/// hook's name, as in `pre_bump`, is used in errors.
+pub fn run(name: &str, commands: &[String], bump: &Bump) -> anyhow::Result<()> {
+    for command in commands {
+        let mut shell = if cfg!(windows) {
+            let mut shell = Command::new("cmd");
+            shell.arg("/C");
+            shell
+        } else {
+            let mut shell = Command::new("sh");
+            shell.arg("-c");
+            shell
+        };
+        let status = shell
+            .arg(command)
+            .envs(bump.env())
+            .stdin(Stdio::null())
+            .stdout(std::io::stderr())
+            .status()
+            .with_context(|| format!("unable to run the {name} hook `{command}`"))?;
+        if !status.success() {
+            return Err(Error::io(format!("The {name} hook `{command}` failed ({status}).")).into());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn running_hooks() {
+        let bump = Bump {
+            previous: Some("1.2.3".to_string()),
+            version: "1.3.0".to_string(),
+            kind: "minor".to_string(),
+            tag: None,
+        };
+        let commands = [
+            "test \"$SEMVER_BUMP_PREVIOUS $SEMVER_BUMP_VERSION $SEMVER_BUMP_KIND\" = \"1.2.3 1.3.0 minor\"".to_string(),
+            "test -z \"$SEMVER_BUMP_TAG\"".to_string(),
+        ];
+        run("pre_bump", &commands, &bump).expect("we expected the hooks to see the bump");
+        let failing = ["exit 3".to_string(), "echo unreachable".to_string()];
+        let error = run("post_bump", &failing, &bump).expect_err("we expected a failing hook to be an error");
+        assert!(error.to_string().starts_with("The post_bump hook `exit 3` failed"));
+        assert_eq!(crate::error::classify(&error).kind, crate::error::Kind::Io);
+    }
+}
@@ -0,0 +1,154 @@
+//! Talking to web APIs by running `curl`, which every CI runner has, so that we
+//! don't need an HTTP client and a TLS stack of our own. Headers are handed to
+//! curl in a private temporary file rather than on its command line, where
+//! other processes could read any token in them.
+
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+use anyhow::{anyhow, Context};
+
+/// A response from an API.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Response {
+    /// The HTTP status code.
+    pub status: u16,
+    /// The headers of the response, with lowercase names.
+    pub headers: Vec<(String, String)>,
+    /// The body of the response.
+    pub body: String,
+}
+
+impl Response {
+    /// Whether the status is a 2xx success.
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+
+    /// The value of the first header with this name, ignoring case.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Parse the body as JSON.
+    pub fn json(&self) -> anyhow::Result<serde_json::Value> {
+        serde_json::from_str(&self.body).context("the response was not valid JSON")
+    }
+
+    /// Split what curl printed into the body and the status code it wrote after it.
+    fn parse(output: &str) -> anyhow::Result<Self> {
+        let (body, status) = output.rsplit_once('\n').unwrap_or(("", output));
+        let status = status
+            .trim()
+            .parse()
+            .map_err(|_| anyhow!("curl did not report an HTTP status"))?;
+        Ok(Self {
+            status,
+            headers: Vec::new(),
+            body: body.to_string(),
+        })
+    }
+
+    /// Read the headers curl dumped. After redirects there is a block for each
+    /// response; only the last one is ours.
+    fn parse_headers(dump: &str) -> Vec<(String, String)> {
+        let last = dump
+            .split("\r\n\r\n")
+            .filter(|block| !block.trim().is_empty())
+            .last()
+            .unwrap_or_default();
+        last.lines()
+            .skip(1)
+            .filter_map(|line| line.split_once(':'))
+            .map(|(name, value)| (name.trim().to_ascii_lowercase(), value.trim().to_string()))
+            .collect()
+    }
+}
+
+/// Send a GET request with the given headers.
+pub fn get(url: &str, headers: &[(&str, &str)]) -> anyhow::Result<Response> {
+    request("GET", url, headers, None)
+}
+
+/// Send a POST request with the given headers and a JSON body.
+pub fn post_json(url: &str, headers: &[(&str, &str)], body: &serde_json::Value) -> anyhow::Result<Response> {
+    let mut headers = headers.to_vec();
+    headers.push(("Content-Type", "application/json"));
+    request("POST", url, &headers, Some(body.to_string()))
+}
+
+fn request(method: &str, url: &str, headers: &[(&str, &str)], body: Option<String>) -> anyhow::Result<Response> {
+    let mut header_file = tempfile::NamedTempFile::new().context("unable to make a temporary file for headers")?;
+    for (name, value) in headers {
+        writeln!(header_file, "{name}: {value}")?;
+    }
+    header_file.flush()?;
+    let mut header_arg = std::ffi::OsString::from("@");
+    header_arg.push(header_file.path());
+    let dump = tempfile::NamedTempFile::new().context("unable to make a temporary file for headers")?;
+
+    let mut command = Command::new("curl");
+    command
+        .args(["--silent", "--show-error", "--location", "--request", method])
+        .args(["--write-out", "\n%{http_code}"])
+        .arg("--header")
+        .arg(header_arg)
+        .arg("--dump-header")
+        .arg(dump.path());
+    if body.is_some() {
+        command.args(["--data-binary", "@-"]);
+    }
+    let mut child = command
+        .arg(url)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .context("unable to run curl; is it installed?")?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(body.unwrap_or_default().as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(crate::error::Error::network(format!("{method} {url} failed: {}", stderr.trim())).into());
+    }
+    let mut response = Response::parse(&String::from_utf8_lossy(&output.stdout))?;
+    response.headers = Response::parse_headers(&std::fs::read_to_string(dump.path()).unwrap_or_default());
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsing_responses() {
+        let response = Response::parse("{\"id\": 1}\n201").expect("we expected the output to parse");
+        assert_eq!(response.status, 201);
+        assert!(response.is_success());
+        assert_eq!(response.json().expect("we expected valid JSON")["id"], 1);
+        let response = Response::parse("\n404").expect("we expected the output to parse");
+        assert!(!response.is_success());
+        Response::parse("no status").expect_err("we expected a missing status to be an error");
+    }
+
+    #[test]
+    fn parsing_headers() {
+        let dump = "HTTP/1.1 302 Found\r\nLocation: /v2/\r\n\r\nHTTP/1.1 401 Unauthorized\r\nWWW-Authenticate: Bearer realm=\"https://ghcr.io/token\"\r\nContent-Length: 0\r\n\r\n";
+        let response = Response {
+            status: 401,
+            headers: Response::parse_headers(dump),
+            body: String::new(),
+        };
+        assert_eq!(
+            response.header("www-authenticate"),
+            Some("Bearer realm=\"https://ghcr.io/token\"")
+        );
+        assert_eq!(response.header("Location"), None);
+        assert_eq!(response.headers.len(), 2);
+    }
+}
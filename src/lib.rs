@@ -0,0 +1,1792 @@
+//! The version-bumping logic behind the `semver-bump` cli, exposed as a library
+//! so you can call it from your own release tooling without shelling out.
+//! This is a very simple wrapper around the semver crate. It handles incrementing
+//! or replacing pre-release and build identifiers as well as the usual
+//! major.minor.patch numbers.
+//!
+//! ```
+//! use semver::Version;
+//! use semver_bump::{bump, BumpKind};
+//!
+//! let previous = Version::parse("1.2.3-alpha.4").expect("valid semver");
+//! let next = bump(&previous, &BumpKind::Prerelease(String::new())).expect("bumpable");
+//! assert_eq!(next.to_string(), "1.2.3-alpha.5");
+//! ```
+
+#![deny(future_incompatible, clippy::unwrap_used)]
+#![warn(rust_2018_idioms, trivial_casts)]
+
+use std::cmp::Ordering;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context};
+use semver::{BuildMetadata, Prerelease, Version};
+
+pub mod calver;
+pub mod changes;
+pub mod ci;
+pub mod config;
+pub mod conventional;
+pub mod convert;
+pub mod describe;
+pub mod docker;
+pub mod epoch;
+pub mod error;
+pub mod explain;
+pub mod fourpart;
+pub mod git;
+pub mod github;
+pub mod hooks;
+pub mod http;
+pub mod lint;
+pub mod list;
+pub mod manifest;
+pub mod menu;
+pub mod notes;
+pub mod pep440;
+pub mod registry;
+pub mod req;
+pub mod semver_checks;
+pub mod sha256;
+pub mod template;
+pub mod timestamp;
+pub mod validate;
+
+// Valid separators between the pre-release and its number;
+// no separator at all is also valid.
+const SEPARATORS: [char; 2] = ['.', '-'];
+
+/// The pre-release channels `promote` moves through when not told otherwise,
+/// from least to most mature.
+pub const DEFAULT_CHANNELS: [&str; 3] = ["alpha", "beta", "rc"];
+
+/// The kinds of bump we know how to perform.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum BumpKind {
+    /// Bump the major version number for a breaking change.
+    Major,
+    /// Bump the minor version number for a new feature.
+    Minor,
+    /// Bump the patch version number for a bug fix.
+    Patch,
+    /// Add, replace, or increment a pre-release identifier. Pass an empty string
+    /// to re-use the existing identifier.
+    Prerelease(String),
+    /// Add, replace, or increment a build identifier. Pass an empty string
+    /// to re-use the existing identifier.
+    Build(String),
+    /// Bump the given level, then start a pre-release of the result, as in
+    /// `premajor rc` turning `1.2.3` into `2.0.0-rc.1`. Pass an empty string to
+    /// re-use the name of the existing pre-release identifier.
+    Pre(Level, String),
+    /// Move a pre-release to a later channel, as in `1.0.0-alpha.3` to `1.0.0-beta.1`.
+    Promote {
+        /// The pre-release channels, in order from least to most mature.
+        channels: Vec<String>,
+        /// The channel to move to; the next one in order if not given.
+        to: Option<String>,
+    },
+    /// Graduate a pre-release by dropping its pre-release and build identifiers.
+    Release,
+    /// Start the next Maven development version after a release, as in `1.2.3` to
+    /// `1.2.4-SNAPSHOT`.
+    Snapshot,
+    /// Replace the version outright with this one.
+    Set(Version),
+    /// Decrement the given level, as in `down minor` turning `1.3.2` into `1.2.0`.
+    Down(Level),
+    /// Undo the most recent bump as best we can tell from the shape of the version.
+    Rollback,
+    /// Make whatever bump a change of the given size calls for: the matching major,
+    /// minor, or patch bump, or one level lower for a 0.x version when pre-1.0
+    /// semantics are on.
+    Change(Level),
+    /// Make a pre-release in the given channel for a change of the given size. A
+    /// pre-release that already makes a bump at least that large just moves to the
+    /// channel's next count, so a feature on `1.3.0-beta.1` makes `1.3.0-beta.2`;
+    /// anything else starts a pre-release of the bumped version, as in `1.3.0-beta.1`
+    /// to `2.0.0-beta.1` for a breaking change. Pre-1.0 semantics apply as they do
+    /// to [`BumpKind::Change`].
+    Channel(Level, String),
+}
+
+impl BumpKind {
+    /// The concrete bump this kind stands for when applied to the given version.
+    /// Only [`BumpKind::Change`] and [`BumpKind::Channel`] depend on the version; every
+    /// other kind is returned as is.
+    pub fn resolve(&self, previous: &Version, options: &Options) -> BumpKind {
+        let level = |level: Level| match level {
+            _ if !(options.zerover && previous.major == 0) => level,
+            Level::Major => Level::Minor,
+            Level::Minor | Level::Patch => Level::Patch,
+        };
+        match self {
+            BumpKind::Change(change) => level(*change).into(),
+            BumpKind::Channel(change, channel) => {
+                // The largest bump the pre-release already makes over the last release.
+                let covered = match previous {
+                    _ if previous.pre.is_empty() => None,
+                    _ if previous.patch != 0 => Some(Level::Patch),
+                    _ if previous.minor != 0 => Some(Level::Minor),
+                    _ => Some(Level::Major),
+                };
+                match covered {
+                    // Naming the channel it's already in would start the count over.
+                    Some(covered) if level(*change) <= covered && strip_counter(previous.pre.as_str()) == channel => {
+                        BumpKind::Prerelease(String::new())
+                    }
+                    Some(covered) if level(*change) <= covered => BumpKind::Prerelease(channel.clone()),
+                    _ => BumpKind::Pre(level(*change), channel.clone()),
+                }
+            }
+            kind => kind.clone(),
+        }
+    }
+}
+
+impl Display for BumpKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            BumpKind::Major => "major",
+            BumpKind::Minor => "minor",
+            BumpKind::Patch => "patch",
+            BumpKind::Prerelease(_) => "prerelease",
+            BumpKind::Build(_) => "build",
+            BumpKind::Pre(Level::Major, _) => "premajor",
+            BumpKind::Pre(Level::Minor, _) => "preminor",
+            BumpKind::Pre(Level::Patch, _) => "prepatch",
+            BumpKind::Promote { .. } => "promote",
+            BumpKind::Release => "release",
+            BumpKind::Snapshot => "snapshot",
+            BumpKind::Set(_) => "set",
+            BumpKind::Down(Level::Major) => "down-major",
+            BumpKind::Down(Level::Minor) => "down-minor",
+            BumpKind::Down(Level::Patch) => "down-patch",
+            BumpKind::Rollback => "rollback",
+            BumpKind::Change(Level::Major) => "breaking",
+            BumpKind::Change(Level::Minor) => "feature",
+            BumpKind::Change(Level::Patch) => "fix",
+            BumpKind::Channel(Level::Major, _) => "breaking",
+            BumpKind::Channel(Level::Minor, _) => "feature",
+            BumpKind::Channel(Level::Patch, _) => "fix",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// The size of a change to the major.minor.patch numbers, in increasing order
+/// of significance.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Level {
+    /// A bug fix.
+    Patch,
+    /// A new feature.
+    Minor,
+    /// A breaking change.
+    Major,
+}
+
+impl From<Level> for BumpKind {
+    fn from(level: Level) -> Self {
+        match level {
+            Level::Patch => BumpKind::Patch,
+            Level::Minor => BumpKind::Minor,
+            Level::Major => BumpKind::Major,
+        }
+    }
+}
+
+impl Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Level::Patch => "patch",
+            Level::Minor => "minor",
+            Level::Major => "major",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl FromStr for Level {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "patch" => Ok(Level::Patch),
+            "minor" => Ok(Level::Minor),
+            "major" => Ok(Level::Major),
+            _ => Err(anyhow!("{input} is not a bump level; expected major, minor, or patch")),
+        }
+    }
+}
+
+/// Parse a version that may carry a leading `v` or `V`, as git tags often do.
+/// Returns the prefix, which is empty if there wasn't one, alongside the version.
+pub fn parse_prefixed(input: &str) -> anyhow::Result<(String, Version)> {
+    let trimmed = input.trim();
+    let (prefix, rest) = match trimmed.strip_prefix(['v', 'V']) {
+        Some(rest) => (&trimmed[..1], rest),
+        None => ("", trimmed),
+    };
+    let version = Version::parse(rest).map_err(|e| error::Error::parse(trimmed, e))?;
+    Ok((prefix.to_string(), version))
+}
+
+/// Leniently turn sloppy input into a valid version, the way node-semver's `coerce`
+/// does. Anything before the first number is skipped, missing minor and patch
+/// numbers are filled in with 0, numbers past the third are dropped, and leading
+/// zeros are removed, so `1.2`, `1.02.3.4`, and `release-1.2` become `1.2.0`,
+/// `1.2.3`, and `1.2.0`. A pre-release or build identifier that follows is kept if
+/// it's valid and dropped if not. Returns the prefix, which is `v` or `V` if that's
+/// all that came before the first number, alongside the version.
+pub fn coerce(input: &str) -> anyhow::Result<(String, Version)> {
+    let trimmed = input.trim();
+    let start = trimmed
+        .find(|c: char| c.is_ascii_digit())
+        .ok_or_else(|| error::Error::invalid(trimmed, format!("`{trimmed}` has no version number in it")))?;
+    let prefix = match &trimmed[..start] {
+        p @ ("v" | "V") => p,
+        _ => "",
+    };
+    let rest = &trimmed[start..];
+
+    let mut numbers = [0u64; 3];
+    let mut end = 0;
+    for (idx, part) in rest.split('.').enumerate() {
+        let digits = part.len() - part.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        if digits == 0 {
+            break;
+        }
+        if let Some(slot) = numbers.get_mut(idx) {
+            *slot = part[..digits].parse().map_err(|_| {
+                error::Error::invalid(
+                    trimmed,
+                    format!("`{}` is too large to be a version number", &part[..digits]),
+                )
+            })?;
+        }
+        end += digits;
+        if digits < part.len() {
+            break;
+        }
+        end += 1;
+    }
+    let end = end.min(rest.len());
+    let mut version = Version::new(numbers[0], numbers[1], numbers[2]);
+
+    let suffix = rest[end..].trim_end_matches('.');
+    let (pre, build) = match suffix.split_once('+') {
+        Some((pre, build)) => (pre, build),
+        None => (suffix, ""),
+    };
+    if let Some(pre) = pre.strip_prefix('-') {
+        version.pre = Prerelease::new(pre).unwrap_or(Prerelease::EMPTY);
+    }
+    version.build = BuildMetadata::new(build).unwrap_or(BuildMetadata::EMPTY);
+    Ok((prefix.to_string(), version))
+}
+
+/// Every version that could reasonably follow the given one, alongside the bump that
+/// produces it: the next major, minor, and patch versions, a pre-release of each
+/// in every channel, and, for a pre-release, its next count, its promotions to later
+/// channels, and its release.
+pub fn candidates<S: AsRef<str>>(previous: &Version, channels: &[S]) -> anyhow::Result<Vec<(BumpKind, Version)>> {
+    let mut kinds = vec![BumpKind::Major, BumpKind::Minor, BumpKind::Patch];
+    for channel in channels {
+        for level in [Level::Major, Level::Minor, Level::Patch] {
+            kinds.push(BumpKind::Pre(level, channel.as_ref().to_string()));
+        }
+    }
+    if !previous.pre.is_empty() {
+        kinds.push(BumpKind::Prerelease(String::new()));
+        let pre = previous.pre.to_string();
+        let current = channels.iter().position(|c| c.as_ref() == strip_counter(&pre));
+        if let Some(current) = current {
+            for channel in &channels[current + 1..] {
+                kinds.push(BumpKind::Promote {
+                    channels: channels.iter().map(|c| c.as_ref().to_string()).collect(),
+                    to: Some(channel.as_ref().to_string()),
+                });
+            }
+        }
+        kinds.push(BumpKind::Release);
+    }
+    kinds
+        .into_iter()
+        .map(|kind| bump(previous, &kind).map(|next| (kind, next)))
+        .collect()
+}
+
+/// The most significant part that differs between two versions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Difference {
+    /// Only the build metadata differs.
+    Build,
+    /// The pre-release identifiers differ.
+    Prerelease,
+    /// The patch numbers differ.
+    Patch,
+    /// The minor numbers differ.
+    Minor,
+    /// The major numbers differ.
+    Major,
+}
+
+impl Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Difference::Build => "build",
+            Difference::Prerelease => "prerelease",
+            Difference::Patch => "patch",
+            Difference::Minor => "minor",
+            Difference::Major => "major",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Work out the most significant part that differs between two versions, or `None`
+/// if they're identical. The order of the versions doesn't matter.
+pub fn diff(left: &Version, right: &Version) -> Option<Difference> {
+    if left.major != right.major {
+        Some(Difference::Major)
+    } else if left.minor != right.minor {
+        Some(Difference::Minor)
+    } else if left.patch != right.patch {
+        Some(Difference::Patch)
+    } else if left.pre != right.pre {
+        Some(Difference::Prerelease)
+    } else if left.build != right.build {
+        Some(Difference::Build)
+    } else {
+        None
+    }
+}
+
+/// Whether the next version could have been released straight after the previous
+/// one: it must come after it, and be a major, minor, or patch bump of it or a
+/// pre-release of one. If the previous version is a pre-release, a later
+/// pre-release or the release of the same version also counts. So `1.2.3` can be
+/// followed by `1.2.4`, `1.3.0-rc.1`, or `2.0.0`, but not by `1.2.5` or `1.4.0`.
+pub fn is_successor(previous: &Version, next: &Version) -> bool {
+    if next.cmp_precedence(previous) != Ordering::Greater {
+        return false;
+    }
+    let (major, minor, patch) = (previous.major, previous.minor, previous.patch);
+    let steps = [
+        (major.saturating_add(1), 0, 0),
+        (major, minor.saturating_add(1), 0),
+        (major, minor, patch.saturating_add(1)),
+    ];
+    let release = (next.major, next.minor, next.patch);
+    steps.contains(&release) || (!previous.pre.is_empty() && release == (major, minor, patch))
+}
+
+/// The highest version a project is willing to release, such as `0` for a
+/// project that must stay on 0.x. A ceiling can leave out components: `0`
+/// admits every `0.x.y`, and `1.4` admits everything up to any `1.4.z`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ceiling {
+    version: Version,
+    precision: usize,
+    text: String,
+}
+
+impl FromStr for Ceiling {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let input = input.trim();
+        let core = input.split(['-', '+']).next().unwrap_or_default();
+        let precision = core.split('.').count();
+        let version = match precision {
+            1 | 2 if core.len() == input.len() => Version::parse(&format!("{input}{}", ".0".repeat(3 - precision))),
+            _ => Version::parse(input),
+        }
+        .with_context(|| format!("`{input}` is not a valid ceiling"))?;
+        Ok(Self {
+            version,
+            precision,
+            text: input.to_string(),
+        })
+    }
+}
+
+impl Display for Ceiling {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+impl Ceiling {
+    /// Whether the given version is at or below this ceiling.
+    pub fn admits(&self, version: &Version) -> bool {
+        match self.precision {
+            1 => version.major <= self.version.major,
+            2 => (version.major, version.minor) <= (self.version.major, self.version.minor),
+            _ => version.cmp_precedence(&self.version) != std::cmp::Ordering::Greater,
+        }
+    }
+}
+
+/// A series of releases named by a maintenance branch, such as `release/1.4.x` for
+/// the `1.4.z` versions or `release/2.x` for the `2.y.z` versions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Series {
+    major: u64,
+    minor: Option<u64>,
+}
+
+impl Series {
+    /// The series a branch name ends in, allowing a `v` and a trailing `.x` or `.*`, as
+    /// in `release/1.4.x`, `support/v2`, or `maint-1.4`. Returns `None` for a branch
+    /// that doesn't name one.
+    pub fn from_branch(branch: &str) -> Option<Self> {
+        let name = branch.rsplit(['/', '-']).next()?;
+        let name = name.strip_prefix('v').unwrap_or(name);
+        let name = name
+            .strip_suffix(".x")
+            .or_else(|| name.strip_suffix(".X"))
+            .or_else(|| name.strip_suffix(".*"))
+            .unwrap_or(name);
+        let numbers: Vec<u64> = name.split('.').map(|n| n.parse().ok()).collect::<Option<_>>()?;
+        match numbers[..] {
+            [major] => Some(Self { major, minor: None }),
+            [major, minor] => Some(Self {
+                major,
+                minor: Some(minor),
+            }),
+            _ => None,
+        }
+    }
+
+    /// Whether the version belongs to this series.
+    pub fn contains(&self, version: &Version) -> bool {
+        version.major == self.major && self.minor.is_none_or(|minor| version.minor == minor)
+    }
+
+    /// The first version of the series, as in `1.4.0`.
+    pub fn base(&self) -> Version {
+        Version::new(self.major, self.minor.unwrap_or(0), 0)
+    }
+
+    /// A glob matching the tags of the series' versions, as in `v1.4.*`.
+    pub fn tag_pattern(&self, prefix: &str) -> String {
+        match self.minor {
+            Some(minor) => format!("{prefix}{}.{minor}.*", self.major),
+            None => format!("{prefix}{}.*", self.major),
+        }
+    }
+}
+
+impl Display for Series {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.minor {
+            Some(minor) => write!(f, "{}.{minor}.x", self.major),
+            None => write!(f, "{}.x", self.major),
+        }
+    }
+}
+
+/// Settings that adjust how bumps behave.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Options {
+    /// The separator to put between an identifier and a newly added counter, as
+    /// in `alpha.1` or `alpha-1`. Must be one of `.` or `-`.
+    pub separator: char,
+    /// Carry the pre-release identifier over on major, minor, and patch bumps, so
+    /// `1.2.3-alpha.1` becomes `2.0.0-alpha.1` instead of `2.0.0`.
+    pub keep_prerelease: bool,
+    /// Carry the build metadata over on major, minor, and patch bumps.
+    pub keep_build: bool,
+    /// How far to count on major, minor, patch, and pre-release bumps, so a patch
+    /// bump by 3 turns `1.0.0` into `1.0.3`. Must be at least 1.
+    pub by: u64,
+    /// Treat 0.x versions the way cargo does, where a breaking change bumps the minor
+    /// version and a feature bumps the patch version. Only affects [`BumpKind::Change`].
+    pub zerover: bool,
+    /// Which number counts up when an existing pre-release is bumped, for identifiers
+    /// with more than one, such as `rc.1.post.3`.
+    pub segment: Segment,
+    /// Zero-pad pre-release counters to this many digits, as in `alpha-001`. Padded
+    /// counters are joined to their identifier with a `-`, since semver doesn't allow
+    /// leading zeros in a numeric identifier.
+    pub pad: Option<usize>,
+    /// Carry the pre-release counter over when a pre-major, pre-minor, or pre-patch bump
+    /// stays in the same channel, so `1.2.3-rc.9` becomes `1.2.4-rc.10` instead of
+    /// starting over at `1.2.4-rc.1`.
+    pub continue_counter: bool,
+    /// Stamp pre-releases with this date instead of counting them, as in
+    /// `nightly.20240519`. A second pre-release on the same date gets a counter after
+    /// the date, as in `nightly.20240519.1`.
+    pub pre_date: Option<String>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            separator: '.',
+            keep_prerelease: false,
+            keep_build: false,
+            by: 1,
+            zerover: false,
+            segment: Segment::Last,
+            pad: None,
+            continue_counter: false,
+            pre_date: None,
+        }
+    }
+}
+
+/// Which of the numbers in a pre-release counts up when it's bumped.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Segment {
+    /// The first numeric identifier, as in `rc.1.post.3` to `rc.2.post.3`.
+    First,
+    /// The number at the end, as in `rc.1.post.3` to `rc.1.post.4`.
+    #[default]
+    Last,
+    /// The numeric identifier at this position, counting numeric identifiers only and
+    /// starting from 1.
+    Nth(usize),
+}
+
+impl Display for Segment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Segment::First => write!(f, "first"),
+            Segment::Last => write!(f, "last"),
+            Segment::Nth(n) => write!(f, "{n}"),
+        }
+    }
+}
+
+impl FromStr for Segment {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "first" => Ok(Segment::First),
+            "last" => Ok(Segment::Last),
+            _ => match input.parse::<usize>() {
+                Ok(0) | Err(_) => Err(anyhow!(
+                    "{input} is not a segment; expected first, last, or a position counting from 1"
+                )),
+                Ok(n) => Ok(Segment::Nth(n)),
+            },
+        }
+    }
+}
+
+/// Bump the given version as requested, returning the next version.
+pub fn bump(previous: &Version, kind: &BumpKind) -> anyhow::Result<Version> {
+    bump_with(previous, kind, &Options::default())
+}
+
+/// Bump the given version as requested with the given settings, returning the next version.
+pub fn bump_with(previous: &Version, kind: &BumpKind, options: &Options) -> anyhow::Result<Version> {
+    if !SEPARATORS.contains(&options.separator) {
+        return Err(anyhow!(
+            "`{}` is not a valid separator; use one of `.` or `-`",
+            options.separator
+        ));
+    }
+    if options.by == 0 {
+        return Err(anyhow!("Bumping by 0 would leave the version where it is."));
+    }
+    let next = match kind {
+        BumpKind::Major | BumpKind::Minor | BumpKind::Patch => {
+            let too_large = || anyhow!("Bumping {previous} by {} would overflow.", options.by);
+            let mut next = match kind {
+                BumpKind::Major => Version::new(previous.major.checked_add(options.by).ok_or_else(too_large)?, 0, 0),
+                BumpKind::Minor => Version::new(
+                    previous.major,
+                    previous.minor.checked_add(options.by).ok_or_else(too_large)?,
+                    0,
+                ),
+                _ => Version::new(
+                    previous.major,
+                    previous.minor,
+                    previous.patch.checked_add(options.by).ok_or_else(too_large)?,
+                ),
+            };
+            if options.keep_prerelease {
+                next.pre = previous.pre.clone();
+            }
+            if options.keep_build {
+                next.build = previous.build.clone();
+            }
+            next
+        }
+        BumpKind::Prerelease(tag) => prerelease_with(previous, tag.as_str(), options)?,
+        BumpKind::Build(tag) => build_with(previous, tag.as_str(), options)?,
+        BumpKind::Pre(level, tag) => pre_with(previous, *level, tag.as_str(), options)?,
+        BumpKind::Promote { channels, to } => promote_with(previous, channels, to.as_deref(), options)?,
+        BumpKind::Release => release(previous),
+        BumpKind::Snapshot => snapshot(previous)?,
+        BumpKind::Set(version) => version.clone(),
+        BumpKind::Down(level) => down(previous, *level)?,
+        BumpKind::Rollback => rollback(previous)?,
+        BumpKind::Change(_) | BumpKind::Channel(..) => bump_with(previous, &kind.resolve(previous, options), options)?,
+    };
+    Ok(next)
+}
+
+/// Increment the major version.
+pub fn major(previous: &Version) -> Version {
+    Version::new(previous.major + 1, 0, 0)
+}
+
+/// Increment the minor version.
+pub fn minor(previous: &Version) -> Version {
+    Version::new(previous.major, previous.minor + 1, 0)
+}
+
+/// Increment the patch version.
+pub fn patch(previous: &Version) -> Version {
+    Version::new(previous.major, previous.minor, previous.patch + 1)
+}
+
+/// Bump the given level, then start a new pre-release of the result with a
+/// count of 1. If no tag is given, the name of the previous pre-release
+/// identifier is re-used, so `1.2.3-rc.4` becomes `2.0.0-rc.1` for a major bump.
+/// With [`Options::continue_counter`], staying in the same channel counts on from
+/// the previous pre-release instead.
+pub fn pre(previous: &Version, level: Level, tag: &str) -> anyhow::Result<Version> {
+    pre_with(previous, level, tag, &Options::default())
+}
+
+fn pre_with(previous: &Version, level: Level, tag: &str, options: &Options) -> anyhow::Result<Version> {
+    let too_large = || anyhow!("Bumping {previous} by 1 would overflow.");
+    let base = match level {
+        Level::Major => Version::new(previous.major.checked_add(1).ok_or_else(too_large)?, 0, 0),
+        Level::Minor => Version::new(previous.major, previous.minor.checked_add(1).ok_or_else(too_large)?, 0),
+        Level::Patch => Version::new(
+            previous.major,
+            previous.minor,
+            previous.patch.checked_add(1).ok_or_else(too_large)?,
+        ),
+    };
+    let previous_pre = previous.pre.to_string();
+    let tag = if tag.is_empty() && options.pre_date.is_some() {
+        undated(&previous_pre)
+    } else if tag.is_empty() {
+        strip_counter(&previous_pre)
+    } else {
+        tag
+    };
+    if tag.is_empty() {
+        return Err(anyhow!(
+            "The current version does not have a prerelease suffix and you did not provide one."
+        ));
+    }
+    if options.continue_counter && !previous.pre.is_empty() && strip_counter(&previous_pre) == tag {
+        let mut carried = base;
+        carried.pre = previous.pre.clone();
+        return prerelease_with(&carried, "", options);
+    }
+    prerelease_with(&base, tag, options)
+}
+
+/// Remove the counter from the end of an identifier, along with its separator,
+/// so `alpha.3` and `alpha3` both become `alpha`.
+pub(crate) fn strip_counter(identifier: &str) -> &str {
+    let without_digits = identifier.trim_end_matches(|c: char| c.is_ascii_digit());
+    if without_digits.len() == identifier.len() || without_digits.is_empty() {
+        return identifier;
+    }
+    without_digits.trim_end_matches(SEPARATORS)
+}
+
+/// Move a pre-release to a later channel, starting its count over at 1. With no
+/// target channel, move to the next channel in order; promoting past the last
+/// channel drops the pre-release entirely. Moving to an earlier channel, or to
+/// the channel the version is already in, is an error.
+pub fn promote<S: AsRef<str>>(previous: &Version, channels: &[S], to: Option<&str>) -> anyhow::Result<Version> {
+    promote_with(previous, channels, to, &Options::default())
+}
+
+fn promote_with<S: AsRef<str>>(
+    previous: &Version,
+    channels: &[S],
+    to: Option<&str>,
+    options: &Options,
+) -> anyhow::Result<Version> {
+    let channels: Vec<&str> = channels.iter().map(|c| c.as_ref()).collect();
+    if previous.pre.is_empty() {
+        return Err(anyhow!(
+            "{previous} is not a pre-release, so there is nothing to promote."
+        ));
+    }
+    let pre = previous.pre.to_string();
+    let current = strip_counter(&pre);
+    let position = channels.iter().position(|c| *c == current).ok_or_else(|| {
+        anyhow!(
+            "The pre-release channel `{current}` is not one of the known channels: {}",
+            channels.join(", ")
+        )
+    })?;
+
+    let target = match to {
+        Some(to) => {
+            let target = channels
+                .iter()
+                .position(|c| *c == to)
+                .ok_or_else(|| anyhow!("`{to}` is not one of the known channels: {}", channels.join(", ")))?;
+            if target <= position {
+                return Err(anyhow!(
+                    "Refusing to move {previous} from `{current}` back to `{to}`; channels only move forward."
+                ));
+            }
+            target
+        }
+        None => position + 1,
+    };
+    match channels.get(target) {
+        Some(channel) => prerelease_with(&release(previous), channel, options),
+        None => Ok(release(previous)),
+    }
+}
+
+/// Strip the pre-release and build identifiers, so `1.2.3-rc.4+abc` becomes `1.2.3`.
+pub fn release(previous: &Version) -> Version {
+    Version::new(previous.major, previous.minor, previous.patch)
+}
+
+/// Start the next development version after a release the way Maven does, by bumping
+/// the patch version and marking it `SNAPSHOT`, so `1.2.3` becomes `1.2.4-SNAPSHOT`. A
+/// snapshot already is the next development version, so it's returned as it is, with
+/// any build metadata dropped. Any other pre-release is an error: `SNAPSHOT` sorts
+/// before lowercase identifiers like `rc.1`, so it has to be released first.
+pub fn snapshot(previous: &Version) -> anyhow::Result<Version> {
+    let mut next = match previous.pre.as_str() {
+        "" => patch(previous),
+        "SNAPSHOT" => release(previous),
+        _ => {
+            return Err(anyhow!(
+                "{previous} is a pre-release; release it before starting the next snapshot."
+            ))
+        }
+    };
+    next.pre = Prerelease::new("SNAPSHOT")?;
+    Ok(next)
+}
+
+/// Decrement the given level, zeroing the levels below it and dropping any pre-release
+/// and build identifiers, so `1.3.2-rc.1` becomes `1.2.0` for a minor step down.
+/// Stepping a level down past zero is an error.
+pub fn down(previous: &Version, level: Level) -> anyhow::Result<Version> {
+    let below_zero = || anyhow!("The {level} version of {previous} is already 0, so it can't go any lower.");
+    let next = match level {
+        Level::Major => Version::new(previous.major.checked_sub(1).ok_or_else(below_zero)?, 0, 0),
+        Level::Minor => Version::new(previous.major, previous.minor.checked_sub(1).ok_or_else(below_zero)?, 0),
+        Level::Patch => Version::new(
+            previous.major,
+            previous.minor,
+            previous.patch.checked_sub(1).ok_or_else(below_zero)?,
+        ),
+    };
+    Ok(next)
+}
+
+/// Work out the version before this one. A pre-release with a counter above 1 has
+/// its counter decremented, so `1.2.3-rc.3` becomes `1.2.3-rc.2`; the first
+/// pre-release in a series could have come from anywhere, so that's an error. A
+/// release steps down its lowest non-zero level, so `1.3.0` becomes `1.2.0`. The
+/// levels below that can't be recovered from the version alone and are left at zero.
+pub fn rollback(previous: &Version) -> anyhow::Result<Version> {
+    if !previous.pre.is_empty() {
+        let pre = previous.pre.as_str();
+        let name = pre.trim_end_matches(|c: char| c.is_ascii_digit());
+        let count: u64 = pre[name.len()..].parse().unwrap_or(0);
+        if count <= 1 {
+            return Err(anyhow!(
+                "{previous} is the first pre-release in its series, so there's no telling what came before it."
+            ));
+        }
+        let mut next = release(previous);
+        next.pre = Prerelease::new(&format!("{name}{}", count - 1))?;
+        return Ok(next);
+    }
+    if previous.patch > 0 {
+        down(previous, Level::Patch)
+    } else if previous.minor > 0 {
+        down(previous, Level::Minor)
+    } else if previous.major > 0 {
+        down(previous, Level::Major)
+    } else {
+        Err(anyhow!(
+            "{previous} is as low as versions go, so there's nothing to roll back to."
+        ))
+    }
+}
+
+/// A version component with an identifier we know how to increment: either a
+/// pre-release or a build metadata segment.
+pub trait Incrementable: Display {
+    /// Construct a new instance of this component from a string.
+    fn create_new(input: String) -> anyhow::Result<Box<Self>>;
+}
+
+impl Incrementable for Prerelease {
+    fn create_new(input: String) -> anyhow::Result<Box<Prerelease>> {
+        match Prerelease::from_str(input.as_str()) {
+            Ok(v) => Ok(Box::new(v)),
+            Err(e) => Err(anyhow::Error::from(e)),
+        }
+    }
+}
+
+impl Incrementable for BuildMetadata {
+    fn create_new(input: String) -> anyhow::Result<Box<BuildMetadata>> {
+        match BuildMetadata::from_str(input.as_str()) {
+            Ok(v) => Ok(Box::new(v)),
+            Err(e) => Err(anyhow::Error::from(e)),
+        }
+    }
+}
+
+/// Increment the passed-in separator plus maybe-number.
+fn increment_identifier(suffix: &str, default_separator: char) -> anyhow::Result<String> {
+    let mut characters = suffix.chars().peekable();
+
+    if let Some(maybe_sep) = characters.peek() {
+        if SEPARATORS.contains(maybe_sep) {
+            let separator = characters.next().expect("but we just checked this character!");
+            let remainder: String = characters.collect();
+            let number = remainder.parse::<u64>()?;
+            return Ok(format!("{separator}{}", number + 1));
+        } else if maybe_sep.is_ascii_digit() {
+            let number = suffix.parse::<u64>()?;
+            // preserve lack of separator
+            return Ok(format!("{}", number + 1));
+        }
+    }
+    Ok(format!("{suffix}{default_separator}1"))
+}
+
+/// Count up one of the numeric identifiers in a dotted identifier, leaving the others as
+/// they are, so the first segment of `rc.1.post.3` counts up to `rc.2.post.3`.
+fn increment_segment(previous: &str, segment: Segment) -> anyhow::Result<String> {
+    let mut parts: Vec<String> = previous.split('.').map(str::to_string).collect();
+    let numbers: Vec<usize> = parts
+        .iter()
+        .enumerate()
+        .filter(|(_, part)| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()))
+        .map(|(i, _)| i)
+        .collect();
+    let position = match segment {
+        Segment::First => numbers.first(),
+        Segment::Last => numbers.last(),
+        Segment::Nth(n) => numbers.get(n.saturating_sub(1)),
+    };
+    let Some(&position) = position else {
+        let which = match segment {
+            Segment::Nth(n) => format!("number {n}"),
+            _ => format!("{segment} one"),
+        };
+        return Err(anyhow!(
+            "`{previous}` has {} numeric identifiers, so there's no {which} to count up.",
+            numbers.len()
+        ));
+    };
+    let number: u64 = parts[position].parse()?;
+    parts[position] = number
+        .checked_add(1)
+        .ok_or_else(|| anyhow!("Counting up `{previous}` would overflow."))?
+        .to_string();
+    Ok(parts.join("."))
+}
+
+/// Update the identifier for this version number.
+/// If we don't have an existing identifier, we add one.
+/// If we have an existing identifier that matches a passed-in tag, we increment.
+/// If we have an existing identifier and no passed-in tag, we increment existing.
+/// If we have no existing identifier and no tag, we report an input error to the user.
+pub fn increment<T: Incrementable>(input: &T, tag: &str) -> anyhow::Result<Box<T>> {
+    increment_with(input, tag, &Options::default())
+}
+
+fn increment_with<T: Incrementable>(input: &T, tag: &str, options: &Options) -> anyhow::Result<Box<T>> {
+    let previous = input.to_string();
+    let sep = options.separator;
+
+    let counting_up = !previous.is_empty() && (tag.is_empty() || previous.starts_with(tag));
+    let identifier = if counting_up && options.segment != Segment::Last {
+        increment_segment(&previous, options.segment)?
+    } else if tag.is_empty() && !previous.is_empty() {
+        if let Some(idx) = previous.rfind(SEPARATORS) {
+            let split = previous.split_at(idx);
+            let incremented = increment_identifier(split.1, sep)?;
+            format!("{}{incremented}", split.0)
+        } else {
+            match increment_identifier(previous.to_string().as_str(), sep) {
+                Ok(v) => v,
+                Err(_) => {
+                    format!("{previous}{sep}1")
+                }
+            }
+        }
+    } else if !tag.is_empty() && tag != previous {
+        let last = tag.chars().last().unwrap_or_default();
+        if last.is_ascii_digit() {
+            tag.to_owned()
+        } else {
+            format!("{tag}{sep}1")
+        }
+    } else if !tag.is_empty() && previous.starts_with(tag) {
+        let remainder = previous.to_string().replace(tag, "");
+        let incremented = increment_identifier(remainder.as_str(), sep)?;
+        format!("{tag}{incremented}")
+    } else if !tag.is_empty() {
+        format!("{tag}{sep}1")
+    } else if !previous.is_empty() {
+        increment_identifier(previous.to_string().as_str(), sep)?
+    } else {
+        return Err(anyhow!(
+            "The current version does not have a prerelease suffix and you did not provide one."
+        ));
+    };
+
+    let next = T::create_new(identifier)?;
+    Ok(next)
+}
+
+/// Replace or add a prerelease identifier, or increment the number at the
+/// end of an existing prerelease identifier.
+pub fn prerelease(previous: &Version, tag: &str) -> anyhow::Result<Version> {
+    prerelease_with(previous, tag, &Options::default())
+}
+
+fn prerelease_with(previous: &Version, tag: &str, options: &Options) -> anyhow::Result<Version> {
+    let mut next = Version::new(previous.major, previous.minor, previous.patch);
+    if let Some(date) = options.pre_date.as_deref() {
+        next.pre = dated(&previous.pre, tag, date)?;
+        return Ok(next);
+    }
+    if let Some(width) = options.pad {
+        next.pre = padded(&previous.pre, tag, width, options.by)?;
+        return Ok(next);
+    }
+    let mut identifier = increment_with(&previous.pre, tag, options)?;
+    // Counting further re-uses whatever identifier the first step settled on.
+    for _ in 1..options.by {
+        identifier = increment_with(identifier.as_ref(), "", options)?;
+    }
+    next.pre = *identifier;
+    Ok(next)
+}
+
+/// Stamp a pre-release with a date, as in `nightly.20240519`, counting up from
+/// `nightly.20240519.1` when the previous pre-release has the same date.
+fn dated(previous: &Prerelease, tag: &str, date: &str) -> anyhow::Result<Prerelease> {
+    let previous = previous.as_str();
+    let name = if tag.is_empty() { undated(previous) } else { tag };
+    if name.is_empty() {
+        return Err(anyhow!(
+            "The current version does not have a prerelease suffix and you did not provide one."
+        ));
+    }
+    let stamped = format!("{name}.{date}");
+    let identifier = match previous.strip_prefix(stamped.as_str()) {
+        Some("") => format!("{stamped}.1"),
+        Some(rest) => match rest.strip_prefix('.').and_then(|count| count.parse::<u64>().ok()) {
+            Some(count) => format!("{stamped}.{}", count + 1),
+            None => stamped,
+        },
+        None => stamped,
+    };
+    Prerelease::new(&identifier).with_context(|| format!("`{identifier}` is not a valid pre-release"))
+}
+
+/// The name of a pre-release without its date and counter: the identifiers before
+/// the first number, so `nightly.20240519.2` becomes `nightly`.
+pub(crate) fn undated(identifier: &str) -> &str {
+    let end = identifier
+        .split('.')
+        .take_while(|part| !part.bytes().all(|b| b.is_ascii_digit()))
+        .map(|part| part.len() + 1)
+        .sum::<usize>();
+    identifier[..end.min(identifier.len())].trim_end_matches('.')
+}
+
+/// Count a pre-release up with its counter zero-padded to the given width, as in
+/// `alpha-009` to `alpha-010`. Padded counters are alphanumeric identifiers, which
+/// compare as text, so they only order correctly while every counter in a series has
+/// the same width. Counting up a counter of another width, or past the width, is an
+/// error rather than a silent break in the ordering.
+fn padded(previous: &Prerelease, tag: &str, width: usize, by: u64) -> anyhow::Result<Prerelease> {
+    if width == 0 {
+        return Err(anyhow!("Counters can't be padded to 0 digits."));
+    }
+    let previous = previous.as_str();
+    // The previous identifier and its counter, padded or not, as in `alpha-009` or `alpha.9`.
+    let stem = previous.trim_end_matches(|c: char| c.is_ascii_digit());
+    let digits = &previous[stem.len()..];
+    let channel = stem.trim_end_matches(SEPARATORS);
+    let name = if tag.is_empty() { channel } else { tag };
+    if name.is_empty() {
+        return Err(anyhow!(
+            "The current version does not have a prerelease suffix and you did not provide one."
+        ));
+    }
+    let count = if name == channel && !digits.is_empty() {
+        if digits.len() != width || !stem.ends_with('-') {
+            return Err(anyhow!(
+                "The counter in `{previous}` isn't padded to {width} digits like `{channel}-{:0width$}`; \
+                 changing the padding partway through a series breaks its ordering.",
+                1
+            ));
+        }
+        let count: u64 = digits.parse()?;
+        count
+            .checked_add(by)
+            .ok_or_else(|| anyhow!("Counting up `{previous}` would overflow."))?
+    } else {
+        by
+    };
+    let counter = format!("{count:0width$}");
+    if counter.len() > width {
+        return Err(anyhow!(
+            "The counter {count} doesn't fit in {width} digits, and a wider counter would sort before the ones \
+             that came before it."
+        ));
+    }
+    Ok(Prerelease::new(&format!("{name}-{counter}"))?)
+}
+
+/// This works just like prerelease, only it operates on the build segment.
+pub fn build(previous: &Version, tag: &str) -> anyhow::Result<Version> {
+    build_with(previous, tag, &Options::default())
+}
+
+fn build_with(previous: &Version, tag: &str, options: &Options) -> anyhow::Result<Version> {
+    let mut next = Version::new(previous.major, previous.minor, previous.patch);
+    next.pre = previous.pre.clone();
+    let identifier = increment_with(&previous.build, tag, options)?;
+    next.build = *identifier;
+    Ok(next)
+}
+
+#[cfg(test)]
+mod tests {
+    use semver::Version;
+
+    use super::*;
+
+    #[test]
+    fn major_bump() {
+        let input = Version::parse("1.0.0").expect("test data must be valid semver");
+        let next = major(&input);
+        assert_eq!(next.major, input.major + 1);
+        let input = Version::parse("3.3.3").expect("test data must be valid semver");
+        let next = major(&input);
+        assert_eq!(next.major, input.major + 1);
+        assert_eq!(next.minor, 0);
+        assert_eq!(next.patch, 0);
+        let input = Version::parse("747.341.321-alpha1").expect("test data must be valid semver");
+        let next = major(&input);
+        assert!(next.pre.is_empty());
+    }
+
+    #[test]
+    fn minor_bump() {
+        // boring but I will write a test
+        let input = Version::parse("1.2.3").expect("test data must be valid semver");
+        let next = minor(&input);
+        assert_eq!(next.major, input.major);
+        assert_eq!(next.minor, input.minor + 1);
+        assert_eq!(next.patch, 0);
+    }
+
+    #[test]
+    fn patch_bump() {
+        // boring but I will write a test
+        let input = Version::parse("1.2.3").expect("test data must be valid semver");
+        let next = patch(&input);
+        assert_eq!(next.major, input.major);
+        assert_eq!(next.minor, input.minor);
+        assert_eq!(next.patch, input.patch + 1);
+    }
+
+    #[test]
+    fn pre_bumps() {
+        let input = Version::parse("1.2.3").expect("test data must be valid semver");
+        let next = pre(&input, Level::Major, "rc").expect("we expected premajor to work");
+        assert_eq!(next.to_string(), "2.0.0-rc.1");
+        let next = pre(&input, Level::Minor, "beta").expect("we expected preminor to work");
+        assert_eq!(next.to_string(), "1.3.0-beta.1");
+        let next = pre(&input, Level::Patch, "alpha.1").expect("we expected prepatch to work");
+        assert_eq!(next.to_string(), "1.2.4-alpha.1");
+        pre(&input, Level::Patch, "").expect_err("we expected a missing identifier to be an error");
+
+        let input = Version::parse("1.2.3-rc.4+build.5").expect("test data must be valid semver");
+        let next = pre(&input, Level::Major, "").expect("we expected premajor to work");
+        assert_eq!(next.to_string(), "2.0.0-rc.1");
+        let input = Version::parse("1.2.3-beta7").expect("test data must be valid semver");
+        let next = bump(&input, &BumpKind::Pre(Level::Patch, String::new())).expect("we expected prepatch to work");
+        assert_eq!(next.to_string(), "1.2.4-beta.1");
+
+        let input = Version::new(u64::MAX, 0, u64::MAX);
+        let overflow = pre(&input, Level::Major, "rc").expect_err("we expected premajor to overflow");
+        assert_eq!(overflow.to_string(), format!("Bumping {input} by 1 would overflow."));
+        pre(&input, Level::Patch, "rc").expect_err("we expected prepatch to overflow");
+        pre(&input, Level::Minor, "rc").expect("we expected preminor to have room");
+    }
+
+    #[test]
+    fn counter_stripping() {
+        assert_eq!(strip_counter("alpha.3"), "alpha");
+        assert_eq!(strip_counter("ceti-alpha-5"), "ceti-alpha");
+        assert_eq!(strip_counter("beta12"), "beta");
+        assert_eq!(strip_counter("rc"), "rc");
+        assert_eq!(strip_counter("7"), "7");
+    }
+
+    #[test]
+    fn channel_promotion() {
+        let input = Version::parse("1.0.0-alpha.3").expect("test data must be valid semver");
+        let next = promote(&input, &DEFAULT_CHANNELS, None).expect("we expected promotion to work");
+        assert_eq!(next.to_string(), "1.0.0-beta.1");
+        let next = promote(&input, &DEFAULT_CHANNELS, Some("rc")).expect("we expected promotion to work");
+        assert_eq!(next.to_string(), "1.0.0-rc.1");
+        let input = Version::parse("1.0.0-rc.2+build.9").expect("test data must be valid semver");
+        let next = promote(&input, &DEFAULT_CHANNELS, None).expect("we expected promotion to work");
+        assert_eq!(next.to_string(), "1.0.0");
+
+        promote(&input, &DEFAULT_CHANNELS, Some("alpha")).expect_err("we expected moving backward to fail");
+        promote(&input, &DEFAULT_CHANNELS, Some("rc")).expect_err("we expected staying put to fail");
+        let input = Version::parse("1.0.0-nightly.2").expect("test data must be valid semver");
+        promote(&input, &DEFAULT_CHANNELS, None).expect_err("we expected an unknown channel to fail");
+        let next = promote(&input, &["nightly", "preview"], None).expect("we expected custom channels to work");
+        assert_eq!(next.to_string(), "1.0.0-preview.1");
+        let input = Version::parse("1.0.0").expect("test data must be valid semver");
+        promote(&input, &DEFAULT_CHANNELS, None).expect_err("we expected a stable version to fail");
+    }
+
+    #[test]
+    fn release_graduation() {
+        let input = Version::parse("1.2.3-rc.4+abc").expect("test data must be valid semver");
+        assert_eq!(release(&input).to_string(), "1.2.3");
+        let input = Version::parse("1.2.3").expect("test data must be valid semver");
+        assert_eq!(release(&input), input);
+    }
+
+    #[test]
+    fn stepping_down() {
+        let input = Version::parse("2.3.4-rc.1+abc").expect("test data must be valid semver");
+        assert_eq!(
+            down(&input, Level::Major)
+                .expect("we expected to step down")
+                .to_string(),
+            "1.0.0"
+        );
+        assert_eq!(
+            down(&input, Level::Minor)
+                .expect("we expected to step down")
+                .to_string(),
+            "2.2.0"
+        );
+        assert_eq!(
+            down(&input, Level::Patch)
+                .expect("we expected to step down")
+                .to_string(),
+            "2.3.3"
+        );
+        let input = Version::parse("0.3.0").expect("test data must be valid semver");
+        down(&input, Level::Major).expect_err("we expected stepping below zero to be an error");
+        down(&input, Level::Patch).expect_err("we expected stepping below zero to be an error");
+    }
+
+    #[test]
+    fn snapshots() {
+        for (input, expected) in [
+            ("1.2.3", "1.2.4-SNAPSHOT"),
+            ("1.2.3+build.5", "1.2.4-SNAPSHOT"),
+            ("1.3.0-SNAPSHOT", "1.3.0-SNAPSHOT"),
+        ] {
+            let input = Version::parse(input).expect("test data must be valid semver");
+            let next = bump(&input, &BumpKind::Snapshot).expect("we expected the snapshot to work");
+            assert_eq!(next.to_string(), expected);
+            // A snapshot sorts after the release before it and before its own release,
+            // and releasing it gives back that release.
+            assert!(next >= input);
+            let released = bump(&next, &BumpKind::Release).expect("we expected the release to work");
+            assert!(next < released);
+            assert_eq!(released, Version::new(next.major, next.minor, next.patch));
+            assert_eq!(
+                bump(&released, &BumpKind::Snapshot).expect("we expected the snapshot to work"),
+                Version::parse(&format!("{}.{}.{}-SNAPSHOT", next.major, next.minor, next.patch + 1))
+                    .expect("we expected a valid version")
+            );
+        }
+        let input = Version::parse("1.3.0-rc.1").expect("test data must be valid semver");
+        snapshot(&input).expect_err("we expected a snapshot of a pre-release to be an error");
+    }
+
+    #[test]
+    fn rolling_back() {
+        for (input, expected) in [
+            ("1.2.3", "1.2.2"),
+            ("1.3.0", "1.2.0"),
+            ("2.0.0", "1.0.0"),
+            ("1.2.3-rc.3+abc", "1.2.3-rc.2"),
+            ("1.2.3-beta10", "1.2.3-beta9"),
+        ] {
+            let input = Version::parse(input).expect("test data must be valid semver");
+            let next = bump(&input, &BumpKind::Rollback).expect("we expected the rollback to work");
+            assert_eq!(next.to_string(), expected);
+        }
+        for input in ["0.0.0", "1.2.3-rc.1", "1.2.3-rc"] {
+            let input = Version::parse(input).expect("test data must be valid semver");
+            rollback(&input).expect_err("we expected there to be nothing to roll back to");
+        }
+    }
+
+    #[test]
+    fn prerelease_adding() {
+        let input = Version::parse("1.0.0").expect("test data must be valid semver");
+        let next = prerelease(&input, "alpha").expect("we expected the prerelease bump to work");
+        assert_eq!(next.major, input.major);
+        assert_eq!(
+            next.pre,
+            Prerelease::new("alpha.1").expect("test data must be valid semver")
+        );
+        let input = Version::parse("1.0.0-alpha").expect("test data must be valid semver");
+        let next = prerelease(&input, "").expect("we expected the prerelease bump to work");
+        assert_eq!(next.major, input.major);
+        assert_eq!(
+            next.pre,
+            Prerelease::new("alpha.1").expect("test data must be valid semver")
+        );
+        let input = Version::parse("1.0.0-alpha").expect("test data must be valid semver");
+        let next = prerelease(&input, "alpha").expect("we expected the prerelease bump to work");
+        assert_eq!(next.major, input.major);
+        assert_eq!(
+            next.pre,
+            Prerelease::new("alpha.1").expect("test data must be valid semver")
+        );
+    }
+
+    #[test]
+    fn prerelease_bumping() {
+        let input = Version::parse("1.0.0-alpha.1").expect("test data must be valid semver");
+        let next = prerelease(&input, "").expect("we expected the prerelease bump to work");
+        assert_eq!(next.major, input.major);
+        assert_eq!(
+            next.pre,
+            Prerelease::new("alpha.2").expect("test data must be valid semver")
+        );
+        let next = prerelease(&input, "beta").expect("we expected the prerelease bump to work");
+        assert_eq!(
+            next.pre,
+            Prerelease::new("beta.1").expect("test data must be valid semver")
+        );
+        let input = Version::parse("1.0.0-1").expect("test data must be valid semver");
+        let next = prerelease(&input, "").expect("we expected the prerelease bump to work");
+        assert_eq!(next.pre, Prerelease::new("2").expect("test data must be valid semver"));
+    }
+
+    #[test]
+    fn prerelease_error_cases() {
+        let input = Version::parse("1.0.0").expect("test data must be valid semver");
+        prerelease(&input, "").expect_err("we expected an error from this call");
+        prerelease(&input, "+illegal+").expect_err("we expected an error from this call");
+        let input = Version::parse("1.0.0-alpha.four").expect("test data must be valid semver");
+        prerelease(&input, "").expect_err("we expected an error from this call");
+    }
+
+    #[test]
+    fn separator_detection() {
+        let input = Version::parse("1.2.3-ceti-alpha-4").expect("test data must be valid semver");
+        let next = prerelease(&input, "").expect("we expected prerelease() to work");
+        assert_eq!(
+            next.pre,
+            Prerelease::new("ceti-alpha-5").expect("test data must be valid semver")
+        );
+        assert_eq!(next.to_string(), "1.2.3-ceti-alpha-5".to_string());
+
+        let input = Version::parse("1.2.3-ceti-alpha.4").expect("test data must be valid semver");
+        let next = prerelease(&input, "").expect("we expected prerelease() to work");
+        assert_eq!(next.to_string(), "1.2.3-ceti-alpha.5".to_string());
+    }
+
+    #[test]
+    fn build_bump() {
+        let input = Version::parse("1.2.3-four+4").expect("test data must be valid semver");
+        let next = build(&input, "").expect("we expected build() to work");
+        assert_eq!(next.to_string(), "1.2.3-four+5".to_string());
+        let input = Version::parse("1.2.3-ceti-alpha+4").expect("test data must be valid semver");
+        let next = build(&input, "").expect("we expected build() to work");
+        assert_eq!(next.to_string(), "1.2.3-ceti-alpha+5".to_string());
+    }
+
+    #[test]
+    fn bump_dispatch() {
+        let input = Version::parse("1.2.3-four+4").expect("test data must be valid semver");
+        let next = bump(&input, &BumpKind::Major).expect("we expected bump() to work");
+        assert_eq!(next.to_string(), "2.0.0");
+        let next = bump(&input, &BumpKind::Minor).expect("we expected bump() to work");
+        assert_eq!(next.to_string(), "1.3.0");
+        let next = bump(&input, &BumpKind::Patch).expect("we expected bump() to work");
+        assert_eq!(next.to_string(), "1.2.4");
+        let next = bump(&input, &BumpKind::Prerelease("beta".to_string())).expect("we expected bump() to work");
+        assert_eq!(next.to_string(), "1.2.3-beta.1");
+        let next = bump(&input, &BumpKind::Build(String::new())).expect("we expected bump() to work");
+        assert_eq!(next.to_string(), "1.2.3-four+5");
+        let replacement = Version::parse("0.1.0").expect("test data must be valid semver");
+        let next = bump(&input, &BumpKind::Set(replacement.clone())).expect("we expected bump() to work");
+        assert_eq!(next, replacement);
+    }
+
+    #[test]
+    fn prefixed_parsing() {
+        let (prefix, version) = parse_prefixed("v1.2.3\n").expect("we expected a prefixed version to parse");
+        assert_eq!(prefix, "v");
+        assert_eq!(version.to_string(), "1.2.3");
+        let (prefix, version) = parse_prefixed("1.2.3-rc.1").expect("we expected a bare version to parse");
+        assert_eq!(prefix, "");
+        assert_eq!(version.to_string(), "1.2.3-rc.1");
+        parse_prefixed("version1.2.3").expect_err("we expected only a single v to be allowed");
+    }
+
+    #[test]
+    fn coercion() {
+        for (input, prefix, expected) in [
+            ("1", "", "1.0.0"),
+            ("1.2", "", "1.2.0"),
+            ("1.2.3.4", "", "1.2.3"),
+            ("v1.2.0-beta", "v", "1.2.0-beta"),
+            ("1.02-rc.1+abc", "", "1.2.0-rc.1+abc"),
+            ("release-1.2 final", "", "1.2.0"),
+            ("V3. ", "V", "3.0.0"),
+            ("1.2.3-bad_id", "", "1.2.3"),
+        ] {
+            let (found, version) = coerce(input).expect("we expected the input to coerce");
+            assert_eq!(found, prefix);
+            assert_eq!(version.to_string(), expected);
+        }
+        coerce("latest").expect_err("we expected input without a number to be an error");
+    }
+
+    #[test]
+    fn keeping_suffixes() {
+        let input = Version::parse("1.2.3-alpha.1+build.5").expect("test data must be valid semver");
+        let both = Options {
+            keep_prerelease: true,
+            keep_build: true,
+            ..Default::default()
+        };
+        let next = bump_with(&input, &BumpKind::Major, &both).expect("we expected the bump to work");
+        assert_eq!(next.to_string(), "2.0.0-alpha.1+build.5");
+        let build = Options {
+            keep_build: true,
+            ..Default::default()
+        };
+        let next = bump_with(&input, &BumpKind::Patch, &build).expect("we expected the bump to work");
+        assert_eq!(next.to_string(), "1.2.4+build.5");
+        let next = bump_with(&input, &BumpKind::Release, &both).expect("we expected the bump to work");
+        assert_eq!(next.to_string(), "1.2.3");
+    }
+
+    #[test]
+    fn counting_segments() {
+        let input = Version::parse("1.2.3-rc.1.post.3").expect("test data must be valid semver");
+        let counted = |segment: &str, kind: BumpKind| {
+            let options = Options {
+                segment: segment.parse().expect("we expected a valid segment"),
+                ..Default::default()
+            };
+            bump_with(&input, &kind, &options).map(|next| next.to_string())
+        };
+        let prerelease = || BumpKind::Prerelease(String::new());
+        assert_eq!(
+            counted("last", prerelease()).expect("we expected the bump to work"),
+            "1.2.3-rc.1.post.4"
+        );
+        assert_eq!(
+            counted("first", prerelease()).expect("we expected the bump to work"),
+            "1.2.3-rc.2.post.3"
+        );
+        assert_eq!(
+            counted("2", prerelease()).expect("we expected the bump to work"),
+            "1.2.3-rc.1.post.4"
+        );
+        assert_eq!(
+            counted("first", BumpKind::Prerelease("rc".to_string())).expect("we expected the bump to work"),
+            "1.2.3-rc.2.post.3"
+        );
+        // Only counting up an existing pre-release is affected.
+        assert_eq!(
+            counted("first", BumpKind::Prerelease("beta".to_string())).expect("we expected the bump to work"),
+            "1.2.3-beta.1"
+        );
+        assert_eq!(
+            counted("first", BumpKind::Minor).expect("we expected the bump to work"),
+            "1.3.0"
+        );
+        let error = counted("3", prerelease()).expect_err("we expected a missing segment to be an error");
+        assert!(error
+            .to_string()
+            .contains("has 2 numeric identifiers, so there's no number 3"));
+        "0".parse::<Segment>()
+            .expect_err("we expected segments to count from 1");
+        "middle"
+            .parse::<Segment>()
+            .expect_err("we expected an unknown segment to be an error");
+    }
+
+    #[test]
+    fn continuing_counters() {
+        let input = Version::parse("1.2.3-rc.9").expect("test data must be valid semver");
+        let bumped = |kind: BumpKind, continue_counter| {
+            let options = Options {
+                continue_counter,
+                ..Default::default()
+            };
+            bump_with(&input, &kind, &options)
+                .expect("we expected the bump to work")
+                .to_string()
+        };
+        let prepatch = |tag: &str| BumpKind::Pre(Level::Patch, tag.to_string());
+        assert_eq!(bumped(prepatch("rc"), false), "1.2.4-rc.1");
+        assert_eq!(bumped(prepatch("rc"), true), "1.2.4-rc.10");
+        assert_eq!(bumped(prepatch(""), true), "1.2.4-rc.10");
+        assert_eq!(
+            bumped(BumpKind::Pre(Level::Major, "rc".to_string()), true),
+            "2.0.0-rc.10"
+        );
+        // A new channel starts its own count either way.
+        assert_eq!(bumped(prepatch("beta"), true), "1.2.4-beta.1");
+        let release = Version::parse("1.2.3").expect("test data must be valid semver");
+        let options = Options {
+            continue_counter: true,
+            ..Default::default()
+        };
+        let next = bump_with(&release, &prepatch("rc"), &options).expect("we expected the bump to work");
+        assert_eq!(next.to_string(), "1.2.4-rc.1");
+    }
+
+    #[test]
+    fn dating_prereleases() {
+        let dated = |version: &str, kind: BumpKind| {
+            let options = Options {
+                pre_date: Some("20240519".to_string()),
+                ..Default::default()
+            };
+            let input = Version::parse(version).expect("test data must be valid semver");
+            bump_with(&input, &kind, &options).map(|next| next.to_string())
+        };
+        let prerelease = |tag: &str| BumpKind::Prerelease(tag.to_string());
+        assert_eq!(
+            dated("1.2.3", prerelease("nightly")).expect("we expected the bump to work"),
+            "1.2.3-nightly.20240519"
+        );
+        assert_eq!(
+            dated("1.2.3-nightly.20240518", prerelease("")).expect("we expected the bump to work"),
+            "1.2.3-nightly.20240519"
+        );
+        assert_eq!(
+            dated("1.2.3-nightly.20240519", prerelease("nightly")).expect("we expected the bump to work"),
+            "1.2.3-nightly.20240519.1"
+        );
+        assert_eq!(
+            dated("1.2.3-nightly.20240519.1", prerelease("")).expect("we expected the bump to work"),
+            "1.2.3-nightly.20240519.2"
+        );
+        assert_eq!(
+            dated("1.2.3-nightly.20240519.4", BumpKind::Pre(Level::Minor, String::new()))
+                .expect("we expected the bump to work"),
+            "1.3.0-nightly.20240519"
+        );
+        assert_eq!(
+            dated("1.2.3-rc.2", prerelease("nightly")).expect("we expected the bump to work"),
+            "1.2.3-nightly.20240519"
+        );
+        dated("1.2.3", prerelease("")).expect_err("we expected a missing identifier to be an error");
+        assert_eq!(undated("nightly.20240519.2"), "nightly");
+        assert_eq!(undated("dev.nightly"), "dev.nightly");
+        assert_eq!(undated("20240519"), "");
+    }
+
+    #[test]
+    fn padding_counters() {
+        let padded = |version: &str, kind: BumpKind| {
+            let options = Options {
+                pad: Some(3),
+                ..Default::default()
+            };
+            let input = Version::parse(version).expect("test data must be valid semver");
+            bump_with(&input, &kind, &options).map(|next| next.to_string())
+        };
+        let prerelease = |tag: &str| BumpKind::Prerelease(tag.to_string());
+        assert_eq!(
+            padded("1.2.3", prerelease("alpha")).expect("we expected the bump to work"),
+            "1.2.3-alpha-001"
+        );
+        assert_eq!(
+            padded("1.2.3-alpha-009", prerelease("")).expect("we expected the bump to work"),
+            "1.2.3-alpha-010"
+        );
+        assert_eq!(
+            padded("1.2.3-alpha-009", prerelease("alpha")).expect("we expected the bump to work"),
+            "1.2.3-alpha-010"
+        );
+        assert_eq!(
+            padded("1.2.3-alpha-009", prerelease("beta")).expect("we expected the bump to work"),
+            "1.2.3-beta-001"
+        );
+        assert_eq!(
+            padded("1.2.3-alpha-009", BumpKind::Pre(Level::Minor, String::new()))
+                .expect("we expected the bump to work"),
+            "1.3.0-alpha-001"
+        );
+        // Padded counters compare as text, which orders them while they're all the same width.
+        let earlier = Version::parse("1.2.3-alpha-009").expect("test data must be valid semver");
+        let later = Version::parse("1.2.3-alpha-010").expect("test data must be valid semver");
+        assert!(earlier < later);
+
+        let error = padded("1.2.3-alpha.9", prerelease("")).expect_err("we expected mixed padding to be an error");
+        assert!(error.to_string().contains("isn't padded to 3 digits like `alpha-001`"));
+        padded("1.2.3-alpha-09", prerelease("")).expect_err("we expected a narrower counter to be an error");
+        let error =
+            padded("1.2.3-alpha-999", prerelease("")).expect_err("we expected overflowing the width to be an error");
+        assert!(error
+            .to_string()
+            .starts_with("The counter 1000 doesn't fit in 3 digits"));
+        padded("1.2.3", prerelease("")).expect_err("we expected a missing identifier to be an error");
+    }
+
+    #[test]
+    fn bumping_by_more_than_one() {
+        let by = |by| Options {
+            by,
+            ..Default::default()
+        };
+        let input = Version::parse("1.2.3-rc.1").expect("test data must be valid semver");
+        let bumped = |kind: BumpKind, step| {
+            bump_with(&input, &kind, &by(step))
+                .expect("we expected the bump to work")
+                .to_string()
+        };
+        assert_eq!(bumped(BumpKind::Major, 2), "3.0.0");
+        assert_eq!(bumped(BumpKind::Minor, 3), "1.5.0");
+        assert_eq!(bumped(BumpKind::Patch, 3), "1.2.6");
+        assert_eq!(bumped(BumpKind::Prerelease(String::new()), 3), "1.2.3-rc.4");
+        assert_eq!(bumped(BumpKind::Prerelease("beta".to_string()), 3), "1.2.3-beta.3");
+        bump_with(&input, &BumpKind::Patch, &by(0)).expect_err("we expected bumping by 0 to be an error");
+        let huge = Version::new(u64::MAX, 0, 0);
+        bump_with(&huge, &BumpKind::Major, &by(1)).expect_err("we expected overflow to be an error");
+    }
+
+    #[test]
+    fn listing_candidates() {
+        let input = Version::parse("1.2.3").expect("test data must be valid semver");
+        let found = candidates(&input, &["rc"]).expect("we expected to list candidates");
+        let versions: Vec<String> = found.iter().map(|(_, v)| v.to_string()).collect();
+        assert_eq!(
+            versions,
+            vec!["2.0.0", "1.3.0", "1.2.4", "2.0.0-rc.1", "1.3.0-rc.1", "1.2.4-rc.1"]
+        );
+
+        let input = Version::parse("1.3.0-beta.2").expect("test data must be valid semver");
+        let found = candidates(&input, &DEFAULT_CHANNELS).expect("we expected to list candidates");
+        let versions: Vec<String> = found.iter().skip(12).map(|(_, v)| v.to_string()).collect();
+        assert_eq!(versions, vec!["1.3.0-beta.3", "1.3.0-rc.1", "1.3.0"]);
+        assert_eq!(found[13].0.to_string(), "promote");
+    }
+
+    #[test]
+    fn differences() {
+        for (left, right, expected) in [
+            ("1.2.3", "2.0.0", Some(Difference::Major)),
+            ("2.0.0", "1.2.3", Some(Difference::Major)),
+            ("1.2.3", "1.3.0", Some(Difference::Minor)),
+            ("1.2.3", "1.2.4-rc.1", Some(Difference::Patch)),
+            ("1.2.3-rc.1", "1.2.3", Some(Difference::Prerelease)),
+            ("1.2.3+abc", "1.2.3+def", Some(Difference::Build)),
+            ("1.2.3", "1.2.3", None),
+        ] {
+            let left = Version::parse(left).expect("test data must be valid semver");
+            let right = Version::parse(right).expect("test data must be valid semver");
+            assert_eq!(diff(&left, &right), expected);
+        }
+    }
+
+    #[test]
+    fn successors() {
+        for (previous, next, expected) in [
+            ("1.2.3", "1.2.4", true),
+            ("1.2.3", "1.3.0-rc.1", true),
+            ("1.2.3", "2.0.0", true),
+            ("1.3.0-rc.1", "1.3.0-rc.4", true),
+            ("1.3.0-rc.1", "1.3.0", true),
+            ("1.3.0-rc.1", "1.3.1", true),
+            ("1.2.3", "1.2.5", false),
+            ("1.2.3", "1.4.0", false),
+            ("1.2.3", "3.0.0", false),
+            ("1.2.3", "1.2.3+build.1", false),
+            ("1.2.3", "1.2.3", false),
+            ("1.2.3", "1.2.2", false),
+            ("1.2.3", "1.2.3-rc.1", false),
+        ] {
+            let previous = Version::parse(previous).expect("test data must be valid semver");
+            let next = Version::parse(next).expect("test data must be valid semver");
+            assert_eq!(is_successor(&previous, &next), expected, "{previous} then {next}");
+        }
+    }
+
+    #[test]
+    fn semantic_changes() {
+        let zerover = Options {
+            zerover: true,
+            ..Default::default()
+        };
+        for (input, level, plain, pre_one) in [
+            ("0.4.2", Level::Major, "1.0.0", "0.5.0"),
+            ("0.4.2", Level::Minor, "0.5.0", "0.4.3"),
+            ("0.4.2", Level::Patch, "0.4.3", "0.4.3"),
+            ("1.4.2", Level::Major, "2.0.0", "2.0.0"),
+            ("1.4.2", Level::Minor, "1.5.0", "1.5.0"),
+        ] {
+            let input = Version::parse(input).expect("test data must be valid semver");
+            let kind = BumpKind::Change(level);
+            let bumped = |options: &Options| {
+                bump_with(&input, &kind, options)
+                    .expect("we expected the bump to work")
+                    .to_string()
+            };
+            assert_eq!(bumped(&Options::default()), plain);
+            assert_eq!(bumped(&zerover), pre_one);
+        }
+        let input = Version::new(0, 4, 2);
+        assert_eq!(
+            BumpKind::Change(Level::Major).resolve(&input, &zerover),
+            BumpKind::Minor
+        );
+        assert_eq!(BumpKind::Change(Level::Minor).to_string(), "feature");
+    }
+
+    #[test]
+    fn channel_changes() {
+        for (input, level, channel, expected) in [
+            ("1.2.3", Level::Minor, "beta", "1.3.0-beta.1"),
+            ("1.3.0-beta.1", Level::Minor, "beta", "1.3.0-beta.2"),
+            ("1.3.0-beta.1", Level::Patch, "beta", "1.3.0-beta.2"),
+            ("1.3.0-beta.1", Level::Major, "beta", "2.0.0-beta.1"),
+            ("1.3.0-beta.2", Level::Patch, "rc", "1.3.0-rc.1"),
+            ("1.2.4-rc.1", Level::Minor, "rc", "1.3.0-rc.1"),
+            ("2.0.0-alpha.3", Level::Major, "alpha", "2.0.0-alpha.4"),
+        ] {
+            let input = Version::parse(input).expect("test data must be valid semver");
+            let next =
+                bump(&input, &BumpKind::Channel(level, channel.to_string())).expect("we expected the bump to work");
+            assert_eq!(
+                next.to_string(),
+                expected,
+                "bumping {input} for a {level} change in {channel}"
+            );
+        }
+        let zerover = Options {
+            zerover: true,
+            ..Default::default()
+        };
+        let input = Version::parse("0.5.0-beta.1").expect("test data must be valid semver");
+        assert_eq!(
+            BumpKind::Channel(Level::Major, "beta".to_string()).resolve(&input, &zerover),
+            BumpKind::Prerelease(String::new())
+        );
+    }
+
+    #[test]
+    fn ceilings() {
+        let ceiling = |text: &str| text.parse::<Ceiling>().expect("test data must be a valid ceiling");
+        let version = |text: &str| Version::parse(text).expect("test data must be valid semver");
+        assert!(ceiling("0").admits(&version("0.99.3-rc.1")));
+        assert!(!ceiling("0").admits(&version("1.0.0-alpha.1")));
+        assert!(ceiling("1.4").admits(&version("1.4.9")));
+        assert!(!ceiling("1.4").admits(&version("1.5.0")));
+        assert!(ceiling("2.0.0").admits(&version("2.0.0-rc.1")));
+        assert!(!ceiling("2.0.0").admits(&version("2.0.1")));
+        assert_eq!(ceiling("1.4").to_string(), "1.4");
+        "1.x"
+            .parse::<Ceiling>()
+            .expect_err("we expected a wildcard to be rejected");
+        "1.4-rc.1"
+            .parse::<Ceiling>()
+            .expect_err("we expected a partial pre-release to be rejected");
+    }
+
+    #[test]
+    fn branch_series() {
+        let series = |branch: &str| Series::from_branch(branch).map(|s| s.to_string());
+        assert_eq!(series("release/1.4.x").as_deref(), Some("1.4.x"));
+        assert_eq!(series("release/v2.x").as_deref(), Some("2.x"));
+        assert_eq!(series("maint-1.4").as_deref(), Some("1.4.x"));
+        assert_eq!(series("1.4.*").as_deref(), Some("1.4.x"));
+        assert_eq!(series("release/1.4.2"), None);
+        assert_eq!(series("main"), None);
+        assert_eq!(series("release/next"), None);
+
+        let series = Series::from_branch("release/1.4.x").expect("we expected a series");
+        let version = |text: &str| Version::parse(text).expect("test data must be valid semver");
+        assert!(series.contains(&version("1.4.7")));
+        assert!(series.contains(&version("1.4.8-rc.1")));
+        assert!(!series.contains(&version("1.5.0")));
+        assert!(!series.contains(&version("2.4.0")));
+        assert_eq!(series.base(), version("1.4.0"));
+        assert_eq!(series.tag_pattern("v"), "v1.4.*");
+        let series = Series::from_branch("release/2.x").expect("we expected a series");
+        assert!(series.contains(&version("2.9.1")));
+        assert_eq!(series.tag_pattern(""), "2.*");
+    }
+
+    #[test]
+    fn custom_separator() {
+        let options = Options {
+            separator: '-',
+            ..Default::default()
+        };
+        let input = Version::parse("1.0.0").expect("test data must be valid semver");
+        let next = bump_with(&input, &BumpKind::Prerelease("alpha".to_string()), &options)
+            .expect("we expected the prerelease bump to work");
+        assert_eq!(next.to_string(), "1.0.0-alpha-1");
+        let next = bump_with(&input, &BumpKind::Pre(Level::Minor, "rc".to_string()), &options)
+            .expect("we expected the preminor bump to work");
+        assert_eq!(next.to_string(), "1.1.0-rc-1");
+        // existing separators are respected regardless
+        let input = Version::parse("1.0.0-alpha.1").expect("test data must be valid semver");
+        let next = bump_with(&input, &BumpKind::Prerelease(String::new()), &options)
+            .expect("we expected the prerelease bump to work");
+        assert_eq!(next.to_string(), "1.0.0-alpha.2");
+        let options = Options {
+            separator: '_',
+            ..Default::default()
+        };
+        bump_with(&input, &BumpKind::Patch, &options).expect_err("we expected a bad separator to be rejected");
+    }
+
+    #[test]
+    fn levels() {
+        assert!(Level::Major > Level::Minor && Level::Minor > Level::Patch);
+        let level: Level = "minor".parse().expect("we expected minor to be a level");
+        assert_eq!(level, Level::Minor);
+        assert_eq!(level.to_string(), "minor");
+        assert_eq!(BumpKind::from(level), BumpKind::Minor);
+        assert_eq!(BumpKind::Prerelease("rc".to_string()).to_string(), "prerelease");
+        "prerelease"
+            .parse::<Level>()
+            .expect_err("we expected prerelease not to be a level");
+    }
+
+    #[test]
+    fn passing_numbers_in() {
+        let input = Version::parse("1.2.3-four+4").expect("test data must be valid semver");
+        let next = prerelease(&input, "beta.2").expect("we expected prerelease() to work");
+        assert_eq!(next.to_string(), "1.2.3-beta.2");
+        let input = Version::parse("1.2.3-four+4").expect("test data must be valid semver");
+        let next = build(&input, "7").expect("we expected build() to work");
+        assert_eq!(next.to_string(), "1.2.3-four+7");
+    }
+}
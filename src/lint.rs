@@ -0,0 +1,141 @@
+//! Auditing a release history, such as a repository's tags, for the mistakes that
+//! pile up over the years: tags that look like versions but aren't valid semver,
+//! two tags for the same version, a release lower than one made before it in the
+//! same series, and releases that skip over a version.
+
+use std::cmp::Ordering;
+use std::fmt::{self, Display};
+
+use semver::Version;
+
+/// The kinds of problem an audit finds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    /// A tag that looks like a version, but isn't valid semver.
+    Malformed,
+    /// A tag for a version another tag already names.
+    Duplicate,
+    /// A version lower than one made before it in the same major.minor series.
+    OutOfOrder,
+    /// A version that isn't a single bump of the one below it.
+    Gap,
+}
+
+impl Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Kind::Malformed => "malformed",
+            Kind::Duplicate => "duplicate",
+            Kind::OutOfOrder => "out-of-order",
+            Kind::Gap => "gap",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// One problem with one tag.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Finding {
+    pub kind: Kind,
+    pub tag: String,
+    pub message: String,
+}
+
+/// Audit tags in the order they were made. A tag is taken to name a version if,
+/// after the prefix, it starts with a number; other tags are skipped. Versions are
+/// the same if they have the same precedence, so build metadata doesn't tell them
+/// apart. A backport, such as `1.2.5` after `1.3.0`, isn't out of order, since it's
+/// in another series.
+pub fn lint<S: AsRef<str>>(tags: &[S], prefix: &str) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    let mut seen: Vec<(&str, Version)> = Vec::new();
+    for tag in tags.iter().map(|tag| tag.as_ref().trim()) {
+        let name = tag.strip_prefix(prefix).unwrap_or(tag);
+        if !name.starts_with(|c: char| c.is_ascii_digit()) {
+            continue;
+        }
+        let version = match Version::parse(name) {
+            Ok(version) => version,
+            Err(e) => {
+                findings.push(finding(Kind::Malformed, tag, format!("not a valid version: {e}")));
+                continue;
+            }
+        };
+        if let Some((earlier, _)) = seen.iter().find(|(_, v)| v.cmp_precedence(&version) == Ordering::Equal) {
+            findings.push(finding(Kind::Duplicate, tag, format!("the same version as {earlier}")));
+            continue;
+        }
+        let higher = seen
+            .iter()
+            .filter(|(_, v)| (v.major, v.minor) == (version.major, version.minor))
+            .filter(|(_, v)| v.cmp_precedence(&version) == Ordering::Greater)
+            .max_by(|(_, a), (_, b)| a.cmp_precedence(b));
+        if let Some((earlier, _)) = higher {
+            findings.push(finding(
+                Kind::OutOfOrder,
+                tag,
+                format!("made after {earlier}, a higher version in the same series"),
+            ));
+        }
+        seen.push((tag, version));
+    }
+
+    seen.sort_by(|(_, a), (_, b)| a.cmp_precedence(b));
+    for pair in seen.windows(2) {
+        let [(before, previous), (tag, next)] = pair else {
+            continue;
+        };
+        if !crate::is_successor(previous, next) {
+            findings.push(finding(Kind::Gap, tag, format!("skips ahead of {before}")));
+        }
+    }
+    findings
+}
+
+fn finding(kind: Kind, tag: &str, message: String) -> Finding {
+    Finding {
+        kind,
+        tag: tag.to_string(),
+        message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn linting_tags() {
+        let tags = [
+            "v1.0.0",
+            "docs-snapshot",
+            "v1.1.0",
+            "v1.1.0+rebuild",
+            "v1.3.0",
+            "v1.02.0",
+            "v1.3.2",
+            "v1.3.1",
+            "v1.1.1",
+            "v2.0.0-rc.1",
+        ];
+        let findings = lint(&tags, "v");
+        let found: Vec<(Kind, &str)> = findings.iter().map(|f| (f.kind, f.tag.as_str())).collect();
+        assert_eq!(
+            found,
+            [
+                (Kind::Duplicate, "v1.1.0+rebuild"),
+                (Kind::Malformed, "v1.02.0"),
+                (Kind::OutOfOrder, "v1.3.1"),
+                (Kind::Gap, "v1.3.0"),
+            ]
+        );
+        assert_eq!(findings[0].message, "the same version as v1.1.0");
+        assert_eq!(
+            findings[2].message,
+            "made after v1.3.2, a higher version in the same series"
+        );
+        assert_eq!(findings[3].message, "skips ahead of v1.1.1");
+
+        assert!(lint(&["1.0.0", "1.0.1", "1.1.0-rc.1", "1.1.0"], "v").is_empty());
+    }
+}
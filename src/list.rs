@@ -0,0 +1,169 @@
+//! Working with lists of versions, such as the tags in a repository or the
+//! releases of a package, one version per line.
+
+use anyhow::Context;
+use semver::{Version, VersionReq};
+
+/// Parse a list of versions, one per line, ignoring blank lines. A leading `v`
+/// on a version is accepted and dropped.
+pub fn parse(text: &str) -> anyhow::Result<Vec<Version>> {
+    text.lines()
+        .enumerate()
+        .map(|(idx, line)| (idx, line.trim()))
+        .filter(|(_, line)| !line.is_empty())
+        .map(|(idx, line)| {
+            crate::parse_prefixed(line)
+                .map(|(_, version)| version)
+                .with_context(|| format!("line {}: `{line}` is not a valid version", idx + 1))
+        })
+        .collect()
+}
+
+/// Sort versions in semver precedence order, lowest first. Versions that differ only
+/// in build metadata have the same precedence; they're ordered by their build metadata
+/// so that the output is stable.
+pub fn sort(versions: &mut Vec<Version>, reverse: bool, unique: bool) {
+    versions.sort();
+    if unique {
+        versions.dedup();
+    }
+    if reverse {
+        versions.reverse();
+    }
+}
+
+/// The highest version by semver precedence, optionally ignoring pre-releases.
+pub fn max(versions: &[Version], stable_only: bool) -> Option<&Version> {
+    versions.iter().filter(|v| !stable_only || v.pre.is_empty()).max()
+}
+
+/// The lowest version by semver precedence, optionally ignoring pre-releases.
+pub fn min(versions: &[Version], stable_only: bool) -> Option<&Version> {
+    versions.iter().filter(|v| !stable_only || v.pre.is_empty()).min()
+}
+
+/// The versions that satisfy the requirement, in the order given. As usual for
+/// requirements, a pre-release only satisfies one that names a pre-release of the
+/// same major.minor.patch.
+pub fn satisfying<'a>(versions: &'a [Version], requirement: &VersionReq) -> Vec<&'a Version> {
+    versions.iter().filter(|v| requirement.matches(v)).collect()
+}
+
+/// The highest pre-release of the base version in the channel, as in `1.3.0-rc.2`
+/// for the `rc` channel of `1.3.0`. The base defaults to the major.minor.patch of
+/// the highest version in the list.
+pub fn channel_latest<'a>(versions: &'a [Version], channel: &str, base: Option<&Version>) -> Option<&'a Version> {
+    let highest = versions.iter().max()?;
+    let base = base.unwrap_or(highest);
+    versions
+        .iter()
+        .filter(|v| (v.major, v.minor, v.patch) == (base.major, base.minor, base.patch))
+        .filter(|v| !v.pre.is_empty() && crate::strip_counter(v.pre.as_str()) == channel)
+        .max()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parsing_lists() {
+        let versions = parse("1.0.0\n\n  v2.0.0-rc.1 \n").expect("test data must be valid semver");
+        assert_eq!(versions.len(), 2);
+        assert_eq!(versions[1].to_string(), "2.0.0-rc.1");
+        let err = parse("1.0.0\nnope\n").expect_err("we expected an invalid line to be an error");
+        assert!(err.to_string().starts_with("line 2:"));
+        assert_eq!(crate::error::classify(&err).kind, crate::error::Kind::Parse);
+    }
+
+    #[test]
+    fn sorting_lists() {
+        let input = "1.0.0\n1.0.0-rc.1\n1.0.0-alpha.10\n1.0.0-alpha.2\n0.9.0\n1.0.0\n1.0.0-beta\n";
+        let mut versions = parse(input).expect("test data must be valid semver");
+        sort(&mut versions, false, false);
+        let sorted: Vec<String> = versions.iter().map(|v| v.to_string()).collect();
+        assert_eq!(
+            sorted,
+            vec![
+                "0.9.0",
+                "1.0.0-alpha.2",
+                "1.0.0-alpha.10",
+                "1.0.0-beta",
+                "1.0.0-rc.1",
+                "1.0.0",
+                "1.0.0"
+            ]
+        );
+        sort(&mut versions, true, true);
+        let sorted: Vec<String> = versions.iter().map(|v| v.to_string()).collect();
+        assert_eq!(
+            sorted,
+            vec![
+                "1.0.0",
+                "1.0.0-rc.1",
+                "1.0.0-beta",
+                "1.0.0-alpha.10",
+                "1.0.0-alpha.2",
+                "0.9.0"
+            ]
+        );
+    }
+
+    #[test]
+    fn highest_and_lowest() {
+        let versions = parse("1.0.0\n2.0.0-rc.1\n0.9.0-beta\n0.9.1\n").expect("test data must be valid semver");
+        let highest = max(&versions, false).expect("we expected a highest version");
+        assert_eq!(highest.to_string(), "2.0.0-rc.1");
+        let highest = max(&versions, true).expect("we expected a highest stable version");
+        assert_eq!(highest.to_string(), "1.0.0");
+        let lowest = min(&versions, false).expect("we expected a lowest version");
+        assert_eq!(lowest.to_string(), "0.9.0-beta");
+        let lowest = min(&versions, true).expect("we expected a lowest stable version");
+        assert_eq!(lowest.to_string(), "0.9.1");
+        assert!(max(&[], false).is_none());
+    }
+
+    #[test]
+    fn filtering() {
+        let versions = parse(
+            "1.1.0
+1.4.2
+2.0.0
+1.2.0
+1.5.0-rc.1
+",
+        )
+        .expect("test data must be valid semver");
+        let requirement = VersionReq::parse(">=1.2, <2").expect("test data must be a valid requirement");
+        let matching: Vec<String> = satisfying(&versions, &requirement)
+            .iter()
+            .map(|v| v.to_string())
+            .collect();
+        assert_eq!(matching, ["1.4.2", "1.2.0"]);
+        let requirement = VersionReq::parse("^3").expect("test data must be a valid requirement");
+        assert!(satisfying(&versions, &requirement).is_empty());
+    }
+
+    #[test]
+    fn latest_in_channel() {
+        let versions = parse(
+            "1.2.0
+1.3.0-beta.4
+1.3.0-rc.2
+1.3.0-rc.10
+1.2.0-rc.11
+1.3.0-rc.3
+",
+        )
+        .expect("test data must be valid semver");
+        let latest = channel_latest(&versions, "rc", None).expect("we expected an rc of 1.3.0");
+        assert_eq!(latest.to_string(), "1.3.0-rc.10");
+        let latest = channel_latest(&versions, "beta", None).expect("we expected a beta of 1.3.0");
+        assert_eq!(latest.to_string(), "1.3.0-beta.4");
+        let base = Version::new(1, 2, 0);
+        let latest = channel_latest(&versions, "rc", Some(&base)).expect("we expected an rc of 1.2.0");
+        assert_eq!(latest.to_string(), "1.2.0-rc.11");
+        assert!(channel_latest(&versions, "alpha", None).is_none());
+        assert!(channel_latest(&[], "rc", None).is_none());
+    }
+}
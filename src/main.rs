@@ -1,23 +1,35 @@
 //! Yet another semver bumping cli because all the other ones weren't quite perfect.
-//! This is a very simple wrapper around the semver crate that behaves
-//! exactly as a need a version-bumping tool to behave, and that is built
+//! This is a thin command-line wrapper around the `semver_bump` library, which
+//! behaves exactly as a need a version-bumping tool to behave, and that is built
 //! and released in a way that makes it convenient to use in Github workflows.
-//! It handles incrementing or replacing pre-release and build identifiers as well
-//! as the usual major.minor.patch numbers.
 
 #![deny(future_incompatible, clippy::unwrap_used)]
 #![warn(rust_2018_idioms, trivial_casts)]
 
-use std::fmt::Display;
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
 use std::str::FromStr;
 
-use anyhow::anyhow;
-use clap::{Parser, Subcommand};
-use semver::{BuildMetadata, Prerelease, Version};
-
-// Valid separators between the pre-release and its number;
-// no separator at all is also valid.
-const SEPARATORS: [char; 2] = ['.', '-'];
+use anyhow::Context;
+use clap::error::{ContextKind, ContextValue, ErrorKind};
+use clap::{CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
+use semver::{BuildMetadata, Prerelease, Version, VersionReq};
+use semver_bump::config::{Config, Target};
+use semver_bump::describe::{self, Description};
+use semver_bump::git::Git;
+use semver_bump::manifest::helm::ChartField;
+use semver_bump::manifest::{self, CargoManifest, Changelog, HelmChart, Manifest, VersionFile, Workspace};
+use semver_bump::template::Template;
+use semver_bump::{
+    bump_with, calver, candidates, changes, ci, coerce, conventional, convert, diff, docker, epoch, error, explain,
+    fourpart, github, hooks, is_successor, lint, list, major, menu, minor, notes, parse_prefixed, patch, registry,
+    release, req, semver_checks, sha256, timestamp, validate, BumpKind, Ceiling, Difference, Level, Options, Segment,
+    Series, DEFAULT_CHANNELS,
+};
 
 #[derive(Parser, Debug)]
 #[clap(name = "semver-bump", version)]
@@ -26,16 +38,25 @@ const SEPARATORS: [char; 2] = ['.', '-'];
 pub struct Args {
     #[clap(subcommand)]
     cmd: Command,
+    /// Read defaults from this config file instead of looking for a `.semver-bump.toml`
+    /// between the current directory and the root of the git repository.
+    #[arg(long, global = true, value_name = "PATH")]
+    config: Option<PathBuf>,
+    /// How to report a failure on stderr: as a sentence, or as a `json` object with its
+    /// kind, the input that caused it, and a hint. Either way, the exit status says what
+    /// kind of failure it was.
+    #[arg(long, global = true, value_name = "FORMAT", value_enum, default_value_t)]
+    error_format: ErrorFormat,
 }
 
 #[derive(Clone, Debug, Subcommand)]
 pub enum Command {
     /// Bump the major version number for a breaking change.
-    Major,
+    Major(BumpArgs),
     /// Bump the minor version number for a new feature.
-    Minor,
+    Minor(BumpArgs),
     /// Bump the patch version number for a bug fix.
-    Patch,
+    Patch(BumpArgs),
     #[command(about = "Bump any version number at the end of a pre-release identifier", long_about)]
     /// This command handles incrementing prerelease identifiers of the form
     /// `<id><sep><#>`. If no pre-release identifier is present in the input, one
@@ -45,288 +66,3245 @@ pub enum Command {
         /// The pre-release identifier to use; optional if you're re-using the existing identifier.
         /// Must contain only alphanumeric characters plus any of the valid separator characters.
         identifier: Option<String>,
+        #[command(flatten)]
+        args: BumpArgs,
     },
     /// Bump any version number at the end of a build identifier.
     Build {
         // An optional build identifier to use if you want to add one to a version,
         // or to replace an existing build identifier. Behaves like bumping a prerelease.
         identifier: Option<String>,
+        #[command(flatten)]
+        args: BumpArgs,
+    },
+    /// Bump the major version, then start a pre-release of it, as in `1.2.3` to `2.0.0-rc.1`.
+    Premajor(PreArgs),
+    /// Bump the minor version, then start a pre-release of it, as in `1.2.3` to `1.3.0-rc.1`.
+    Preminor(PreArgs),
+    /// Bump the patch version, then start a pre-release of it, as in `1.2.3` to `1.2.4-rc.1`.
+    Prepatch(PreArgs),
+    #[command(about = "Promote a pre-release to a later channel", long_about)]
+    /// Promote a pre-release to a later channel, as in `1.0.0-alpha.3` to `1.0.0-beta.1`.
+    /// Without a target channel, the version moves to the next channel in order; promoting
+    /// past the last channel drops the pre-release. Channels only ever move forward, so
+    /// accidentally moving from `rc` back to `alpha` is an error.
+    Promote {
+        /// The channel to promote to.
+        to: Option<String>,
+        /// The pre-release channels, in order from least to most mature. Defaults to
+        /// `alpha,beta,rc`.
+        #[arg(long, value_delimiter = ',')]
+        channels: Option<Vec<String>>,
+        #[command(flatten)]
+        args: BumpArgs,
+    },
+    /// Graduate a pre-release by dropping its pre-release and build identifiers.
+    Release(BumpArgs),
+    /// Start the next Maven development version after a release by bumping the patch version
+    /// and marking it `SNAPSHOT`, as in `1.2.3` to `1.2.4-SNAPSHOT`. A snapshot is left as it
+    /// is, and any other pre-release is an error. Use `release` to drop the `SNAPSHOT` again.
+    Snapshot(BumpArgs),
+    #[command(about = "Record pending changes and release them", long_about)]
+    /// Record each change in a small file under `.changes/` as it's made, saying how big a bump
+    /// it calls for and describing it, then release them all at once with `change apply`, which
+    /// makes the largest bump any of them calls for and deletes the files.
+    Change {
+        #[command(subcommand)]
+        action: ChangeAction,
+    },
+    #[command(about = "Bump the version as called for by conventional commit messages", long_about)]
+    /// Scan the messages of the commits between a git ref and HEAD, and bump the version
+    /// as the conventional commits rules call for: a `feat` calls for a minor bump, a `fix`
+    /// calls for a patch bump, and a breaking change marked with `!` or a `BREAKING CHANGE`
+    /// footer calls for a major bump. The largest bump called for wins. With
+    /// `--from-pr-labels`, the labels on a GitHub pull request decide instead. If the config
+    /// file's `branches` table gives the current branch a pre-release channel, the bump is
+    /// made as a pre-release in that channel.
+    #[command(group(clap::ArgGroup::new("start").required(true)))]
+    Auto {
+        /// The git ref to start scanning from, usually the tag of the previous release.
+        #[arg(long, group = "start")]
+        since: Option<String>,
+        /// Start scanning from the most recent tag reachable from HEAD that starts with the tag
+        /// prefix.
+        #[arg(long, group = "start")]
+        since_tag: bool,
+        /// Start scanning from where HEAD branched off this ref, as in `origin/main`, so only the
+        /// branch's own commits count.
+        #[arg(long, value_name = "REF", group = "start")]
+        merge_base: Option<String>,
+        /// Only count commits that change something under this path, for one package in a
+        /// monorepo. Repeat it for more paths.
+        #[arg(long, value_name = "PATH", conflicts_with = "from_pr_labels")]
+        path: Vec<PathBuf>,
+        /// Only count commits with this conventional commit scope, as in `feat(api): ...`.
+        /// Repeat it or separate scopes with commas for more.
+        #[arg(long, value_delimiter = ',', conflicts_with = "from_pr_labels")]
+        scope: Vec<String>,
+        /// The rules to judge commits by: `conventional`, the default, or `semantic-release`,
+        /// which also counts `perf` commits and reverts as fixes. Overrides the config file's
+        /// `rules`.
+        #[arg(long, value_name = "PRESET", value_parser = conventional::Preset::from_str, conflicts_with = "from_pr_labels")]
+        rules: Option<conventional::Preset>,
+        /// In GitHub Actions, bump as the labels on the pull request call for: the one the
+        /// workflow runs for, or else the merged one that brought in $GITHUB_SHA. The labels are
+        /// `semver:major`, `semver:minor`, and `semver:patch`, unless the config file's `labels`
+        /// table says otherwise. The largest bump called for wins.
+        #[arg(long, group = "start")]
+        from_pr_labels: bool,
+        #[command(flatten)]
+        args: BumpArgs,
+    },
+    /// Bump for a breaking change: major, or minor for 0.x with `--zerover`.
+    Breaking(BumpArgs),
+    /// Bump for a new feature: minor, or patch for 0.x with `--zerover`.
+    Feature(BumpArgs),
+    /// Bump for a bug fix: patch.
+    Fix(BumpArgs),
+    #[command(about = "Suggest the bump a crate's API changes call for", long_about)]
+    /// Run cargo-semver-checks, which compares a crate's public API with its last release, and
+    /// print the smallest bump its findings allow: `major` for a breaking change, `minor` for an
+    /// addition, and `patch` if the API is unchanged. The levels are cargo's, so with `--apply`, a
+    /// 0.x crate is bumped as `--zerover` would bump it.
+    Suggest {
+        /// Ask cargo-semver-checks, which must be installed, as with
+        /// `cargo install cargo-semver-checks`.
+        #[arg(long, required = true)]
+        cargo: bool,
+        /// Read a report cargo-semver-checks already wrote from this file, or `-` for stdin,
+        /// instead of running it.
+        #[arg(long, value_name = "PATH", conflicts_with_all = ["package", "baseline_rev"])]
+        report: Option<PathBuf>,
+        /// The package to check in a workspace.
+        #[arg(long, short)]
+        package: Option<String>,
+        /// Compare against the crate at this git revision instead of its release on crates.io.
+        #[arg(long, value_name = "REV")]
+        baseline_rev: Option<String>,
+        /// Make the suggested bump, instead of just printing it.
+        #[arg(long)]
+        apply: bool,
+        #[command(flatten)]
+        args: BumpArgs,
+    },
+    #[command(about = "Replace the version with an explicit one", long_about)]
+    /// Validate the given version and emit it, updating files and tags just as the bumping
+    /// commands do. The previous version is only read if there's a file or manifest to update,
+    /// if one is passed on the command line, or if `--require-greater` asks for a comparison.
+    Set {
+        /// The new version.
+        #[arg(value_name = "NEW", value_parser = parse_version)]
+        next: Version,
+        #[command(flatten)]
+        args: BumpArgs,
+    },
+    #[command(about = "Bump every package in a cargo workspace", long_about)]
+    /// Bump every package in a cargo workspace. The shared `workspace.package.version` is
+    /// bumped if there is one, and so is the version of every member that doesn't inherit
+    /// it. Then the requirements members place on each other, in path dependencies and in
+    /// `[workspace.dependencies]`, are rewritten to match, keeping their operators. Prints
+    /// the name and new version of each package.
+    Workspace {
+        /// The kind of bump to make.
+        bump: WorkspaceBump,
+        /// The pre-release identifier to use with `prerelease` and the `pre*` bumps; optional
+        /// if you're re-using the existing identifier.
+        identifier: Option<String>,
+        /// The workspace's root manifest.
+        #[arg(long, value_name = "PATH", default_value = "Cargo.toml")]
+        root: PathBuf,
+        /// Print a unified diff of the changes that would be made, without writing anything.
+        #[arg(long)]
+        dry_run: bool,
+    },
+    #[command(about = "List every version that could follow this one", long_about)]
+    /// List every version that could reasonably follow this one: the next major, minor, and
+    /// patch versions, a pre-release of each in every channel, and, for a pre-release, its
+    /// next count, its promotions to later channels, and its release. Each line holds a
+    /// version followed by the command that produces it.
+    Next {
+        /// The version to start from. If not provided, it's read from stdin.
+        version: Option<String>,
+        /// The pre-release channels, in order from least to most mature. Defaults to
+        /// `alpha,beta,rc`.
+        #[arg(long, value_delimiter = ',')]
+        channels: Option<Vec<String>>,
+        /// Print the candidates as a JSON array of objects with `version`, `bump`, and
+        /// `identifier` fields.
+        #[arg(long)]
+        json: bool,
+    },
+    #[command(about = "Pick the next version from a menu of candidates", long_about)]
+    /// Show the current version and a menu of the versions that could follow it, as listed
+    /// by `next`, and make the bump picked with the arrow keys and enter. The bump is made as
+    /// any other is, so it updates files, commits, and tags as the options given ask. The
+    /// current version is read from the version, `--file`, or `--manifest` given, or the
+    /// files in the config file; stdin is left for the keyboard.
+    Interactive {
+        /// The pre-release channels, in order from least to most mature. Defaults to
+        /// `alpha,beta,rc`.
+        #[arg(long, value_delimiter = ',')]
+        channels: Option<Vec<String>>,
+        #[command(flatten)]
+        args: BumpArgs,
+    },
+    #[command(about = "Work out the version before this one", long_about)]
+    /// Work out the version before this one, for when a release is pulled. A pre-release
+    /// has its counter decremented, as in `1.2.3-rc.3` to `1.2.3-rc.2`; the first pre-release
+    /// in a series is an error, since it could have come from anywhere. A release steps down
+    /// its lowest non-zero number, as in `1.3.0` to `1.2.0`. The numbers below that can't be
+    /// recovered from the version alone and are left at zero.
+    Rollback(BumpArgs),
+    #[command(about = "Decrement the major, minor, or patch version", long_about)]
+    /// Decrement the major, minor, or patch version, zeroing the numbers below it and
+    /// dropping any pre-release and build identifiers, as in `down minor` turning `1.3.2`
+    /// into `1.2.0`. Going below zero is an error.
+    Down {
+        /// The level to step down: major, minor, or patch.
+        #[arg(value_parser = Level::from_str)]
+        level: Level,
+        #[command(flatten)]
+        args: BumpArgs,
+    },
+    #[command(about = "Start a new epoch, as in `1!2.3.4`", long_about)]
+    /// Bump the epoch in front of a version, as in `1.2.3` to `1!1.2.3` or `1!1.2.3` to
+    /// `2!1.2.3`, for when a package has to start its version numbers over. Versions in a later
+    /// epoch come after every version in an earlier one, whatever their numbers. Pass `--reset`
+    /// to start over at a new version in the same step.
+    Epoch {
+        /// The version to bump. If not provided, it's read from stdin.
+        version: Option<String>,
+        /// The version to start the new epoch at, instead of keeping the current one.
+        #[arg(long, value_name = "VERSION", value_parser = parse_version)]
+        reset: Option<Version>,
+    },
+    #[command(about = "Bump a calendar version", long_about)]
+    /// Bump a calendar version, such as `2024.3.1`, to today's date. If the version is from
+    /// an earlier date, its date components move to today and its counter starts over at 0;
+    /// if it's from today, the counter increments. Dates are in UTC. Patterns are made of
+    /// `YYYY` (full year), `YY` and `0Y` (year since 2000, plain or zero-padded), `MM` and
+    /// `0M` (month), `DD` and `0D` (day of the month), and `MICRO` or `PATCH` (the counter),
+    /// separated by `.` or `-`.
+    Calver {
+        /// The version to bump. If not provided, it's read from stdin; an empty version
+        /// starts a new series.
+        version: Option<String>,
+        /// The calendar version pattern.
+        #[arg(long, default_value = calver::DEFAULT_PATTERN, value_parser = calver::Pattern::from_str)]
+        pattern: calver::Pattern,
+        /// Bump to this date, written as `YYYY-MM-DD`, instead of today.
+        #[arg(long, value_parser = calver::Date::from_str)]
+        date: Option<calver::Date>,
+        /// Read the version from this file, then write the bumped version back to it. Pass `auto`
+        /// to use the `VERSION` or `.version` file at the root of the repository.
+        #[arg(long, conflicts_with = "version")]
+        file: Option<PathBuf>,
+    },
+    #[command(about = "Compare two versions by semver precedence", long_about)]
+    /// Compare two versions by semver precedence, printing `lt`, `eq`, or `gt` to describe
+    /// how the first version relates to the second. The exit status says the same thing:
+    /// 0 for `eq`, 10 for `lt`, and 11 for `gt`. Build metadata does not affect precedence.
+    /// A version may have an epoch, as in `1!2.3.4`, which is compared first.
+    Compare {
+        /// The version on the left-hand side of the comparison.
+        #[arg(value_parser = epoch::parse)]
+        left: (u64, Version),
+        /// The version on the right-hand side of the comparison.
+        #[arg(value_parser = epoch::parse)]
+        right: (u64, Version),
+    },
+    #[command(
+        about = "Print the most significant part that differs between two versions",
+        long_about
+    )]
+    /// Print the most significant part that differs between two versions: `major`, `minor`,
+    /// `patch`, `prerelease`, `build`, or `none` if they're identical. The exit status says the
+    /// same thing: 0 for `none`, 20 for `major`, 21 for `minor`, 22 for `patch`, 23 for
+    /// `prerelease`, and 24 for `build`. The order of the versions doesn't matter.
+    Diff {
+        /// One of the versions to compare.
+        #[arg(value_parser = parse_version)]
+        left: Version,
+        /// The other version to compare.
+        #[arg(value_parser = parse_version)]
+        right: Version,
+    },
+    #[command(about = "Check that one version comes after another", long_about)]
+    /// Check that the new version comes after the old one by semver precedence, exiting with an
+    /// error if it doesn't, so a release can't go backwards. With `--strict-succession`, the new
+    /// version must also be a single major, minor, or patch bump of the old one, or a pre-release
+    /// of one, so `1.2.3` can be followed by `1.2.4`, `1.3.0-rc.1`, or `2.0.0`, but not `1.2.5`.
+    Precedes {
+        /// The earlier version.
+        #[arg(value_parser = parse_version)]
+        old: Version,
+        /// The version that should come after it.
+        #[arg(value_parser = parse_version)]
+        new: Version,
+        /// Also reject a new version that skips over a release.
+        #[arg(long)]
+        strict_succession: bool,
+    },
+    #[command(about = "Spell a version the way Python, Debian, or RPM packages do", long_about)]
+    /// Print the version as another packaging ecosystem spells it: `pep440` for Python, as in
+    /// `1.2.3rc1`; `deb` for a Debian upstream version, as in `1.2.3~rc1`; or `rpm`, as in
+    /// `1.2.3~rc1`. A pre-release with no PEP 440 equivalent is an error.
+    Convert {
+        /// The version to convert. If not provided, it's read from stdin.
+        version: Option<String>,
+        /// The ecosystem to spell it for: `pep440`, `deb`, or `rpm`.
+        #[arg(long, value_name = "ECOSYSTEM", value_parser = convert::Ecosystem::from_str)]
+        to: convert::Ecosystem,
+    },
+    #[command(about = "List the container image tags to publish a version under", long_about)]
+    /// List the conventional container image tags for a version, one per line: the version
+    /// itself, then for a release `MAJOR.MINOR` and `MAJOR` (unless it's 0) and `latest`, or
+    /// for a pre-release its channel, as in `rc`. Build metadata has its `+` turned into a `-`,
+    /// since image tags can't hold a `+`.
+    Tags {
+        /// The version to tag. If not provided, it's read from stdin.
+        version: Option<String>,
+        /// Put this in front of each version tag, as in `v1.2`.
+        #[arg(long, default_value = "")]
+        prefix: String,
+        /// Leave out `latest`, as when publishing a fix to an older release line.
+        #[arg(long)]
+        no_latest: bool,
+        /// Print the tags as a JSON array.
+        #[arg(long)]
+        json: bool,
+    },
+    #[command(about = "Explain what a version means and how each bump would change it", long_about)]
+    /// Print a breakdown of a version: each of its parts, whether it's a pre-release, how
+    /// it orders against the release and the versions around it, and what each bump listed
+    /// by `next` would turn it into.
+    Explain {
+        /// The version to explain. If not provided, it's read from stdin.
+        version: Option<String>,
+        /// The pre-release channels, in order from least to most mature. Defaults to
+        /// `alpha,beta,rc`.
+        #[arg(long, value_delimiter = ',')]
+        channels: Option<Vec<String>>,
+    },
+    #[command(about = "Check that a version is strict semver 2.0.0", long_about)]
+    /// Check that the candidate is a strict semver 2.0.0 version. If it is, print it and
+    /// exit successfully. If it isn't, explain exactly what's wrong with it and exit with
+    /// an error.
+    Validate {
+        /// The candidate version. If not provided, it's read from stdin.
+        candidate: Option<String>,
+    },
+    #[command(about = "Turn a sloppy version into strict semver 2.0.0", long_about)]
+    /// Fix what can be fixed in a nearly valid version and print it as strict semver 2.0.0:
+    /// whitespace and a byte order mark around it are trimmed, a leading `v` is dropped,
+    /// missing minor and patch numbers are filled in with 0, and leading zeros are removed. If
+    /// it still isn't valid, explain what's wrong with it and exit with an error.
+    Normalize {
+        /// The version to normalize. If not provided, it's read from stdin.
+        candidate: Option<String>,
+        /// Lowercase the pre-release identifiers, as in `RC.1` to `rc.1`. This changes how they
+        /// sort, since uppercase letters come before lowercase ones.
+        #[arg(long)]
+        lowercase: bool,
+    },
+    /// Read versions from stdin, one per line, and print them in semver precedence order.
+    Sort {
+        /// Print the highest version first.
+        #[arg(long, short)]
+        reverse: bool,
+        /// Print each distinct version only once.
+        #[arg(long, short)]
+        unique: bool,
+    },
+    /// Print the highest of a list of versions by semver precedence.
+    Max(PickArgs),
+    /// Print the lowest of a list of versions by semver precedence.
+    Min(PickArgs),
+    #[command(about = "Print the latest pre-release in a channel", long_about)]
+    /// Print the highest pre-release in a channel for the version being released, as in
+    /// `1.3.0-rc.10` for `rc`, from versions read from stdin, one per line, or from the
+    /// repository's tags with `--tags`. The version being released is the major.minor.patch of
+    /// the highest version in the list unless you pass `--base`. With `--bump`, print the
+    /// pre-release after it instead, or the first one if there's none yet.
+    ChannelLatest {
+        /// The pre-release channel, such as `rc`.
+        channel: String,
+        /// The version whose pre-releases to look at.
+        #[arg(long, value_name = "VERSION", value_parser = parse_version)]
+        base: Option<Version>,
+        /// Read the versions from the repository's tags, skipping any that aren't versions.
+        #[arg(long)]
+        tags: bool,
+        /// Print the next pre-release in the channel instead.
+        #[arg(long)]
+        bump: bool,
+    },
+    #[command(about = "Print the versions that satisfy a version requirement", long_about)]
+    /// Read versions from stdin, one per line, and print the ones that satisfy a version
+    /// requirement, such as `>=1.2, <2`, in the order they were read. As usual for requirements,
+    /// a pre-release only satisfies one that names a pre-release of the same version. Exits with
+    /// status 1 if none do.
+    Filter {
+        /// The requirement to keep versions that satisfy.
+        #[arg(value_parser = VersionReq::parse)]
+        requirement: VersionReq,
+        /// Print only the highest version that satisfies the requirement.
+        #[arg(long)]
+        latest: bool,
+    },
+    #[command(about = "Print the latest version published somewhere", long_about)]
+    /// Look up the versions published somewhere other than this repository and print the
+    /// highest one, so a repository without its tags can still bump from the last release.
+    /// Anything that isn't a version, with or without a `v`, is ignored.
+    Latest(LatestArgs),
+    #[command(about = "Check whether a version satisfies version requirements", long_about)]
+    /// Check whether a version satisfies the given version requirements, such as `^1.2` or
+    /// `>=1.2, <2`. Exits successfully if the version satisfies all of them, or any of them
+    /// if you pass `--any`, and with status 1 if it does not.
+    Matches {
+        /// The requirements to check the version against.
+        #[arg(required = true, value_parser = VersionReq::parse)]
+        requirements: Vec<VersionReq>,
+        /// The version to check. If not provided, it's read from stdin.
+        #[arg(long, value_name = "VERSION", value_parser = parse_version)]
+        current: Option<Version>,
+        /// Succeed if any one of the requirements is satisfied, instead of all of them.
+        #[arg(long)]
+        any: bool,
+    },
+    #[command(about = "Work out how to change or combine version requirements", long_about)]
+    /// Work out how to change a caret or tilde version requirement, such as `^1.2`, for a new
+    /// release, the way a dependency-update bot would, or what several requirements accept
+    /// between them. Requirements are read the way cargo reads them, so a bare `1.2` means
+    /// `^1.2`. Prints the resulting requirement.
+    Req {
+        #[command(subcommand)]
+        action: ReqAction,
+    },
+    #[command(about = "Write release notes from commit messages", long_about)]
+    /// Write Markdown release notes from the subjects of the commits between two git refs,
+    /// grouped by conventional commit type: breaking changes, features, bug fixes, performance,
+    /// documentation, and everything else. Merge commits are left out.
+    Notes {
+        /// The git ref to start from, usually the tag of the previous release.
+        #[arg(long)]
+        from: String,
+        /// The git ref to end at.
+        #[arg(long, default_value = "HEAD")]
+        to: String,
+        /// The version being released, for the template's `{next}`.
+        #[arg(long, value_name = "VERSION", value_parser = parse_version)]
+        next: Option<Version>,
+        /// The previous version, for the template's `{prev}`. Defaults to the version `--from`
+        /// names, if it's a tag like `v1.2.3`.
+        #[arg(long, value_name = "VERSION", value_parser = parse_version)]
+        prev: Option<Version>,
+        /// Render the notes with this template, whose placeholders are `{notes}`, `{prev}`,
+        /// `{next}`, `{from}`, `{to}`, and `{date}`. Defaults to a `## {next} - {date}` heading
+        /// above the notes when `--next` is given, and the bare notes otherwise.
+        #[arg(long, value_name = "TEMPLATE", value_parser = notes_template)]
+        template: Option<Template>,
+        /// Read the template from this file.
+        #[arg(long, value_name = "PATH", conflicts_with = "template")]
+        template_file: Option<PathBuf>,
+    },
+    #[command(about = "Check that the version matches the tag on HEAD", long_about)]
+    /// Check that the version in a file or manifest matches a git tag pointing at HEAD,
+    /// ignoring the tag's prefix. Prints the matching tag and exits successfully, or explains
+    /// the mismatch and exits with an error. Without `--file` or `--manifest`, the files listed
+    /// in the config file are checked.
+    VerifyTag {
+        /// Read the version from this file. Pass `auto` to use the `VERSION` or `.version` file
+        /// at the root of the repository.
+        #[arg(long)]
+        file: Option<PathBuf>,
+        /// Read the version from this package manifest.
+        #[arg(long, conflicts_with = "file")]
+        manifest: Option<PathBuf>,
+        /// The prefix tags are named with. Defaults to `v`.
+        #[arg(long)]
+        tag_prefix: Option<String>,
     },
+    #[command(about = "Audit the tags for a messy release history", long_about)]
+    /// Audit the repository's tags, oldest first, or a list read from stdin with `--stdin`, and
+    /// report the problems found, one per line: `malformed` for a tag that starts with a number
+    /// after its prefix but isn't valid semver, `duplicate` for a second tag for the same
+    /// version, `out-of-order` for a version made after a higher one in the same major.minor
+    /// series, and `gap` for a version that isn't a single bump of the one below it. Tags that
+    /// don't look like versions are skipped. Exits with status 1 if anything was found.
+    LintTags {
+        /// Read the tags from stdin, one per line, in the order they were made.
+        #[arg(long)]
+        stdin: bool,
+        /// The prefix tags are named with. Defaults to `v`.
+        #[arg(long)]
+        tag_prefix: Option<String>,
+        /// Print the problems as a JSON array of objects with `kind`, `tag`, and `message`
+        /// fields.
+        #[arg(long)]
+        json: bool,
+    },
+    #[command(about = "Print a single component of a version", long_about)]
+    /// Print a single component of a version. Versions without a pre-release or build
+    /// identifier print an empty line when asked for one.
+    Get {
+        /// The component to print.
+        component: Component,
+        /// The version to read from. If not provided, it's read from stdin.
+        #[arg(value_parser = parse_version)]
+        version: Option<Version>,
+    },
+    #[command(about = "Print a shell completion script", long_about)]
+    /// Print a completion script for the given shell. For example, add
+    /// `source <(semver-bump completions bash)` to your `.bashrc`, or write the output
+    /// of `semver-bump completions fish` to `~/.config/fish/completions/semver-bump.fish`.
+    Completions {
+        /// The shell to generate completions for.
+        shell: Shell,
+    },
+}
+
+/// The components of a version that `get` can print.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum Component {
+    Major,
+    Minor,
+    Patch,
+    Prerelease,
+    Build,
+}
+
+/// Options shared by all of the bumping commands.
+#[derive(Clone, Debug, Default, clap::Args)]
+pub struct BumpArgs {
+    /// The version to bump. If not provided, it's read from stdin.
+    version: Option<String>,
+    /// The version to bump, as a flag instead of a positional argument.
+    #[arg(long, value_name = "VERSION", conflicts_with = "version")]
+    current: Option<String>,
+    /// Read the version to bump from this environment variable.
+    #[arg(long, value_name = "VAR", conflicts_with_all = ["version", "current"])]
+    from_env: Option<String>,
+    /// Read the version from this file, then write the bumped version back to it.
+    #[arg(long, conflicts_with_all = ["version", "current", "from_env"])]
+    file: Option<PathBuf>,
+    /// Read the version from this package manifest, then write the bumped version back to it.
+    /// Supported manifests: Cargo.toml, package.json, composer.json, pyproject.toml, Chart.yaml,
+    /// pom.xml, gradle.properties, build.gradle, build.gradle.kts, *.gemspec, version.rb.
+    #[arg(long, conflicts_with_all = ["version", "current", "from_env", "file"])]
+    manifest: Option<PathBuf>,
+    /// What kind of manifest `--manifest` is, for a file whose name doesn't say: one of
+    /// `cargo`, `npm`, `composer`, `pyproject`, `helm`, `maven`, `gradle`, or `ruby`. Pass
+    /// `exec:PROGRAM` to have a program of your own read and write the version.
+    #[arg(long, value_name = "TYPE", value_parser = manifest::Kind::from_str, requires = "manifest")]
+    manifest_type: Option<manifest::Kind>,
+    /// Bump the version under this dotted key path in a `--manifest` JSON, YAML, or TOML file,
+    /// as in `tool.mytool.version`, whatever the file is.
+    #[arg(long, value_name = "KEY", requires = "manifest", conflicts_with_all = ["manifest_type", "chart_field"])]
+    key: Option<String>,
+    /// Which fields of a Helm chart's Chart.yaml to bump: `version`, `appVersion`, or both,
+    /// separated by a comma. Defaults to `version`.
+    #[arg(long, value_name = "FIELDS", value_delimiter = ',', value_parser = ChartField::from_str, requires = "manifest")]
+    chart_field: Vec<ChartField>,
+    /// Release the unreleased changes in this Keep a Changelog file: the `## [Unreleased]`
+    /// section becomes the new version's, dated today, and the comparison links are updated.
+    #[arg(long, value_name = "PATH")]
+    changelog: Option<PathBuf>,
+    /// Create an annotated git tag for the new version in the current repository.
+    #[arg(long)]
+    tag: bool,
+    /// The prefix to put in front of the version when naming the tag, and in changelog links.
+    /// Defaults to `v`.
+    #[arg(long)]
+    tag_prefix: Option<String>,
+    /// What to do when the tag for the new version already exists: exit with an `error`,
+    /// `skip` creating the tag, or bump again until the `next` free tag is found.
+    #[arg(long, value_name = "STRATEGY", value_enum, default_value_t = TagCollision::Error, requires = "tag")]
+    if_tag_exists: TagCollision,
+    /// Also check that the tag doesn't already exist on this remote, defaulting to `origin`,
+    /// before creating it locally.
+    #[arg(long, value_name = "REMOTE", num_args = 0..=1, default_missing_value = "origin", requires = "tag")]
+    check_remote: Option<String>,
+    /// Commit the files that were bumped, and only those. Refuses to run if anything else
+    /// is already staged.
+    #[arg(long)]
+    commit: bool,
+    /// The message for `--commit`, as a template in which `{prev}` is the previous version
+    /// and `{next}` the new one. Defaults to `Bump version from {prev} to {next}`.
+    #[arg(long, value_name = "TEMPLATE", value_parser = Template::from_str, requires = "commit")]
+    message: Option<Template>,
+    /// Sign the tag and commit made by `--tag` and `--commit`, with whichever GPG or SSH key
+    /// git is configured to sign with.
+    #[arg(long)]
+    sign: bool,
+    /// Push the tag and commit made by `--tag` and `--commit` to this remote. Defaults to
+    /// `origin`. With `--dry-run`, print what would be pushed instead.
+    #[arg(long, value_name = "REMOTE", num_args = 0..=1, default_missing_value = "origin")]
+    push: Option<String>,
+    /// Once the tag is pushed, create a GitHub release for it, with notes made from the commits
+    /// since the previous version's tag. It's marked as a pre-release if the version is one.
+    /// Needs a token in $GITHUB_TOKEN or $GH_TOKEN.
+    #[arg(long, requires_all = ["tag", "push"])]
+    github_release: bool,
+    /// The repository to create the release in, as `OWNER/NAME`. Defaults to $GITHUB_REPOSITORY.
+    #[arg(long, value_name = "OWNER/NAME", requires = "github_release")]
+    github_repo: Option<String>,
+    /// Save the GitHub release as a draft instead of publishing it.
+    #[arg(long, requires = "github_release")]
+    draft: bool,
+    /// The separator to put between a pre-release identifier and a newly added counter,
+    /// either `.` or `-`. Defaults to `.`.
+    #[arg(long)]
+    separator: Option<char>,
+    /// Exit with an error if the new version does not have a strictly higher precedence than
+    /// the previous version.
+    #[arg(long)]
+    require_greater: bool,
+    /// Exit with an error if the new version is higher than this one. Components can be left
+    /// out, so `--max 0` allows any `0.x.y` and forbids `1.0.0`.
+    #[arg(long, value_name = "VERSION", value_parser = Ceiling::from_str)]
+    max: Option<Ceiling>,
+    /// Append the new version to the GitHub Actions step outputs file named by $GITHUB_OUTPUT,
+    /// as `NAME=<version>`, along with `previous=<version>` and `bump=<kind>`.
+    #[arg(long, value_name = "NAME", num_args = 0..=1, default_missing_value = "version")]
+    github_output: Option<String>,
+    /// Append the new version to a dotenv file for GitLab CI's `artifacts:reports:dotenv`, as
+    /// `VERSION=<version>`, along with `PREVIOUS_VERSION=<version>` and `BUMP=<kind>`.
+    #[arg(long, value_name = "PATH", num_args = 0..=1, default_missing_value = "build.env")]
+    gitlab_dotenv: Option<PathBuf>,
+    /// Hand `version`, `previous`, and `bump` variables to this CI system: `github` does what
+    /// --github-output does, `gitlab` what --gitlab-dotenv does, and `azure` and `teamcity` print
+    /// commands to stdout ahead of the new version. `auto` picks the system we're running in, and
+    /// does nothing outside CI.
+    #[arg(long, value_name = "SYSTEM", value_parser = ci::Choice::from_str)]
+    ci: Option<ci::Choice>,
+    /// Add a table of the previous and new versions, the kind of bump, and the tag to the
+    /// GitHub Actions job summary in $GITHUB_STEP_SUMMARY.
+    #[arg(long)]
+    summary: bool,
+    /// Always print the new version with a `v` prefix, even if the previous version had none.
+    /// By default the new version has a prefix only if the previous version did.
+    #[arg(long, conflicts_with = "strip_prefix")]
+    keep_prefix: bool,
+    /// Print the new version without any prefix the previous version had.
+    #[arg(long)]
+    strip_prefix: bool,
+    /// Keep to the release series the current branch's name gives, as in `release/1.4.x`, so the
+    /// new version must be a `1.4.z` one. With no version on the command line, the previous
+    /// version is the series' latest tag reachable from HEAD, or `1.4.0` if it has none yet.
+    #[arg(long)]
+    from_branch: bool,
+    /// Read every line of stdin and bump each version independently, printing one result per line.
+    #[arg(long, conflicts_with_all = ["version", "current", "from_env", "file", "manifest", "changelog", "tag", "commit", "github_output", "gitlab_dotenv", "ci", "summary", "from_branch"])]
+    each: bool,
+    /// Add build metadata describing the git repository to the new version, appending it to
+    /// any build metadata already there. Takes a comma-separated list of parts: `sha` for the
+    /// abbreviated commit hash, as in `g1a2b3c4`; `count` for the number of commits; and
+    /// `dirty` if there are uncommitted changes. Defaults to `sha,dirty`.
+    #[arg(
+        long,
+        value_name = "PARTS",
+        value_delimiter = ',',
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "sha,dirty"
+    )]
+    build_from_git: Option<Vec<GitPart>>,
+    /// Add the CI system's build number to the new version's build metadata, as in
+    /// `1.2.3+build.4821`. It comes from $GITHUB_RUN_NUMBER on GitHub Actions, $CI_PIPELINE_IID on
+    /// GitLab, $BUILD_BUILDID on Azure Pipelines, and $BUILD_NUMBER anywhere else.
+    #[arg(long)]
+    build_from_ci: bool,
+    /// Add a digest of a file's contents to the new version's build metadata, as in
+    /// `1.2.3+sha256.3a7bd3e2360a`: the first 12 hex digits of its SHA-256. Pass `-` to hash
+    /// whatever is piped to stdin, in which case the version must come from somewhere else.
+    #[arg(long, value_name = "PATH")]
+    build_from_hash: Option<PathBuf>,
+    /// The digest `--build-from-hash` adds, read once so every step of a bump agrees.
+    #[arg(skip)]
+    build_hash: Option<String>,
+    /// Add the current UTC time to the new version's build metadata, appending it to any build
+    /// metadata already there. The format defaults to `YYYYMMDDHHMMSS`; `MM` is the month,
+    /// unless it comes after `HH`, in which case it's the minute.
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = timestamp::DEFAULT_FORMAT,
+        value_parser = timestamp::Format::from_str
+    )]
+    build_timestamp: Option<timestamp::Format>,
+    /// Stamp pre-releases with today's UTC date instead of counting them, as in
+    /// `prerelease nightly --pre-date` making `1.3.0-nightly.20240519`. A second pre-release
+    /// on the same day gets a counter, as in `nightly.20240519.1`. The format defaults to
+    /// `YYYYMMDD`, and takes the same fields as `--build-timestamp`.
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "YYYYMMDD",
+        value_parser = timestamp::Format::from_str
+    )]
+    pre_date: Option<timestamp::Format>,
+    /// Today's date as `--pre-date` formats it, read once so every step of a bump agrees.
+    #[arg(skip)]
+    pre_date_stamp: Option<String>,
+    /// Count this far instead of by one on major, minor, patch, and pre-release bumps, so
+    /// `patch --by 3` turns `1.0.0` into `1.0.3`.
+    #[arg(long, value_name = "N", default_value_t = 1)]
+    by: u64,
+    /// Which number counts up when a pre-release with several, such as `rc.1.post.3`, is
+    /// bumped: `first`, `last`, or a position counting numeric identifiers from 1.
+    #[arg(long, value_name = "N", default_value_t = Segment::Last, value_parser = Segment::from_str)]
+    segment: Segment,
+    /// Zero-pad pre-release counters to this many digits, as in `alpha-001`. Since semver
+    /// forbids leading zeros in numbers, padded counters are joined to their identifier with
+    /// a `-` and compare as text, so every counter in a series must have the same width.
+    #[arg(long, value_name = "N", value_parser = clap::value_parser!(u8).range(1..=20))]
+    pad: Option<u8>,
+    /// Start the pre-release counter over at 1 when a pre-major, pre-minor, or pre-patch bump
+    /// moves to a new base version, as in `prepatch rc` turning `1.2.3-rc.9` into
+    /// `1.2.4-rc.1`. This is the default; the flag overrides `continue_counter` in the config file.
+    #[arg(long, conflicts_with = "continue_counter")]
+    reset_counter: bool,
+    /// Carry the pre-release counter over to the new base version when the channel stays the
+    /// same, as in `prepatch rc` turning `1.2.3-rc.9` into `1.2.4-rc.10`.
+    #[arg(long)]
+    continue_counter: bool,
+    /// Treat 0.x versions the way cargo does: a breaking change bumps the minor version and a
+    /// feature bumps the patch version. Affects `breaking`, `feature`, and `fix`, and `auto`,
+    /// which works out the kind of change from commit messages.
+    #[arg(long)]
+    zerover: bool,
+    /// Mark the new version as a Maven development snapshot by making its pre-release
+    /// identifier `SNAPSHOT`, as in `1.3.0-SNAPSHOT`. Use `release` to drop it again.
+    #[arg(long)]
+    snapshot: bool,
+    /// Keep the pre-release identifier on major, minor, and patch bumps, so `1.2.3-alpha.1`
+    /// becomes `2.0.0-alpha.1` instead of `2.0.0`.
+    #[arg(long)]
+    keep_prerelease: bool,
+    /// Keep the build metadata on major, minor, and patch bumps.
+    #[arg(long)]
+    keep_build: bool,
+    /// Accept sloppy input versions such as `1.2`, `1.2.3.4`, or `release-1.2`, normalizing
+    /// them into valid semver before bumping.
+    #[arg(long)]
+    coerce: bool,
+    /// Read and print four-part .NET-style versions, as in `1.2.3.4`. The fourth number is a
+    /// revision: `build` counts it up, and major, minor, and patch bumps reset it to 0. Print
+    /// the result as four numbers with `parts`, the default, or with the revision folded into
+    /// the build metadata, as in `1.2.3+4`, with `build`. Works on versions given on the
+    /// command line or stdin.
+    #[arg(
+        long,
+        value_name = "STYLE",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "parts",
+        value_parser = fourpart::Style::from_str,
+        conflicts_with_all = ["coerce", "file", "manifest", "tag", "commit", "each"]
+    )]
+    four_part: Option<fourpart::Style>,
+    /// Accept only exact input versions: strict semver with no `v` prefix and no build metadata.
+    /// Empty input is reported as such, and with `--each`, so are blank lines, which are
+    /// otherwise passed through. Applies to versions given on the command line, in the
+    /// environment, or on stdin.
+    #[arg(long, conflicts_with_all = ["coerce", "four_part", "input_format"])]
+    strict: bool,
+    /// How to read input versions: as plain `semver`, or as the output of `git describe --tags`,
+    /// as in `v1.2.3-14-g1a2b3c4-dirty`.
+    #[arg(long, value_name = "FORMAT", value_enum, default_value_t)]
+    input_format: InputFormat,
+    /// Where `--input-format git-describe` puts the number of commits since the tag: in the
+    /// `build` metadata, or appended to the `pre`-release. The hash and `dirty` always go in the
+    /// build metadata. Defaults to `build`.
+    #[arg(long, value_name = "STYLE", value_parser = describe::Style::from_str)]
+    describe_as: Option<describe::Style>,
+    /// Print the new version with this template instead, as in `{major}.{minor}`. The
+    /// placeholders are `{version}` (or `{next}`), `{major}`, `{minor}`, `{patch}`, `{prerelease}`,
+    /// `{build}`, `{prefix}`, `{prev}`, `{bump}`, `{tag_prefix}`, and `{tag}`; write `{{` and `}}`
+    /// for literal braces.
+    #[arg(long, value_name = "TEMPLATE", value_parser = Template::from_str)]
+    format: Option<Template>,
+    /// After bumping a crate in a cargo workspace, rewrite the requirements other members place
+    /// on it to match, keeping their operators, so `^1.2` becomes `^1.3`.
+    #[arg(long)]
+    update_dependents: bool,
+    /// Print a unified diff of the changes that would be made to the file or manifest, and the
+    /// new version, without writing anything or creating a tag.
+    #[arg(long)]
+    dry_run: bool,
+    /// Files to update named by the config file, used when no other source is given.
+    #[arg(skip)]
+    config_files: Vec<Target>,
+    /// Files to delete once the new version is written, such as the change files `change apply`
+    /// releases.
+    #[arg(skip)]
+    consumed: Vec<PathBuf>,
+}
+
+impl BumpArgs {
+    /// Fill in anything not given on the command line from the config file.
+    fn with_config(mut self, config: &Config) -> anyhow::Result<Self> {
+        if self.format.is_none() {
+            self.format = config.format.as_deref().map(Template::from_str).transpose()?;
+        }
+        self.tag_prefix = self.tag_prefix.or_else(|| config.tag_prefix.clone());
+        self.separator = self.separator.or(config.separator);
+        self.pad = self.pad.or(config.pad);
+        self.continue_counter |= config.continue_counter && !self.reset_counter;
+        self.pre_date_stamp = self.pre_date.as_ref().map(timestamp::Format::now).transpose()?;
+        self.zerover |= config.zerover;
+        self.sign |= config.sign;
+        if self.max.is_none() {
+            self.max = config.max.as_deref().map(Ceiling::from_str).transpose()?;
+        }
+        if !(self.has_version()
+            || self.file.is_some()
+            || self.manifest.is_some()
+            || self.each
+            || self.four_part.is_some())
+        {
+            self.config_files = config.targets();
+        }
+        if let Some(path) = self.build_from_hash.as_ref() {
+            let contents = if path == Path::new("-") {
+                let version_elsewhere = self.has_version()
+                    || self.file.is_some()
+                    || self.manifest.is_some()
+                    || self.from_branch
+                    || !self.config_files.is_empty();
+                if self.each || !version_elsewhere {
+                    return Err(anyhow::anyhow!(
+                        "--build-from-hash - reads stdin, so the version has to come from somewhere else."
+                    ));
+                }
+                let mut contents = Vec::new();
+                std::io::stdin().read_to_end(&mut contents)?;
+                contents
+            } else {
+                std::fs::read(path).with_context(|| format!("unable to read {} to hash it", path.display()))?
+            };
+            self.build_hash = Some(sha256::hex(&contents)[..12].to_string());
+        }
+        Ok(self)
+    }
+
+    /// How to print the new version: with the output template if there is one, or else
+    /// as the version with the given prefix.
+    fn render(&self, prefix: &str, result: &Version, previous: Option<&Version>, kind: &BumpKind) -> String {
+        match (self.format.as_ref(), self.four_part) {
+            (Some(format), _) => self.expand(format, prefix, result, previous, kind),
+            (None, Some(style)) => format!("{prefix}{}", fourpart::render(result, style)),
+            (None, None) => format!("{prefix}{result}"),
+        }
+    }
+
+    /// Fill in a template's placeholders for this bump.
+    fn expand(
+        &self,
+        template: &Template,
+        prefix: &str,
+        result: &Version,
+        previous: Option<&Version>,
+        kind: &BumpKind,
+    ) -> String {
+        template.render(|name| match name {
+            "version" | "next" => result.to_string(),
+            "major" => result.major.to_string(),
+            "minor" => result.minor.to_string(),
+            "patch" => result.patch.to_string(),
+            "prerelease" => result.pre.to_string(),
+            "build" => result.build.to_string(),
+            "prefix" => prefix.to_string(),
+            "prev" => previous.map(Version::to_string).unwrap_or_default(),
+            "bump" => kind.to_string(),
+            "tag_prefix" => self.tag_prefix().to_string(),
+            "tag" => format!("{}{result}", self.tag_prefix()),
+            _ => String::new(),
+        })
+    }
+
+    /// Parse an input version, leniently or strictly if asked to.
+    fn parse(&self, input: &str) -> anyhow::Result<(String, Version)> {
+        if self.strict {
+            let input = input.trim_end_matches(['\n', '\r']);
+            if input.is_empty() {
+                return Err(error::Error::invalid(input, "The version is empty, which --strict doesn't allow.").into());
+            }
+            let version = validate::validate(input).map_err(|problem| {
+                error::Error::invalid(input, format!("`{input}` is not strict semver: {problem}")).with_hint(problem)
+            })?;
+            if !version.build.is_empty() {
+                return Err(error::Error::invalid(
+                    input,
+                    format!("`{input}` has build metadata, which --strict doesn't allow."),
+                )
+                .into());
+            }
+            return Ok((String::new(), version));
+        }
+        let description = match self.input_format {
+            InputFormat::Semver => None,
+            InputFormat::GitDescribe => Some(Description::parse(input)),
+        };
+        let (epoch, tag) = epoch::split(description.as_ref().map_or(input, |d| d.tag))?;
+        let (prefix, mut version) = if self.coerce {
+            coerce(tag)?
+        } else if self.four_part.is_some() {
+            fourpart::parse(tag)?
+        } else {
+            parse_prefixed(tag)?
+        };
+        if let Some(description) = description {
+            description.apply(&mut version, self.describe_as.unwrap_or_default())?;
+        }
+        Ok((format!("{epoch}{prefix}"), version))
+    }
+
+    /// The prefix to put in front of the version when naming the tag.
+    fn tag_prefix(&self) -> &str {
+        self.tag_prefix.as_deref().unwrap_or("v")
+    }
+
+    /// The bumping options to use.
+    fn options(&self) -> Options {
+        Options {
+            separator: self.separator.unwrap_or(Options::default().separator),
+            keep_prerelease: self.keep_prerelease,
+            keep_build: self.keep_build,
+            by: self.by,
+            zerover: self.zerover,
+            segment: self.segment,
+            pad: self.pad.map(usize::from),
+            continue_counter: self.continue_counter,
+            pre_date: self.pre_date_stamp.clone(),
+        }
+    }
+
+    /// Whether the previous version was passed on the command line or named with `--from-env`.
+    fn has_version(&self) -> bool {
+        self.version.is_some() || self.current.is_some() || self.from_env.is_some()
+    }
+
+    /// The prefix to print in front of the new version, given the previous version's prefix.
+    /// Any epoch is kept, since dropping it would change how the version sorts.
+    fn output_prefix(&self, previous: &str) -> String {
+        let (epoch, previous) = epoch::split(previous).unwrap_or(("", previous));
+        if self.strip_prefix {
+            epoch.to_string()
+        } else if self.keep_prefix && previous.is_empty() {
+            format!("{epoch}v")
+        } else {
+            format!("{epoch}{previous}")
+        }
+    }
+}
+
+/// The bumps `workspace` can make.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum WorkspaceBump {
+    Major,
+    Minor,
+    Patch,
+    Premajor,
+    Preminor,
+    Prepatch,
+    Prerelease,
+    Release,
+}
+
+impl WorkspaceBump {
+    /// The bump to make, using the given pre-release identifier where one is called for.
+    fn kind(self, identifier: Option<String>) -> BumpKind {
+        let identifier = identifier.unwrap_or_default();
+        match self {
+            WorkspaceBump::Major => BumpKind::Major,
+            WorkspaceBump::Minor => BumpKind::Minor,
+            WorkspaceBump::Patch => BumpKind::Patch,
+            WorkspaceBump::Premajor => BumpKind::Pre(Level::Major, identifier),
+            WorkspaceBump::Preminor => BumpKind::Pre(Level::Minor, identifier),
+            WorkspaceBump::Prepatch => BumpKind::Pre(Level::Patch, identifier),
+            WorkspaceBump::Prerelease => BumpKind::Prerelease(identifier),
+            WorkspaceBump::Release => BumpKind::Release,
+        }
+    }
+}
+
+/// The pieces of build metadata `--build-from-git` can add.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum GitPart {
+    Sha,
+    Count,
+    Dirty,
+}
+
+/// The shapes input versions can come in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum InputFormat {
+    #[default]
+    Semver,
+    GitDescribe,
+}
+
+/// What `--tag` does when the tag it would create already exists.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum TagCollision {
+    #[default]
+    Error,
+    Skip,
+    Next,
+}
+
+/// How `--error-format` reports a failure.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum ErrorFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+/// Options for the commands that bump a level and start a pre-release in one step.
+#[derive(Clone, Debug, clap::Args)]
+pub struct PreArgs {
+    /// The pre-release identifier to use; optional if you're re-using the name of the
+    /// existing identifier.
+    identifier: Option<String>,
+    #[command(flatten)]
+    args: BumpArgs,
+}
+
+impl PreArgs {
+    /// Run the bump at the given level.
+    fn run(self, level: Level, config: &Config) -> anyhow::Result<()> {
+        run_bump(
+            BumpKind::Pre(level, self.identifier.unwrap_or_default()),
+            self.args,
+            config,
+        )
+    }
+}
+
+/// Options for picking one version out of a list.
+#[derive(Clone, Debug, clap::Args)]
+pub struct PickArgs {
+    /// The versions to choose from. If not provided, they're read from stdin, one per line.
+    #[arg(value_parser = parse_version)]
+    versions: Vec<Version>,
+    /// Ignore pre-release versions.
+    #[arg(long)]
+    stable_only: bool,
+}
+
+impl PickArgs {
+    /// The versions given on the command line, or read from stdin if there were none.
+    fn versions(self) -> anyhow::Result<Vec<Version>> {
+        if self.versions.is_empty() {
+            list::parse(&read_all()?)
+        } else {
+            Ok(self.versions)
+        }
+    }
+}
+
+/// Options for looking up the latest published version.
+#[derive(Clone, Debug, clap::Args)]
+pub struct LatestArgs {
+    #[command(flatten)]
+    source: LatestSource,
+    /// Ignore pre-release versions.
+    #[arg(long)]
+    stable_only: bool,
+    /// Print the version this npm dist-tag points at, such as `next`, instead of the highest.
+    #[arg(long, requires = "npm", conflicts_with = "stable_only")]
+    dist_tag: Option<String>,
+}
+
+/// Where `latest` looks for published versions.
+#[derive(Clone, Debug, clap::Args)]
+#[group(required = true, multiple = false)]
+pub struct LatestSource {
+    /// The tags of this GitHub repository, given as `OWNER/NAME`. A token in $GITHUB_TOKEN or
+    /// $GH_TOKEN is used if there is one.
+    #[arg(long, value_name = "OWNER/NAME")]
+    github: Option<String>,
+    /// The versions of this crate on crates.io, leaving out yanked versions.
+    #[arg(long = "crate", value_name = "NAME")]
+    krate: Option<String>,
+    /// The versions of this package in the npm registry.
+    #[arg(long, value_name = "PACKAGE")]
+    npm: Option<String>,
+    /// The tags of this container image, given as `REGISTRY/NAME`, as in `ghcr.io/org/image`.
+    /// Only public images work.
+    #[arg(long, value_name = "IMAGE")]
+    oci: Option<String>,
+    /// The versions of this package on PyPI, translated from PEP 440 where they can be.
+    #[arg(long, value_name = "PACKAGE")]
+    pypi: Option<String>,
+}
+
+impl Command {
+    /// Whether the command reads anything from a `.semver-bump.toml`. Those that don't
+    /// leave it alone, so a broken one can't get in their way.
+    fn uses_config(&self) -> bool {
+        !matches!(
+            self,
+            Command::Change {
+                action: ChangeAction::Add { .. }
+            } | Command::Epoch { .. }
+                | Command::Calver { .. }
+                | Command::Compare { .. }
+                | Command::Diff { .. }
+                | Command::Precedes { .. }
+                | Command::Convert { .. }
+                | Command::Tags { .. }
+                | Command::Validate { .. }
+                | Command::Normalize { .. }
+                | Command::Sort { .. }
+                | Command::Max(_)
+                | Command::Min(_)
+                | Command::ChannelLatest { .. }
+                | Command::Filter { .. }
+                | Command::Latest(_)
+                | Command::Matches { .. }
+                | Command::Req { .. }
+                | Command::Notes { .. }
+                | Command::Completions { .. }
+                | Command::Get { .. }
+        )
+    }
+}
+
+/// The things `change` can do.
+#[derive(Clone, Debug, Subcommand)]
+pub enum ChangeAction {
+    /// Record a change in a new file, printing its path.
+    Add {
+        /// The bump the change calls for: major, minor, or patch.
+        #[arg(value_parser = Level::from_str)]
+        level: Level,
+        /// What changed, in Markdown.
+        message: String,
+        /// The directory to keep change files in.
+        #[arg(long, value_name = "PATH", default_value = changes::DEFAULT_DIR)]
+        dir: PathBuf,
+    },
+    /// Make the largest bump the pending changes call for, then delete their files. With
+    /// `--commit`, the deletions are committed along with the new version.
+    Apply {
+        /// The directory to keep change files in.
+        #[arg(long, value_name = "PATH", default_value = changes::DEFAULT_DIR)]
+        dir: PathBuf,
+        /// Write the changes' messages to this file as Markdown, grouped by the bump each
+        /// called for, for a changelog or release notes.
+        #[arg(long, value_name = "PATH")]
+        notes: Option<PathBuf>,
+        #[command(flatten)]
+        args: Box<BumpArgs>,
+    },
+}
+
+/// The things `req` can do.
+#[derive(Clone, Debug, Subcommand)]
+pub enum ReqAction {
+    /// Raise the upper bound past the next incompatible series, keeping the lower bound, as in
+    /// `^1.2` to `>=1.2, <3.0.0`.
+    BumpUpper {
+        /// The requirement to change.
+        requirement: String,
+    },
+    /// Raise the upper bound just enough to accept a version, keeping the lower bound, as in
+    /// `^1.2` to `>=1.2, <3.0.0` for `2.1.0`. A requirement that already accepts the version
+    /// is printed as it is.
+    Widen {
+        /// The requirement to change.
+        requirement: String,
+        /// The version it should accept.
+        #[arg(value_parser = parse_version)]
+        version: Version,
+    },
+    /// Raise the lower bound to a version the requirement already accepts, keeping its
+    /// operator, as in `^1.2` to `^1.4` for `1.4.0`.
+    Narrow {
+        /// The requirement to change.
+        requirement: String,
+        /// The version it should start at.
+        #[arg(value_parser = parse_version)]
+        version: Version,
+    },
+    /// Print the range of versions every requirement accepts, as in `>=1.4.0, <2.0.0` for
+    /// `^1.2` and `>=1.4`. If there are none, prints `none` and exits with status 1.
+    Intersect {
+        /// The requirements to intersect.
+        #[arg(required = true, value_parser = VersionReq::parse)]
+        requirements: Vec<VersionReq>,
+    },
+    /// Print the versions any requirement accepts, as the fewest ranges that cover them, one per
+    /// line, as in `>=1.2.0, <3.0.0` for `^1.2` and `^2`. If there are none, prints `none` and
+    /// exits with status 1.
+    Union {
+        /// The requirements to unite.
+        #[arg(required = true, value_parser = VersionReq::parse)]
+        requirements: Vec<VersionReq>,
+    },
+}
+
+/// Release the pending changes in the directory.
+fn apply_changes(dir: &Path, notes: Option<&Path>, mut args: BumpArgs, config: &Config) -> anyhow::Result<()> {
+    let (paths, pending): (Vec<PathBuf>, Vec<changes::Change>) = changes::pending(dir)?.into_iter().unzip();
+    let level = changes::level(&pending)
+        .ok_or_else(|| anyhow::anyhow!("There are no pending changes in {}.", dir.display()))?;
+    let dry_run = args.dry_run;
+    args.consumed = paths;
+    run_bump(BumpKind::Change(level), args, config)?;
+    if let Some(path) = notes.filter(|_| !dry_run) {
+        std::fs::write(path, changes::notes(&pending))
+            .with_context(|| format!("unable to write {}", path.display()))?;
+    }
+    Ok(())
+}
+
+/// Decide how big a bump the commits since the given ref call for, counting only
+/// commits under these paths and with these scopes, if there are any.
+fn auto_level(
+    git: &Git,
+    since: &str,
+    paths: &[PathBuf],
+    scopes: &[String],
+    rules: &conventional::Rules,
+) -> anyhow::Result<BumpKind> {
+    let mut messages = git.commit_messages_touching(since, "HEAD", paths)?;
+    if !scopes.is_empty() {
+        messages.retain(|message| {
+            conventional::parse_header(message)
+                .and_then(|header| header.scope)
+                .is_some_and(|scope| scopes.contains(&scope))
+        });
+    }
+    let level = rules
+        .analyze(&messages)
+        .ok_or_else(|| anyhow::anyhow!("None of the commits since {since} call for a version bump."))?;
+    Ok(BumpKind::Change(level))
+}
+
+/// Decide how big a bump the labels on the pull request behind this workflow run call for.
+fn label_level(config: &Config) -> anyhow::Result<BumpKind> {
+    let mapping: BTreeMap<String, Level> = if config.labels.is_empty() {
+        [Level::Major, Level::Minor, Level::Patch]
+            .into_iter()
+            .map(|level| (format!("semver:{level}"), level))
+            .collect()
+    } else {
+        config
+            .labels
+            .iter()
+            .map(|(label, level)| Ok((label.clone(), level.parse()?)))
+            .collect::<anyhow::Result<_>>()?
+    };
+    let github = github::Repository::from_env(None)?;
+    let (number, labels) = match github::event_pull_request()? {
+        Some(number) => (number, github.labels(number)?),
+        None => {
+            let sha = std::env::var("GITHUB_SHA")
+                .map_err(|_| anyhow::anyhow!("This isn't a GitHub Actions run for a pull request or a push."))?;
+            github
+                .merged_pull_request(&sha)?
+                .ok_or_else(|| anyhow::anyhow!("{sha} didn't come from a merged pull request."))?
+        }
+    };
+    let level = labels
+        .iter()
+        .filter_map(|label| mapping.get(label))
+        .max()
+        .ok_or_else(|| {
+            let wanted: Vec<&str> = mapping.keys().map(String::as_str).collect();
+            anyhow::anyhow!(
+                "Pull request #{number} has none of the labels that call for a bump: {}.",
+                wanted.join(", ")
+            )
+        })?;
+    Ok(BumpKind::Change(*level))
 }
 
-/// Increment the major version.
-fn major(previous: &Version) -> Version {
-    Version::new(previous.major + 1, 0, 0)
+/// Parse a version from the command line, accepting and dropping a leading `v`.
+fn parse_version(input: &str) -> anyhow::Result<Version> {
+    let (_, version) = parse_prefixed(input)?;
+    Ok(version)
 }
 
-/// Increment the minor version.
-fn minor(previous: &Version) -> Version {
-    Version::new(previous.major, previous.minor + 1, 0)
+/// Read the first line of stdin.
+fn read_line() -> anyhow::Result<String> {
+    let mut buffer = String::new();
+    let stdin = std::io::stdin();
+    stdin.read_line(&mut buffer)?;
+    Ok(buffer)
 }
 
-/// Increment the patch version.
-fn patch(previous: &Version) -> Version {
-    Version::new(previous.major, previous.minor, previous.patch + 1)
+/// Read all of stdin.
+fn read_all() -> anyhow::Result<String> {
+    let mut buffer = String::new();
+    std::io::stdin().read_to_string(&mut buffer)?;
+    Ok(buffer)
+}
+
+/// The branch we're on, or the one the CI system says it's building for a detached HEAD.
+fn current_branch(git: &Git) -> Option<String> {
+    git.current_branch().ok().or_else(ci::branch)
+}
+
+/// The release series the current branch is for.
+fn branch_series(git: &Git) -> anyhow::Result<Series> {
+    let branch = current_branch(git)
+        .ok_or_else(|| anyhow::anyhow!("HEAD is not on a branch, so --from-branch has no branch to read."))?;
+    Series::from_branch(&branch)
+        .ok_or_else(|| anyhow::anyhow!("The branch `{branch}` doesn't name a release series, as in `release/1.4.x`."))
+}
+
+/// The latest version in the series that's tagged and reachable from HEAD, or the
+/// first version of the series if none is yet.
+fn series_version(git: &Git, series: &Series, args: &BumpArgs) -> anyhow::Result<(String, Version)> {
+    let prefix = args.tag_prefix();
+    match git.latest_tag(&series.tag_pattern(prefix)) {
+        Ok(tag) => {
+            let (_, version) = args.parse(tag.strip_prefix(prefix).unwrap_or(&tag))?;
+            Ok((String::new(), version))
+        }
+        Err(_) => Ok((String::new(), series.base())),
+    }
+}
+
+/// Read the previous version from the command line or the environment variable
+/// it names, falling back to the first line of stdin. Returns any `v` prefix it
+/// had alongside the version.
+fn read_version(args: &BumpArgs) -> anyhow::Result<(String, Version)> {
+    let input = match (args.version.as_ref().or(args.current.as_ref()), args.from_env.as_ref()) {
+        (Some(v), _) => v.to_owned(),
+        (None, Some(name)) => match std::env::var(name) {
+            Ok(value) if !value.trim().is_empty() => value,
+            Ok(_) => return Err(anyhow::anyhow!("${name} is empty.")),
+            Err(_) => return Err(anyhow::anyhow!("${name} is not set.")),
+        },
+        (None, None) => read_line()?,
+    };
+    args.parse(&input)
+}
+
+/// The version file to use for `--file`. The name `auto` stands for the conventional
+/// `VERSION` or `.version` file at the root of the repository, or of the current
+/// directory outside one.
+fn version_file(path: &Path) -> anyhow::Result<PathBuf> {
+    if path != Path::new("auto") || path.is_file() {
+        return Ok(path.to_path_buf());
+    }
+    let cwd = std::fs::canonicalize(".")?;
+    let Ok(root) = Git::new(".").toplevel() else {
+        return VersionFile::discover(cwd);
+    };
+    // Name the file relative to where we are, as in `../VERSION`, so diffs read naturally.
+    let root = match cwd.strip_prefix(&root) {
+        Ok(below) => below.components().map(|_| "..").collect(),
+        Err(_) => root,
+    };
+    VersionFile::discover(root)
 }
 
-trait Incrementable: Display {
-    fn create_new(input: String) -> anyhow::Result<Box<Self>>;
+/// Bump the previous version, enforcing any guard rails requested.
+fn next_version(previous: &Version, kind: &BumpKind, bump_args: &BumpArgs) -> anyhow::Result<Version> {
+    let result = bump_with(previous, kind, &bump_args.options())?;
+    finish_version(previous, result, bump_args)
 }
 
-impl Incrementable for Prerelease {
-    fn create_new(input: String) -> anyhow::Result<Box<Prerelease>> {
-        match Prerelease::from_str(input.as_str()) {
-            Ok(v) => Ok(Box::new(v)),
-            Err(e) => Err(anyhow::Error::from(e)),
+/// Work out the new version, from the previous one if there is one. Only `set` can do
+/// without it.
+fn new_version(previous: Option<&Version>, kind: &BumpKind, bump_args: &BumpArgs) -> anyhow::Result<Version> {
+    match (previous, kind) {
+        (Some(previous), kind) => next_version(previous, kind, bump_args),
+        (None, BumpKind::Set(next)) => {
+            let result = stamp(next.clone(), bump_args)?;
+            check_ceiling(&result, bump_args)?;
+            Ok(result)
         }
+        (None, _) => unreachable!("we always read the previous version for relative bumps"),
     }
 }
 
-impl Incrementable for BuildMetadata {
-    fn create_new(input: String) -> anyhow::Result<Box<BuildMetadata>> {
-        match BuildMetadata::from_str(input.as_str()) {
-            Ok(v) => Ok(Box::new(v)),
-            Err(e) => Err(anyhow::Error::from(e)),
+/// Add the finishing touches asked for to a bumped version, then check it's allowed.
+fn finish_version(previous: &Version, result: Version, bump_args: &BumpArgs) -> anyhow::Result<Version> {
+    let result = stamp(result, bump_args)?;
+    if bump_args.require_greater && result.cmp_precedence(previous) != Ordering::Greater {
+        return Err(error::Error::constraint(format!(
+            "The new version {result} is not greater than the previous version {previous}."
+        ))
+        .into());
+    }
+    check_ceiling(&result, bump_args)?;
+    Ok(result)
+}
+
+/// Add the `--snapshot` marker and any build metadata asked for to a new version.
+fn stamp(mut result: Version, bump_args: &BumpArgs) -> anyhow::Result<Version> {
+    if bump_args.snapshot {
+        result.pre = Prerelease::new("SNAPSHOT")?;
+    }
+    if let Some(parts) = bump_args.build_from_git.as_ref() {
+        append_build(&mut result, &git_build_identifiers(parts)?)?;
+    }
+    if bump_args.build_from_ci {
+        let number = ci::build_number()
+            .ok_or_else(|| anyhow::anyhow!("--build-from-ci found no build number in the environment."))?;
+        append_build(&mut result, &["build".to_string(), number])?;
+    }
+    if let Some(format) = bump_args.build_timestamp.as_ref() {
+        append_build(&mut result, &[format.now()?])?;
+    }
+    if let Some(digest) = bump_args.build_hash.as_ref() {
+        append_build(&mut result, &["sha256".to_string(), digest.clone()])?;
+    }
+    Ok(result)
+}
+
+/// Refuse a new version above the ceiling set with `--max`, if there is one.
+fn check_ceiling(result: &Version, bump_args: &BumpArgs) -> anyhow::Result<()> {
+    match bump_args.max.as_ref() {
+        Some(max) if !max.admits(result) => Err(error::Error::constraint(format!(
+            "The new version {result} is above the maximum version {max}."
+        ))
+        .into()),
+        _ => Ok(()),
+    }
+}
+
+/// Describe the repository as build metadata identifiers.
+fn git_build_identifiers(parts: &[GitPart]) -> anyhow::Result<Vec<String>> {
+    let git = Git::new(".");
+    let mut identifiers = Vec::new();
+    for part in parts {
+        match part {
+            GitPart::Sha => identifiers.push(format!("g{}", git.short_sha()?)),
+            GitPart::Count => identifiers.push(git.commit_count()?.to_string()),
+            GitPart::Dirty if git.is_dirty()? => identifiers.push("dirty".to_string()),
+            GitPart::Dirty => {}
         }
     }
+    Ok(identifiers)
 }
 
-/// Increment the passed-in separator plus maybe-number.
-fn increment_identifier(suffix: &str) -> anyhow::Result<String> {
-    let mut characters = suffix.chars().peekable();
+/// Append identifiers to a version's build metadata.
+fn append_build(version: &mut Version, identifiers: &[String]) -> anyhow::Result<()> {
+    let mut build: Vec<&str> = Vec::new();
+    if !version.build.is_empty() {
+        build.push(version.build.as_str());
+    }
+    build.extend(identifiers.iter().map(String::as_str));
+    version.build = BuildMetadata::new(&build.join("."))?;
+    Ok(())
+}
 
-    if let Some(maybe_sep) = characters.peek() {
-        if SEPARATORS.contains(maybe_sep) {
-            let separator = characters.next().expect("but we just checked this character!");
-            let remainder: String = characters.collect();
-            let number = remainder.parse::<u64>()?;
-            return Ok(format!("{separator}{}", number + 1));
-        } else if maybe_sep.is_ascii_digit() {
-            let number = suffix.parse::<u64>()?;
-            // preserve lack of separator
-            return Ok(format!("{}", number + 1));
+/// Bump every version on stdin independently, printing one result per line.
+/// Blank lines are passed through so the output lines up with the input, unless
+/// `--strict` makes them an error.
+fn bump_each(kind: &BumpKind, bump_args: &BumpArgs) -> anyhow::Result<()> {
+    for (idx, line) in read_all()?.lines().enumerate() {
+        if line.trim().is_empty() && !bump_args.strict {
+            println!();
+            continue;
         }
+        let (prefix, previous) = bump_args.parse(line).with_context(|| format!("line {}", idx + 1))?;
+        let result = next_version(&previous, kind, bump_args).with_context(|| format!("line {}", idx + 1))?;
+        println!(
+            "{}",
+            bump_args.render(&bump_args.output_prefix(&prefix), &result, Some(&previous), kind)
+        );
     }
-    Ok(format!("{suffix}.1"))
+    Ok(())
 }
 
-/// Update the identifier for this version number.
-/// If we don't have an existing identifier, we add one.
-/// If we have an existing identifier that matches a passed-in tag, we increment.
-/// If we have an existing identifier and no passed-in tag, we increment existing.
-/// If we have no existing identifier and no tag, we report an input error to the user.
-fn increment<T: Incrementable>(input: &T, tag: &str) -> anyhow::Result<Box<T>> {
-    let previous = input.to_string();
+/// Open the files a bump reads its version from and writes it to: the `--file` or
+/// `--manifest` given, or else the files listed in the config file.
+fn open_targets(bump_args: &BumpArgs) -> anyhow::Result<Vec<Box<dyn Manifest>>> {
+    let targets: Vec<Box<dyn Manifest>> = if let Some(path) = bump_args.file.as_ref() {
+        vec![Box::new(VersionFile::open(version_file(path)?)?)]
+    } else if let Some((path, key)) = bump_args.manifest.as_ref().zip(bump_args.key.as_deref()) {
+        vec![Box::new(manifest::KeyedFile::open(path, key)?)]
+    } else if let Some(path) = bump_args.manifest.as_ref() {
+        let kind = bump_args.manifest_type.clone().or_else(|| manifest::Kind::detect(path));
+        match kind {
+            Some(manifest::Kind::Helm) => vec![Box::new(
+                HelmChart::open(path)?.with_fields(bump_args.chart_field.clone()),
+            )],
+            _ if !bump_args.chart_field.is_empty() => {
+                return Err(anyhow::anyhow!("--chart-field only works when bumping a Chart.yaml."));
+            }
+            Some(kind) => vec![kind.open(path)?],
+            None => vec![manifest::open(path)?],
+        }
+    } else {
+        bump_args
+            .config_files
+            .iter()
+            .map(Target::open)
+            .collect::<anyhow::Result<_>>()?
+    };
+    Ok(targets)
+}
 
-    let identifier = if tag.is_empty() && !previous.is_empty() {
-        if let Some(idx) = previous.rfind(SEPARATORS) {
-            let split = previous.split_at(idx);
-            let incremented = increment_identifier(split.1)?;
-            format!("{}{incremented}", split.0)
-        } else {
-            match increment_identifier(previous.to_string().as_str()) {
-                Ok(v) => v,
-                Err(_) => {
-                    format!("{}.1", previous)
+/// Bump the previous version, writing it wherever it was requested.
+fn run_bump(kind: BumpKind, bump_args: BumpArgs, config: &Config) -> anyhow::Result<()> {
+    if bump_args.sign && !(bump_args.tag || bump_args.commit) {
+        return Err(anyhow::anyhow!("--sign only applies to --tag and --commit."));
+    }
+    if bump_args.push.is_some() && !(bump_args.tag || bump_args.commit) {
+        return Err(anyhow::anyhow!(
+            "--push needs a tag or commit to push; pass --tag or --commit."
+        ));
+    }
+    let bump_args = bump_args.with_config(config)?;
+    if bump_args.each {
+        return bump_each(&kind, &bump_args);
+    }
+
+    let mut targets = open_targets(&bump_args)?;
+
+    let git = Git::new(".");
+    let series = if bump_args.from_branch {
+        Some(branch_series(&git)?)
+    } else {
+        None
+    };
+
+    // Setting an explicit version is the one case where we might not need to know the old one.
+    let needs_previous = !matches!(kind, BumpKind::Set(_)) || bump_args.has_version() || bump_args.require_greater;
+    let (prefix, previous) = match targets.first() {
+        Some(first) => {
+            let previous = first.version()?;
+            for other in &targets[1..] {
+                let version = other.version()?;
+                if version != previous {
+                    return Err(anyhow::anyhow!(
+                        "{} has version {version}, but {} has version {previous}.",
+                        other.path().display(),
+                        first.path().display()
+                    ));
                 }
             }
+            (first.prefix(), Some(previous))
         }
-    } else if !tag.is_empty() && tag != previous {
-        let last = tag.chars().last().unwrap_or_default();
-        if last.is_ascii_digit() {
-            tag.to_owned()
-        } else {
-            format!("{tag}.1")
-        }
-    } else if !tag.is_empty() && previous.starts_with(tag) {
-        let remainder = previous.to_string().replace(tag, "");
-        let incremented = increment_identifier(remainder.as_str())?;
-        format!("{tag}{incremented}")
-    } else if !tag.is_empty() {
-        format!("{tag}.1")
-    } else if !previous.is_empty() {
-        increment_identifier(previous.to_string().as_str())?
+        None if needs_previous => {
+            let (prefix, previous) = match series.as_ref() {
+                Some(series) if !bump_args.has_version() => series_version(&git, series, &bump_args)?,
+                _ => read_version(&bump_args)?,
+            };
+            (prefix, Some(previous))
+        }
+        None => (String::new(), None),
+    };
+    let prefix = &bump_args.output_prefix(&prefix);
+    // Report the concrete bump made, rather than the size of change asked for.
+    let kind = match previous.as_ref() {
+        Some(previous) => kind.resolve(previous, &bump_args.options()),
+        None => kind,
+    };
+    let mut result = new_version(previous.as_ref(), &kind, &bump_args)?;
+
+    // Check for a tag collision before we touch any files.
+    let mut tag = None;
+    if bump_args.tag {
+        (result, tag) = free_tag(&git, previous.as_ref(), &kind, result, &bump_args)?;
+    }
+    if let Some(series) = series.filter(|series| !series.contains(&result)) {
+        return Err(error::Error::constraint(format!(
+            "The new version {result} is outside the {series} series this branch is for."
+        ))
+        .into());
+    }
+    if bump_args.commit {
+        if targets.is_empty() && bump_args.changelog.is_none() {
+            return Err(anyhow::anyhow!(
+                "--commit needs files to bump; pass --file or --manifest, or list files in .semver-bump.toml."
+            ));
+        }
+        if git.has_staged_changes()? {
+            return Err(anyhow::anyhow!(
+                "Other changes are already staged; commit or unstage them before using --commit."
+            ));
+        }
+    }
+
+    let github = if bump_args.github_release {
+        let github = github::Repository::from_env(bump_args.github_repo.as_deref())?;
+        github.require_token()?;
+        Some(github)
     } else {
-        return Err(anyhow!(
-            "The current version does not have a prerelease suffix and you did not provide one."
+        None
+    };
+
+    // Work out what to push now, so a detached HEAD is caught before anything changes.
+    let mut refspecs = Vec::new();
+    if bump_args.push.is_some() {
+        if bump_args.commit {
+            refspecs.push(format!("HEAD:refs/heads/{}", git.current_branch()?));
+        }
+        if let Some(tag) = tag.as_ref() {
+            refspecs.push(format!("refs/tags/{tag}"));
+        }
+    }
+
+    let hook_bump = hooks::Bump {
+        previous: previous.as_ref().map(Version::to_string),
+        version: result.to_string(),
+        kind: kind.to_string(),
+        tag: tag.clone(),
+    };
+    if !bump_args.dry_run {
+        hooks::run("pre_bump", &config.pre_bump, &hook_bump)?;
+    }
+
+    let mut changelog = match bump_args.changelog.as_ref() {
+        Some(path) => Some(Changelog::open(path, bump_args.tag_prefix(), calver::Date::today()?)?),
+        None => None,
+    };
+    for target in targets.iter_mut() {
+        let original = target.contents();
+        target.set_version(&result)?;
+        if bump_args.dry_run {
+            print!("{}", manifest::diff(&original, target.as_ref()));
+        }
+    }
+    if let Some(changelog) = changelog.as_mut() {
+        let original = changelog.contents();
+        changelog.set_version(&result)?;
+        if bump_args.dry_run {
+            print!("{}", manifest::diff(&original, changelog));
+        }
+    }
+    // Rewrite the dependents now, so they're written with everything else or not at all.
+    let dependents = if bump_args.update_dependents {
+        update_dependents(&targets, &result)?
+    } else {
+        Vec::new()
+    };
+    if bump_args.dry_run {
+        for dependent in &dependents {
+            let original = std::fs::read_to_string(dependent.path())?;
+            print!("{}", manifest::diff(&original, dependent));
+        }
+        if let Some(remote) = bump_args.push.as_deref() {
+            for refspec in &refspecs {
+                println!("would push {refspec} to {remote}");
+            }
+        }
+        if let (Some(github), Some(tag)) = (github.as_ref(), tag.as_ref()) {
+            println!("would create a GitHub release for {tag} in {}", github.slug());
+        }
+        for path in &bump_args.consumed {
+            println!("would remove {}", path.display());
+        }
+        println!("{}", bump_args.render(prefix, &result, previous.as_ref(), &kind));
+        return Ok(());
+    }
+    let mut updated: Vec<&dyn Manifest> = targets.iter().map(|t| t.as_ref()).collect();
+    if let Some(changelog) = changelog.as_ref() {
+        updated.push(changelog);
+    }
+    for dependent in &dependents {
+        updated.push(dependent);
+    }
+    let mut backup = manifest::Backup::default();
+    for manifest in &updated {
+        backup.save(manifest.path());
+    }
+    manifest::write_all(updated.iter().copied())?;
+    let mut written: Vec<PathBuf> = Vec::new();
+    for manifest in &updated {
+        if !written.iter().any(|path| same_file(path, manifest.path())) {
+            written.push(manifest.path().to_path_buf());
+        }
+    }
+    if let Err(e) = hooks::run("post_bump", &config.post_bump, &hook_bump) {
+        backup.restore()?;
+        return Err(e.context("the bumped files were put back as they were"));
+    }
+    for path in &bump_args.consumed {
+        // Only a deletion git knows the file for can be committed.
+        let tracked = bump_args.commit && git.is_tracked(path)?;
+        std::fs::remove_file(path).with_context(|| format!("unable to remove {}", path.display()))?;
+        if tracked {
+            written.push(path.clone());
+        }
+    }
+    if bump_args.commit {
+        let default = Template::from_str("Bump version from {prev} to {next}")?;
+        let template = bump_args.message.as_ref().unwrap_or(&default);
+        let message = bump_args.expand(template, prefix, &result, previous.as_ref(), &kind);
+        let paths: Vec<&Path> = written.iter().map(PathBuf::as_path).collect();
+        git.commit_files(&paths, &message, bump_args.sign)?;
+    }
+    if let Some(tag) = tag.as_ref() {
+        git.create_tag(tag, tag, bump_args.sign)?;
+    }
+    if let Some(remote) = bump_args.push.as_deref() {
+        git.push(remote, &refspecs)?;
+    }
+    if let (Some(github), Some(tag)) = (github.as_ref(), tag.as_ref()) {
+        // Without the previous release's tag to start from, GitHub writes the notes itself.
+        let body = match previous.as_ref().map(|p| format!("{}{p}", bump_args.tag_prefix())) {
+            Some(since) if git.tag_exists(&since)? => Some(notes::render(&git.commit_messages_between(&since, tag)?)),
+            _ => None,
+        };
+        let release = github::Release {
+            tag: tag.clone(),
+            body,
+            draft: bump_args.draft,
+            prerelease: !result.pre.is_empty(),
+        };
+        github.create_release(&release)?;
+    }
+    let next = format!("{prefix}{result}");
+    let outputs = |names| ci_outputs(names, &next, previous.as_ref(), &kind);
+    if let Some(name) = bump_args.github_output.as_deref() {
+        ci::github_output(&outputs([name, "previous", "bump"]))?;
+    }
+    if let Some(path) = bump_args.gitlab_dotenv.as_deref() {
+        ci::gitlab_dotenv(path, &outputs(["VERSION", "PREVIOUS_VERSION", "BUMP"]))?;
+    }
+    match bump_args.ci.and_then(ci::Choice::resolve) {
+        Some(ci::System::GitHub) => ci::github_output(&outputs(["version", "previous", "bump"]))?,
+        Some(ci::System::GitLab) => ci::gitlab_dotenv(
+            Path::new("build.env"),
+            &outputs(["VERSION", "PREVIOUS_VERSION", "BUMP"]),
+        )?,
+        Some(system) => {
+            for command in system.commands(&outputs(["version", "previous", "bump"])) {
+                println!("{command}");
+            }
+        }
+        None => {}
+    }
+    if bump_args.summary {
+        let none = || "none".to_string();
+        let rows = [
+            (
+                "Previous version",
+                previous.as_ref().map(Version::to_string).unwrap_or_else(none),
+            ),
+            ("New version", next.clone()),
+            ("Bump", kind.to_string()),
+            ("Tag", tag.clone().unwrap_or_else(none)),
+        ];
+        let table = ci::summary_table(&rows);
+        ci::github_step_summary(&format!("### Released {next}\n\n{table}"))?;
+    }
+    println!("{}", bump_args.render(prefix, &result, previous.as_ref(), &kind));
+
+    Ok(())
+}
+
+/// The new version, the previous one if there was one, and the kind of bump, under the
+/// given names, ready to hand to a CI system.
+fn ci_outputs<'a>(
+    names: [&'a str; 3],
+    next: &str,
+    previous: Option<&Version>,
+    kind: &BumpKind,
+) -> Vec<(&'a str, String)> {
+    let [version, previous_name, bump] = names;
+    let mut outputs = vec![(version, next.to_string())];
+    outputs.extend(previous.map(|previous| (previous_name, previous.to_string())));
+    outputs.push((bump, kind.to_string()));
+    outputs
+}
+
+/// The error for a tag that's already taken, with a hint about how to get past it.
+fn tag_exists(message: String) -> error::Error {
+    error::Error::constraint(message)
+        .with_hint("pass --if-tag-exists next to try the versions after it, or skip to leave it untagged")
+}
+
+/// Settle on the new version and its tag, following `--if-tag-exists` when the tag is
+/// already taken. Returns no tag when it should be skipped.
+fn free_tag(
+    git: &Git,
+    previous: Option<&Version>,
+    kind: &BumpKind,
+    mut result: Version,
+    bump_args: &BumpArgs,
+) -> anyhow::Result<(Version, Option<String>)> {
+    // Read the remote's tags once, rather than asking it about every candidate.
+    let remote_tags = match bump_args.check_remote.as_deref() {
+        Some(remote) => git.remote_tags(remote)?,
+        None => Vec::new(),
+    };
+    let taken =
+        |tag: &str| -> anyhow::Result<bool> { Ok(remote_tags.iter().any(|t| t == tag) || git.tag_exists(tag)?) };
+    let mut tag = format!("{}{result}", bump_args.tag_prefix());
+    if !taken(&tag)? {
+        return Ok((result, Some(tag)));
+    }
+    match bump_args.if_tag_exists {
+        TagCollision::Error => match bump_args.check_remote.as_deref() {
+            Some(remote) if !git.tag_exists(&tag)? => {
+                return Err(tag_exists(format!("The tag {tag} already exists on {remote}.")).into())
+            }
+            _ => return Err(tag_exists(format!("The tag {tag} already exists.")).into()),
+        },
+        TagCollision::Skip => return Ok((result, None)),
+        TagCollision::Next => {}
+    }
+    // Repeat the bump one step at a time, so `minor` tries 1.3.0, then 1.4.0, and a
+    // pre-release tries each counter in turn.
+    let step = match kind {
+        BumpKind::Major | BumpKind::Minor | BumpKind::Patch => kind.clone(),
+        BumpKind::Prerelease(_) | BumpKind::Pre(..) | BumpKind::Promote { .. } => BumpKind::Prerelease(String::new()),
+        BumpKind::Build(_) => BumpKind::Build(String::new()),
+        _ => {
+            return Err(error::Error::constraint(format!(
+                "The tag {tag} already exists, and a {kind} bump has no next version to try."
+            ))
+            .into())
+        }
+    };
+    let Some(previous) = previous else {
+        return Err(error::Error::constraint(format!("The tag {tag} already exists.")).into());
+    };
+    let options = Options {
+        by: 1,
+        ..bump_args.options()
+    };
+    let mut plain = bump_with(previous, kind, &bump_args.options())?;
+    while taken(&tag)? {
+        let next = bump_with(&plain, &step, &options)?;
+        if next == plain {
+            return Err(error::Error::constraint(format!(
+                "The tag {tag} already exists, and there is no next version to try."
+            ))
+            .into());
+        }
+        plain = next;
+        result = finish_version(previous, plain.clone(), bump_args)?;
+        tag = format!("{}{result}", bump_args.tag_prefix());
+    }
+    Ok((result, Some(tag)))
+}
+
+/// Rewrite the requirements the workspace places on each bumped crate to accept the new
+/// version, returning the manifests that changed. A dependent that's also being bumped
+/// starts from its bumped contents, and one that depends on several of the crates gets
+/// all of the changes, so writing them after the targets loses nothing.
+fn update_dependents(targets: &[Box<dyn Manifest>], next: &Version) -> anyhow::Result<Vec<CargoManifest>> {
+    let crates: Vec<&Path> = targets
+        .iter()
+        .map(|t| t.path())
+        .filter(|p| p.file_name().is_some_and(|n| n == "Cargo.toml"))
+        .collect();
+    if crates.is_empty() {
+        return Err(anyhow::anyhow!(
+            "--update-dependents only works when bumping a Cargo.toml."
         ));
+    }
+    let mut dependents: Vec<CargoManifest> = Vec::new();
+    for path in crates {
+        let manifest = CargoManifest::open(path)?;
+        let name = manifest
+            .name()
+            .ok_or_else(|| anyhow::anyhow!("{} does not have a package.name field", path.display()))?;
+        let mut workspace = Workspace::discover(path)?
+            .ok_or_else(|| anyhow::anyhow!("{} is not a member of a cargo workspace.", path.display()))?;
+        for dependent in workspace.update_dependents(name, next)? {
+            if let Some(earlier) = dependents.iter_mut().find(|m| same_file(m.path(), dependent.path())) {
+                earlier.update_dependency(name, next)?;
+                continue;
+            }
+            let updated = match targets.iter().find(|t| same_file(t.path(), dependent.path())) {
+                Some(target) => {
+                    let mut bumped = CargoManifest::parse(dependent.path(), &target.contents())?;
+                    bumped.update_dependency(name, next)?;
+                    bumped
+                }
+                None => dependent.clone(),
+            };
+            dependents.push(updated);
+        }
+    }
+    Ok(dependents)
+}
+
+/// Whether two paths name the same file, however they're spelled.
+fn same_file(left: &Path, right: &Path) -> bool {
+    match (std::fs::canonicalize(left), std::fs::canonicalize(right)) {
+        (Ok(left), Ok(right)) => left == right,
+        _ => left == right,
+    }
+}
+
+/// Bump every package in a cargo workspace, writing the manifests that changed.
+fn bump_workspace(root: &Path, kind: &BumpKind, dry_run: bool, config: &Config) -> anyhow::Result<()> {
+    let mut workspace = Workspace::open(root)?;
+    let originals: Vec<String> = workspace.manifests().map(|m| m.contents()).collect();
+    let options = Options {
+        separator: config.separator.unwrap_or(Options::default().separator),
+        ..Default::default()
     };
+    let changes = workspace.bump(kind, &options)?;
+    let mut changed: Vec<&dyn Manifest> = Vec::new();
+    for (original, manifest) in originals.iter().zip(workspace.manifests()) {
+        if *original == manifest.contents() {
+            continue;
+        }
+        if dry_run {
+            print!("{}", manifest::diff(original, manifest));
+        } else {
+            changed.push(manifest);
+        }
+    }
+    manifest::write_all(changed)?;
+    for change in changes {
+        println!("{} {}", change.name, change.next);
+    }
+    Ok(())
+}
 
-    let next = T::create_new(identifier)?;
-    Ok(next)
+/// Parse a release notes template.
+fn notes_template(input: &str) -> anyhow::Result<Template> {
+    Template::parse(input, &notes::PLACEHOLDERS)
 }
 
-/// Replace or add a prerelease identifier, or increment the number at the
-/// end of an existing prerelease identifier.
-fn prerelease(previous: &Version, tag: &str) -> anyhow::Result<Version> {
-    let mut next = Version::new(previous.major, previous.minor, previous.patch);
-    let identifier = increment(&previous.pre, tag)?;
-    next.pre = *identifier;
-    Ok(next)
+/// Print release notes for the commits between two refs.
+fn release_notes(
+    from: &str,
+    to: &str,
+    next: Option<Version>,
+    prev: Option<Version>,
+    template: Option<Template>,
+) -> anyhow::Result<()> {
+    let messages = Git::new(".").commit_messages_between(from, to)?;
+    let rendered = notes::render(&messages);
+    let template = match (template, next.is_some()) {
+        (Some(template), _) => template,
+        (None, true) => notes_template("## {next} - {date}\n\n{notes}")?,
+        (None, false) => notes_template("{notes}")?,
+    };
+    let prev = prev.or_else(|| parse_prefixed(from).ok().map(|(_, version)| version));
+    let calver::Date { year, month, day } = calver::Date::today()?;
+    let output = template.render(|name| match name {
+        "notes" => rendered.clone(),
+        "prev" => prev.as_ref().map(Version::to_string).unwrap_or_default(),
+        "next" => next.as_ref().map(Version::to_string).unwrap_or_default(),
+        "from" => from.to_string(),
+        "to" => to.to_string(),
+        "date" => format!("{year:04}-{month:02}-{day:02}"),
+        _ => String::new(),
+    });
+    print!("{output}");
+    if !output.ends_with('\n') {
+        println!();
+    }
+    Ok(())
 }
 
-/// This works just like prerelease, only it operates on the build segment.
-fn build(previous: &Version, tag: &str) -> anyhow::Result<Version> {
-    let mut next = Version::new(previous.major, previous.minor, previous.patch);
-    next.pre = previous.pre.clone();
-    let identifier = increment(&previous.build, tag)?;
-    next.build = *identifier;
-    Ok(next)
+/// Check that the version in the given file, manifest, or configured files matches a
+/// tag pointing at HEAD, printing the tag.
+fn verify_tag(
+    file: Option<PathBuf>,
+    manifest: Option<PathBuf>,
+    tag_prefix: Option<String>,
+    config: &Config,
+) -> anyhow::Result<()> {
+    let targets: Vec<Box<dyn Manifest>> = match (file, manifest) {
+        (Some(path), _) => vec![Box::new(VersionFile::open(version_file(&path)?)?)],
+        (None, Some(path)) => vec![manifest::open(path)?],
+        (None, None) => config
+            .targets()
+            .iter()
+            .map(Target::open)
+            .collect::<anyhow::Result<_>>()?,
+    };
+    let Some((first, rest)) = targets.split_first() else {
+        return Err(anyhow::anyhow!(
+            "There is no version to check; pass --file or --manifest, or list files in .semver-bump.toml."
+        ));
+    };
+    let version = first.version()?;
+    for other in rest {
+        let other_version = other.version()?;
+        if other_version != version {
+            return Err(anyhow::anyhow!(
+                "{} has version {other_version}, but {} has version {version}.",
+                other.path().display(),
+                first.path().display()
+            ));
+        }
+    }
+    let prefix = tag_prefix.or_else(|| config.tag_prefix.clone());
+    let tags = Git::new(".").tags_at_head()?;
+    let tag = matching_tag(&version, &tags, prefix.as_deref().unwrap_or("v"))
+        .with_context(|| format!("{} has version {version}", first.path().display()))?;
+    println!("{tag}");
+    Ok(())
 }
 
-fn main() -> anyhow::Result<()> {
-    let args = Args::parse();
+/// Report the problems with the tags, exiting with a failure if there are any.
+fn lint_tags(stdin: bool, prefix: &str, json: bool) -> anyhow::Result<ExitCode> {
+    let tags = if stdin {
+        read_all()?.lines().map(str::to_string).collect()
+    } else {
+        Git::new(".").tags()?
+    };
+    let findings = lint::lint(&tags, prefix);
+    if json {
+        let entries: Vec<serde_json::Value> = findings
+            .iter()
+            .map(|finding| {
+                serde_json::json!({
+                    "kind": finding.kind.to_string(),
+                    "tag": finding.tag,
+                    "message": finding.message,
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+    } else {
+        for finding in &findings {
+            println!("{} {}: {}", finding.kind, finding.tag, finding.message);
+        }
+    }
+    if findings.is_empty() {
+        Ok(ExitCode::SUCCESS)
+    } else {
+        Ok(ExitCode::FAILURE)
+    }
+}
 
-    let mut buffer = String::new();
-    let stdin = std::io::stdin();
-    stdin.read_line(&mut buffer)?;
-    let trimmed = buffer.trim();
-    let previous = Version::parse(trimmed)?;
+/// Find the tag that names this version with the given prefix.
+fn matching_tag<'a>(version: &Version, tags: &'a [String], prefix: &str) -> anyhow::Result<&'a str> {
+    if tags.is_empty() {
+        return Err(anyhow::anyhow!("no tag points at HEAD."));
+    }
+    tags.iter()
+        .map(String::as_str)
+        .find(|tag| {
+            tag.strip_prefix(prefix)
+                .and_then(|rest| Version::parse(rest).ok())
+                .is_some_and(|tagged| tagged == *version)
+        })
+        .ok_or_else(|| anyhow::anyhow!("HEAD is tagged {}.", tags.join(", ")))
+}
 
-    let result = match args.cmd {
-        Command::Major => major(&previous),
-        Command::Minor => minor(&previous),
-        Command::Patch => patch(&previous),
-        Command::Prerelease { identifier } => {
-            let tag = identifier.unwrap_or_default();
-            prerelease(&previous, tag.as_str())?
+/// Print every version that could follow this one, as lines or as JSON.
+fn next_candidates(version: Option<String>, channels: &[String], json: bool) -> anyhow::Result<()> {
+    let input = match version {
+        Some(v) => v,
+        None => read_line()?,
+    };
+    let (prefix, previous) = parse_prefixed(&input)?;
+    let found = candidates(&previous, channels)?;
+    if json {
+        let entries: Vec<serde_json::Value> = found
+            .iter()
+            .map(|(kind, next)| {
+                serde_json::json!({
+                    "version": format!("{prefix}{next}"),
+                    "bump": kind.to_string(),
+                    "identifier": identifier(kind),
+                })
+            })
+            .collect();
+        println!("{}", serde_json::to_string_pretty(&entries)?);
+    } else {
+        for (kind, next) in &found {
+            match identifier(kind) {
+                Some(id) => println!("{prefix}{next} {kind} {id}"),
+                None => println!("{prefix}{next} {kind}"),
+            }
         }
-        Command::Build { identifier } => {
-            let tag = identifier.unwrap_or_default();
-            build(&previous, tag.as_str())?
+    }
+    Ok(())
+}
+
+/// Offer the candidates for the next version and make the bump picked.
+fn interactive(channels: &[String], args: BumpArgs, config: &Config) -> anyhow::Result<()> {
+    let resolved = args.clone().with_config(config)?;
+    let (prefix, previous) = match open_targets(&resolved)?.first() {
+        Some(first) => (first.prefix(), first.version()?),
+        None if resolved.has_version() => read_version(&resolved)?,
+        None => {
+            return Err(anyhow::anyhow!(
+                "interactive can't read the current version from stdin; pass a version, --file, or --manifest."
+            ))
         }
     };
+    let found = candidates(&previous, channels)?;
+    let choices: Vec<String> = found
+        .iter()
+        .map(|(kind, next)| match identifier(kind) {
+            Some(id) => format!("{prefix}{next}  {kind} {id}"),
+            None => format!("{prefix}{next}  {kind}"),
+        })
+        .collect();
+    let prompt = format!("The current version is {prefix}{previous}. Bump it to:");
+    match menu::pick(&prompt, &choices)?.and_then(|picked| found.into_iter().nth(picked)) {
+        Some((kind, _)) => run_bump(kind, args, config),
+        None => Err(anyhow::anyhow!("Nothing was picked, so nothing was bumped.")),
+    }
+}
+
+/// The pre-release identifier or channel a bump names, if it names one.
+fn identifier(kind: &BumpKind) -> Option<&str> {
+    match kind {
+        BumpKind::Prerelease(id) | BumpKind::Pre(_, id) if !id.is_empty() => Some(id),
+        BumpKind::Promote { to, .. } => to.as_deref(),
+        _ => None,
+    }
+}
+
+/// Bump a calendar version, writing it back to a file if it was read from one.
+fn bump_calver(
+    version: Option<String>,
+    pattern: &calver::Pattern,
+    date: Option<calver::Date>,
+    file: Option<PathBuf>,
+) -> anyhow::Result<()> {
+    let today = match date {
+        Some(date) => date,
+        None => calver::Date::today()?,
+    };
+    let previous = match (version, file.as_ref()) {
+        (Some(version), _) => version,
+        (None, Some(path)) => std::fs::read_to_string(path)?,
+        (None, None) => read_line()?,
+    };
+    let result = calver::bump(&previous, pattern, &today)?;
+    if let Some(path) = file.as_ref() {
+        manifest::write_atomically(path, &format!("{result}\n"))?;
+    }
     println!("{result}");
+    Ok(())
+}
+
+/// Print how the two versions compare, exiting with a matching status code.
+fn compare(left: &(u64, Version), right: &(u64, Version)) -> ExitCode {
+    let (word, code) = match epoch::cmp_precedence(left, right) {
+        Ordering::Less => ("lt", 10),
+        Ordering::Equal => ("eq", 0),
+        Ordering::Greater => ("gt", 11),
+    };
+    println!("{word}");
+    ExitCode::from(code)
+}
+
+/// Print the most significant difference between two versions, exiting with a matching status code.
+fn difference(left: &Version, right: &Version) -> ExitCode {
+    let difference = diff(left, right);
+    let code = match difference {
+        None => 0,
+        Some(Difference::Major) => 20,
+        Some(Difference::Minor) => 21,
+        Some(Difference::Patch) => 22,
+        Some(Difference::Prerelease) => 23,
+        Some(Difference::Build) => 24,
+    };
+    let word = difference.map_or("none".to_string(), |d| d.to_string());
+    println!("{word}");
+    ExitCode::from(code)
+}
 
+/// Check that the new version comes after the old one, and, if asked, that it skips nothing.
+fn precedes(old: &Version, new: &Version, strict_succession: bool) -> anyhow::Result<()> {
+    if new.cmp_precedence(old) != Ordering::Greater {
+        return Err(error::Error::constraint(format!("{new} does not come after {old}.")).into());
+    }
+    if strict_succession && !is_successor(old, new) {
+        let its_release = if old.pre.is_empty() {
+            String::new()
+        } else {
+            format!("{}, ", release(old))
+        };
+        return Err(error::Error::constraint(format!(
+            "{new} skips ahead of {old}; the next release would be {its_release}{}, {}, or {}.",
+            patch(old),
+            minor(old),
+            major(old)
+        ))
+        .into());
+    }
     Ok(())
 }
 
+/// Print what a version means, followed by what each bump would make of it.
+fn explain(version: Option<String>, channels: &[String]) -> anyhow::Result<()> {
+    let input = match version {
+        Some(v) => v,
+        None => read_line()?,
+    };
+    let (prefix, version) = parse_prefixed(&input)?;
+    print!("{}", explain::explain(&version));
+    println!("\nEach bump would make:");
+    for (kind, next) in candidates(&version, channels)? {
+        let bump = match identifier(&kind) {
+            Some(id) => format!("{kind} {id}"),
+            None => kind.to_string(),
+        };
+        println!("  {bump:<16} {prefix}{next}");
+    }
+    Ok(())
+}
+
+/// Check a candidate version, explaining what's wrong with it if it's invalid.
+fn validate(candidate: Option<String>) -> anyhow::Result<()> {
+    let candidate = match candidate {
+        Some(c) => c,
+        None => read_line()?.trim_end_matches(['\n', '\r']).to_string(),
+    };
+    match validate::validate(&candidate) {
+        Ok(version) => {
+            println!("{version}");
+            Ok(())
+        }
+        Err(problem) => Err(error::Error::parse(
+            &candidate,
+            format!("`{candidate}` is not a valid semver version: {problem}"),
+        )
+        .into()),
+    }
+}
+
+/// Fix what can safely be fixed in a sloppy version.
+fn normalize(candidate: &str, lowercase: bool) -> anyhow::Result<Version> {
+    let candidate = candidate.trim();
+    validate::normalize(candidate, lowercase).map_err(|problem| {
+        error::Error::invalid(
+            candidate,
+            format!("`{candidate}` can't be made valid semver: {problem}"),
+        )
+        .with_hint(problem)
+        .into()
+    })
+}
+
+/// Print the version chosen from the list, or report that there was nothing to choose.
+fn pick(args: PickArgs, choose: fn(&[Version], bool) -> Option<&Version>) -> anyhow::Result<()> {
+    let stable_only = args.stable_only;
+    let versions = args.versions()?;
+    let chosen = choose(&versions, stable_only).ok_or_else(|| {
+        if stable_only {
+            anyhow::anyhow!("There are no stable versions to choose from.")
+        } else {
+            anyhow::anyhow!("There are no versions to choose from.")
+        }
+    })?;
+    println!("{chosen}");
+    Ok(())
+}
+
+/// Print the latest pre-release in the channel, or the one after it.
+fn channel_latest(channel: &str, base: Option<Version>, tags: bool, bump: bool) -> anyhow::Result<()> {
+    let versions = if tags {
+        published_versions(&Git::new(".").tags()?)
+    } else {
+        list::parse(&read_all()?)?
+    };
+    let base = base
+        .or_else(|| versions.iter().max().map(|v| Version::new(v.major, v.minor, v.patch)))
+        .ok_or_else(|| anyhow::anyhow!("There are no versions to look through."))?;
+    let latest = list::channel_latest(&versions, channel, Some(&base));
+    let version = match (latest, bump) {
+        (Some(latest), false) => latest.clone(),
+        (None, false) => return Err(anyhow::anyhow!("There are no {channel} pre-releases of {base}.")),
+        _ if versions.contains(&base) => {
+            return Err(anyhow::anyhow!(
+                "{base} is already released; pass --base to start pre-releases of another version."
+            ))
+        }
+        (Some(latest), true) => bump_with(latest, &BumpKind::Prerelease(String::new()), &Options::default())?,
+        (None, true) => bump_with(&base, &BumpKind::Prerelease(channel.to_string()), &Options::default())?,
+    };
+    println!("{version}");
+    Ok(())
+}
+
+/// Print the highest version published in the chosen source.
+fn latest(args: LatestArgs) -> anyhow::Result<()> {
+    let (versions, source) = match args.source {
+        LatestSource { github: Some(slug), .. } => (
+            published_versions(&github::Repository::from_env(Some(&slug))?.tags()?),
+            slug,
+        ),
+        LatestSource { krate: Some(name), .. } => (registry::crate_versions(&name)?, name),
+        LatestSource { npm: Some(name), .. } => {
+            let package = registry::npm_package(&name)?;
+            if let Some(tag) = args.dist_tag.as_ref() {
+                let version = package
+                    .dist_tags
+                    .get(tag)
+                    .ok_or_else(|| anyhow::anyhow!("{name} has no dist-tag named {tag}."))?;
+                println!("{version}");
+                return Ok(());
+            }
+            (package.versions, name)
+        }
+        LatestSource {
+            oci: Some(reference), ..
+        } => {
+            let image = registry::Image::parse(&reference)?;
+            (published_versions(&registry::image_tags(&image)?), reference)
+        }
+        LatestSource { pypi: Some(name), .. } => (registry::pypi_versions(&name)?, name),
+        _ => return Err(anyhow::anyhow!("Say where to look for versions.")),
+    };
+    let chosen = list::max(&versions, args.stable_only).ok_or_else(|| {
+        if args.stable_only {
+            anyhow::anyhow!("{source} has no stable versions.")
+        } else {
+            anyhow::anyhow!("{source} has no versions.")
+        }
+    })?;
+    println!("{chosen}");
+    Ok(())
+}
+
+/// The versions among these published names, such as tags, skipping names that aren't versions.
+fn published_versions<S: AsRef<str>>(names: &[S]) -> Vec<Version> {
+    names
+        .iter()
+        .filter_map(|name| parse_prefixed(name.as_ref()).ok())
+        .map(|(_, version)| version)
+        .collect()
+}
+
+/// Check a version against a set of requirements.
+fn matches(version: &Version, requirements: &[VersionReq], any: bool) -> ExitCode {
+    let satisfied = if any {
+        requirements.iter().any(|req| req.matches(version))
+    } else {
+        requirements.iter().all(|req| req.matches(version))
+    };
+    if satisfied {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+/// Pull a single component out of a version.
+fn get(version: &Version, component: Component) -> String {
+    match component {
+        Component::Major => version.major.to_string(),
+        Component::Minor => version.minor.to_string(),
+        Component::Patch => version.patch.to_string(),
+        Component::Prerelease => version.pre.to_string(),
+        Component::Build => version.build.to_string(),
+    }
+}
+
+fn main() -> ExitCode {
+    let args = match Args::try_parse() {
+        Ok(args) => args,
+        // Help and usage errors print as clap prints them, unless JSON was asked for.
+        Err(e) if !e.use_stderr() || !wants_json_errors(std::env::args()) => e.exit(),
+        Err(e) => return report(&usage_error(&e).into(), ErrorFormat::Json),
+    };
+    let error_format = args.error_format;
+    match run(args) {
+        Ok(code) => code,
+        Err(e) => report(&e, error_format),
+    }
+}
+
+/// Whether the command line asks for `--error-format json`, for when it couldn't be
+/// parsed far enough to say so itself.
+fn wants_json_errors(argv: impl IntoIterator<Item = String>) -> bool {
+    let argv: Vec<String> = argv.into_iter().take_while(|arg| arg != "--").collect();
+    argv.iter().any(|arg| arg == "--error-format=json")
+        || argv
+            .windows(2)
+            .any(|pair| pair[0] == "--error-format" && pair[1] == "json")
+}
+
+/// A command line clap couldn't make sense of, as a parse failure, with the value it
+/// rejected as the input. A rejected version gets the same hint as any other.
+fn usage_error(e: &clap::Error) -> error::Error {
+    let rendered = e.to_string();
+    let message = rendered.lines().next().unwrap_or_default();
+    let message = message.strip_prefix("error: ").unwrap_or(message).to_string();
+    let input = match e.get(ContextKind::InvalidValue) {
+        Some(ContextValue::String(value)) => Some(value.clone()),
+        _ => None,
+    };
+    match input {
+        Some(input) if e.kind() == ErrorKind::ValueValidation => error::Error::parse(&input, message),
+        input => error::Error {
+            kind: error::Kind::Parse,
+            message,
+            input,
+            hint: None,
+        },
+    }
+}
+
+/// Print a failure to stderr in the requested format, returning the status for its kind
+/// to exit with.
+fn report(e: &anyhow::Error, format: ErrorFormat) -> ExitCode {
+    let failure = error::classify(e);
+    match format {
+        ErrorFormat::Text => eprintln!("Error: {e:?}"),
+        ErrorFormat::Json => {
+            let report = serde_json::json!({
+                "error": {
+                    "kind": failure.kind.to_string(),
+                    "message": failure.message,
+                    "input": failure.input,
+                    "hint": failure.hint,
+                }
+            });
+            eprintln!("{report}");
+        }
+    }
+    ExitCode::from(failure.kind.exit_code())
+}
+
+/// Run the command the arguments ask for.
+fn run(args: Args) -> anyhow::Result<ExitCode> {
+    let config = match args.config.as_ref() {
+        Some(path) => Config::load(path)?,
+        None if args.cmd.uses_config() => Config::discover(std::env::current_dir()?)?,
+        None => Config::default(),
+    };
+    let config = &config;
+    match args.cmd {
+        Command::Major(args) => run_bump(BumpKind::Major, args, config)?,
+        Command::Minor(args) => run_bump(BumpKind::Minor, args, config)?,
+        Command::Patch(args) => run_bump(BumpKind::Patch, args, config)?,
+        Command::Prerelease { identifier, args } => {
+            run_bump(BumpKind::Prerelease(identifier.unwrap_or_default()), args, config)?
+        }
+        Command::Build { identifier, args } => run_bump(BumpKind::Build(identifier.unwrap_or_default()), args, config)?,
+        Command::Premajor(args) => args.run(Level::Major, config)?,
+        Command::Preminor(args) => args.run(Level::Minor, config)?,
+        Command::Prepatch(args) => args.run(Level::Patch, config)?,
+        Command::Promote { to, channels, args } => {
+            let channels = channels
+                .or_else(|| config.channels.clone())
+                .unwrap_or_else(|| DEFAULT_CHANNELS.map(String::from).to_vec());
+            run_bump(BumpKind::Promote { channels, to }, args, config)?
+        }
+        Command::Release(args) => run_bump(BumpKind::Release, args, config)?,
+        Command::Snapshot(args) => run_bump(BumpKind::Snapshot, args, config)?,
+        Command::Change { action } => match action {
+            ChangeAction::Add { level, message, dir } => {
+                let path = changes::add(&dir, &changes::Change { level, message })?;
+                println!("{}", path.display());
+            }
+            ChangeAction::Apply { dir, notes, args } => apply_changes(&dir, notes.as_deref(), *args, config)?,
+        },
+        Command::Auto {
+            since,
+            since_tag,
+            merge_base,
+            path,
+            scope,
+            rules,
+            from_pr_labels,
+            args,
+        } => {
+            let git = Git::new(".");
+            let since = match (since, merge_base) {
+                _ if from_pr_labels => None,
+                (Some(since), _) => Some(since),
+                (None, Some(other)) => Some(git.merge_base(&other)?),
+                (None, None) if since_tag => {
+                    let prefix = args.tag_prefix.as_ref().or(config.tag_prefix.as_ref());
+                    Some(git.latest_tag(&format!("{}*", prefix.map_or("v", String::as_str)))?)
+                }
+                (None, None) => None,
+            };
+            let kind = match since {
+                Some(since) => {
+                    let rules = match (rules, config.rules.as_ref()) {
+                        (Some(preset), _) => preset.rules(),
+                        (None, Some(setting)) => setting.rules()?,
+                        (None, None) => conventional::Rules::default(),
+                    };
+                    auto_level(&git, &since, &path, &scope, &rules)?
+                }
+                None => label_level(config)?,
+            };
+            let kind = match kind {
+                BumpKind::Change(level) if !config.branches.is_empty() => {
+                    let branch = current_branch(&git).ok_or_else(|| {
+                        anyhow::anyhow!("HEAD is not on a branch, so there is no branch to pick a channel for.")
+                    })?;
+                    match config.channel(&branch) {
+                        Some(channel) => BumpKind::Channel(level, channel.to_string()),
+                        None => kind,
+                    }
+                }
+                kind => kind,
+            };
+            run_bump(kind, args, config)?
+        }
+        Command::Breaking(args) => run_bump(BumpKind::Change(Level::Major), args, config)?,
+        Command::Feature(args) => run_bump(BumpKind::Change(Level::Minor), args, config)?,
+        Command::Fix(args) => run_bump(BumpKind::Change(Level::Patch), args, config)?,
+        Command::Suggest {
+            cargo: _,
+            report,
+            package,
+            baseline_rev,
+            apply,
+            mut args,
+        } => {
+            let report = match report {
+                Some(path) if path == Path::new("-") => read_all()?,
+                Some(path) => {
+                    std::fs::read_to_string(&path).with_context(|| format!("unable to read {}", path.display()))?
+                }
+                None => semver_checks::run(package.as_deref(), baseline_rev.as_deref())?,
+            };
+            let level = semver_checks::required_level(&report)
+                .ok_or_else(|| anyhow::anyhow!("The report has no cargo-semver-checks summary in it."))?;
+            if apply {
+                args.zerover = true;
+                run_bump(BumpKind::Change(level), args, config)?
+            } else {
+                println!("{level}");
+            }
+        }
+        Command::Set { next, args } => run_bump(BumpKind::Set(next), args, config)?,
+        Command::Workspace {
+            bump,
+            identifier,
+            root,
+            dry_run,
+        } => bump_workspace(&root, &bump.kind(identifier), dry_run, config)?,
+        Command::Next {
+            version,
+            channels,
+            json,
+        } => {
+            let channels = channels
+                .or_else(|| config.channels.clone())
+                .unwrap_or_else(|| DEFAULT_CHANNELS.map(String::from).to_vec());
+            next_candidates(version, &channels, json)?
+        }
+        Command::Interactive { channels, args } => {
+            let channels = channels
+                .or_else(|| config.channels.clone())
+                .unwrap_or_else(|| DEFAULT_CHANNELS.map(String::from).to_vec());
+            interactive(&channels, args, config)?
+        }
+        Command::Rollback(args) => run_bump(BumpKind::Rollback, args, config)?,
+        Command::Down { level, args } => run_bump(BumpKind::Down(level), args, config)?,
+        Command::Epoch { version, reset } => {
+            let input = match version {
+                Some(v) => v,
+                None => read_line()?,
+            };
+            let (epoch, rest) = epoch::split(&input)?;
+            let (prefix, version) = parse_prefixed(rest)?;
+            println!("{}{prefix}{}", epoch::next(epoch)?, reset.unwrap_or(version));
+        }
+        Command::Calver {
+            version,
+            pattern,
+            date,
+            file,
+        } => bump_calver(version, &pattern, date, file)?,
+        Command::Compare { left, right } => return Ok(compare(&left, &right)),
+        Command::Diff { left, right } => return Ok(difference(&left, &right)),
+        Command::Precedes {
+            old,
+            new,
+            strict_succession,
+        } => precedes(&old, &new, strict_succession)?,
+        Command::Convert { version, to } => {
+            let input = match version {
+                Some(v) => v,
+                None => read_line()?,
+            };
+            let (_, version) = parse_prefixed(&input)?;
+            println!("{}", convert::convert(&version, to)?);
+        }
+        Command::Tags {
+            version,
+            prefix,
+            no_latest,
+            json,
+        } => {
+            let input = match version {
+                Some(v) => v,
+                None => read_line()?,
+            };
+            let (_, version) = parse_prefixed(&input)?;
+            let tags = docker::tags(&version, &prefix, !no_latest);
+            if json {
+                println!("{}", serde_json::to_string(&tags)?);
+            } else {
+                tags.iter().for_each(|tag| println!("{tag}"));
+            }
+        }
+        Command::Explain { version, channels } => {
+            let channels = channels
+                .or_else(|| config.channels.clone())
+                .unwrap_or_else(|| DEFAULT_CHANNELS.map(String::from).to_vec());
+            explain(version, &channels)?
+        }
+        Command::Validate { candidate } => validate(candidate)?,
+        Command::Normalize { candidate, lowercase } => {
+            let candidate = match candidate {
+                Some(c) => c,
+                None => read_line()?,
+            };
+            println!("{}", normalize(&candidate, lowercase)?);
+        }
+        Command::Sort { reverse, unique } => {
+            let mut versions = list::parse(&read_all()?)?;
+            list::sort(&mut versions, reverse, unique);
+            versions.iter().for_each(|v| println!("{v}"));
+        }
+        Command::Max(args) => pick(args, list::max)?,
+        Command::Min(args) => pick(args, list::min)?,
+        Command::ChannelLatest {
+            channel,
+            base,
+            tags,
+            bump,
+        } => channel_latest(&channel, base, tags, bump)?,
+        Command::Filter { requirement, latest } => {
+            let versions = list::parse(&read_all()?)?;
+            let mut matching = list::satisfying(&versions, &requirement);
+            if latest {
+                matching = matching.into_iter().max().into_iter().collect();
+            }
+            if matching.is_empty() {
+                return Ok(ExitCode::FAILURE);
+            }
+            matching.iter().for_each(|v| println!("{v}"));
+        }
+        Command::Latest(args) => latest(args)?,
+        Command::Matches {
+            requirements,
+            current,
+            any,
+        } => {
+            let version = match current {
+                Some(v) => v,
+                None => parse_version(&read_line()?)?,
+            };
+            return Ok(matches(&version, &requirements, any));
+        }
+        Command::Req { action } => {
+            let requirement = match action {
+                ReqAction::BumpUpper { requirement } => req::bump_upper(&requirement)?,
+                ReqAction::Widen { requirement, version } => req::widen(&requirement, &version)?,
+                ReqAction::Narrow { requirement, version } => req::narrow(&requirement, &version)?,
+                ReqAction::Intersect { requirements } => match req::intersect(&requirements)? {
+                    Some(range) => range,
+                    None => {
+                        println!("none");
+                        return Ok(ExitCode::FAILURE);
+                    }
+                },
+                ReqAction::Union { requirements } => {
+                    let ranges = req::union(&requirements)?;
+                    if ranges.is_empty() {
+                        println!("none");
+                        return Ok(ExitCode::FAILURE);
+                    }
+                    ranges.join("\n")
+                }
+            };
+            println!("{requirement}");
+        }
+        Command::Notes {
+            from,
+            to,
+            next,
+            prev,
+            template,
+            template_file,
+        } => {
+            let template = match (template, template_file) {
+                (Some(template), _) => Some(template),
+                (None, Some(path)) => {
+                    let text =
+                        std::fs::read_to_string(&path).with_context(|| format!("unable to read {}", path.display()))?;
+                    Some(notes_template(&text)?)
+                }
+                (None, None) => None,
+            };
+            release_notes(&from, &to, next, prev, template)?
+        }
+        Command::VerifyTag {
+            file,
+            manifest,
+            tag_prefix,
+        } => verify_tag(file, manifest, tag_prefix, config)?,
+        Command::LintTags {
+            stdin,
+            tag_prefix,
+            json,
+        } => {
+            let prefix = tag_prefix.or_else(|| config.tag_prefix.clone());
+            return lint_tags(stdin, prefix.as_deref().unwrap_or("v"), json);
+        }
+        Command::Completions { shell } => {
+            clap_complete::generate(shell, &mut Args::command(), "semver-bump", &mut std::io::stdout());
+        }
+        Command::Get { component, version } => {
+            let version = match version {
+                Some(v) => v,
+                None => parse_version(&read_line()?)?,
+            };
+            println!("{}", get(&version, component));
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
 #[cfg(test)]
 mod tests {
-    use semver::Version;
-
     use super::*;
 
+    fn parse(argv: &[&str]) -> Command {
+        let args = Args::try_parse_from(argv).expect("test arguments must parse");
+        args.cmd
+    }
+
+    #[test]
+    fn version_from_arguments() {
+        let Command::Patch(args) = parse(&["semver-bump", "patch", "1.2.3"]) else {
+            panic!("we expected a patch command");
+        };
+        let (_, previous) = read_version(&args).expect("we expected the positional version to parse");
+        assert_eq!(previous.to_string(), "1.2.3");
+
+        let Command::Minor(args) = parse(&["semver-bump", "minor", "--current", "v1.2.3"]) else {
+            panic!("we expected a minor command");
+        };
+        let (prefix, previous) = read_version(&args).expect("we expected the flag version to parse");
+        assert_eq!(prefix, "v");
+        assert_eq!(previous.to_string(), "1.2.3");
+
+        let Command::Prerelease { identifier, args } =
+            parse(&["semver-bump", "prerelease", "alpha", "--current", "1.2.3"])
+        else {
+            panic!("we expected a prerelease command");
+        };
+        assert_eq!(identifier.as_deref(), Some("alpha"));
+        assert_eq!(args.current.as_deref(), Some("1.2.3"));
+
+        Args::try_parse_from(["semver-bump", "patch", "1.2.3", "--current", "1.2.4"])
+            .expect_err("we expected conflicting versions to be rejected");
+        Args::try_parse_from(["semver-bump", "patch", "1.2.3", "--file", "VERSION"])
+            .expect_err("we expected a version and a file to be rejected");
+    }
+
+    #[test]
+    fn coerced_input() {
+        let Command::Minor(args) = parse(&["semver-bump", "minor", "v1.2", "--coerce"]) else {
+            panic!("we expected a minor command");
+        };
+        let (prefix, previous) = read_version(&args).expect("we expected the sloppy version to be coerced");
+        assert_eq!(prefix, "v");
+        assert_eq!(previous.to_string(), "1.2.0");
+        let Command::Minor(args) = parse(&["semver-bump", "minor", "v1.2"]) else {
+            panic!("we expected a minor command");
+        };
+        read_version(&args).expect_err("we expected sloppy versions to be rejected without --coerce");
+    }
+
+    #[test]
+    fn build_metadata() {
+        let Command::Patch(args) = parse(&["semver-bump", "patch", "--build-from-git", "1.2.3"]) else {
+            panic!("we expected a patch command");
+        };
+        assert_eq!(args.build_from_git, Some(vec![GitPart::Sha, GitPart::Dirty]));
+        assert_eq!(args.version.as_deref(), Some("1.2.3"));
+        let Command::Patch(args) = parse(&["semver-bump", "patch", "--build-from-git=count,sha"]) else {
+            panic!("we expected a patch command");
+        };
+        assert_eq!(args.build_from_git, Some(vec![GitPart::Count, GitPart::Sha]));
+        let Command::Patch(args) = parse(&["semver-bump", "patch", "--build-timestamp", "1.2.3"]) else {
+            panic!("we expected a patch command");
+        };
+        assert!(args.build_timestamp.is_some());
+        assert_eq!(args.version.as_deref(), Some("1.2.3"));
+        Args::try_parse_from(["semver-bump", "patch", "--build-timestamp=YYYY_MM"])
+            .expect_err("we expected an illegal timestamp format to be rejected");
+
+        let mut version = Version::parse("1.2.3").expect("test data must be valid semver");
+        append_build(&mut version, &["g1a2b3c4".to_string()]).expect("we expected to add build metadata");
+        assert_eq!(version.to_string(), "1.2.3+g1a2b3c4");
+        append_build(&mut version, &["12".to_string(), "dirty".to_string()])
+            .expect("we expected to add build metadata");
+        assert_eq!(version.to_string(), "1.2.3+g1a2b3c4.12.dirty");
+    }
+
+    #[test]
+    fn prefixes() {
+        let Command::Patch(args) = parse(&["semver-bump", "patch"]) else {
+            panic!("we expected a patch command");
+        };
+        assert_eq!(args.output_prefix("v"), "v");
+        assert_eq!(args.output_prefix(""), "");
+        let Command::Patch(args) = parse(&["semver-bump", "patch", "--keep-prefix"]) else {
+            panic!("we expected a patch command");
+        };
+        assert_eq!(args.output_prefix(""), "v");
+        assert_eq!(args.output_prefix("V"), "V");
+        let Command::Patch(args) = parse(&["semver-bump", "patch", "--strip-prefix", "--each"]) else {
+            panic!("we expected a patch command");
+        };
+        assert_eq!(args.output_prefix("v"), "");
+        Args::try_parse_from(["semver-bump", "patch", "--each", "--file", "VERSION"])
+            .expect_err("we expected --each with a file to be rejected");
+    }
+
+    #[test]
+    fn set_arguments() {
+        let Command::Set { next, args } = parse(&["semver-bump", "set", "2.0.0-rc.1", "1.9.0", "--require-greater"])
+        else {
+            panic!("we expected a set command");
+        };
+        assert_eq!(next.to_string(), "2.0.0-rc.1");
+        assert!(args.require_greater);
+        assert!(args.has_version());
+        Args::try_parse_from(["semver-bump", "set", "2.0"]).expect_err("we expected invalid versions to be rejected");
+
+        let Command::Set { next, args } = parse(&["semver-bump", "set", "2.0.0", "--snapshot", "--max", "2"]) else {
+            panic!("we expected a set command");
+        };
+        let kind = BumpKind::Set(next);
+        let result = new_version(None, &kind, &args).expect("we expected set to work without a previous version");
+        assert_eq!(result.to_string(), "2.0.0-SNAPSHOT");
+        let Command::Set { next, args } = parse(&["semver-bump", "set", "3.0.0", "--max", "2"]) else {
+            panic!("we expected a set command");
+        };
+        new_version(None, &BumpKind::Set(next), &args).expect_err("we expected the ceiling to still apply");
+    }
+
+    #[test]
+    fn commands_that_use_config() {
+        assert!(!parse(&["semver-bump", "compare", "1.0.0", "2.0.0"]).uses_config());
+        assert!(!parse(&["semver-bump", "validate", "1.0.0"]).uses_config());
+        assert!(!parse(&["semver-bump", "change", "add", "minor", "A feature"]).uses_config());
+        assert!(parse(&["semver-bump", "patch", "1.0.0"]).uses_config());
+        assert!(parse(&["semver-bump", "change", "apply"]).uses_config());
+        assert!(parse(&["semver-bump", "lint-tags"]).uses_config());
+    }
+
     #[test]
-    fn major_bump() {
-        let input = Version::parse("1.0.0").expect("test data must be valid semver");
-        let next = major(&input);
-        assert_eq!(next.major, input.major + 1);
-        let input = Version::parse("3.3.3").expect("test data must be valid semver");
-        let next = major(&input);
-        assert_eq!(next.major, input.major + 1);
-        assert_eq!(next.minor, 0);
-        assert_eq!(next.patch, 0);
-        let input = Version::parse("747.341.321-alpha1").expect("test data must be valid semver");
-        let next = major(&input);
-        assert!(next.pre.is_empty());
+    fn config_defaults() {
+        let config = Config {
+            tag_prefix: Some("release-".to_string()),
+            separator: Some('-'),
+            files: vec![Target::Path(PathBuf::from("VERSION"))],
+            max: Some("0".to_string()),
+            zerover: true,
+            root: PathBuf::from("repo"),
+            ..Default::default()
+        };
+        let Command::Patch(args) = parse(&["semver-bump", "patch"]) else {
+            panic!("we expected a patch command");
+        };
+        let args = args.with_config(&config).expect("we expected the config to apply");
+        assert_eq!(args.tag_prefix(), "release-");
+        assert_eq!(args.options().separator, '-');
+        assert!(args.options().zerover);
+        assert_eq!(args.config_files, vec![Target::Path(PathBuf::from("repo/VERSION"))]);
+        let previous = Version::new(0, 9, 0);
+        next_version(&previous, &BumpKind::Minor, &args).expect("we expected a minor bump to stay under the ceiling");
+        next_version(&previous, &BumpKind::Major, &args).expect_err("we expected a major bump to hit the ceiling");
+
+        let Command::Patch(args) = parse(&[
+            "semver-bump",
+            "patch",
+            "1.2.3",
+            "--tag",
+            "--tag-prefix",
+            "v",
+            "--separator",
+            ".",
+        ]) else {
+            panic!("we expected a patch command");
+        };
+        let args = args.with_config(&config).expect("we expected the config to apply");
+        assert_eq!(args.tag_prefix(), "v");
+        assert_eq!(args.options().separator, '.');
+        assert!(args.config_files.is_empty());
     }
 
     #[test]
-    fn minor_bump() {
-        // boring but I will write a test
-        let input = Version::parse("1.2.3").expect("test data must be valid semver");
-        let next = minor(&input);
-        assert_eq!(next.major, input.major);
-        assert_eq!(next.minor, input.minor + 1);
-        assert_eq!(next.patch, 0);
+    fn workspace_arguments() {
+        let Command::Workspace { bump, identifier, .. } = parse(&["semver-bump", "workspace", "preminor", "beta"])
+        else {
+            panic!("we expected a workspace command");
+        };
+        assert_eq!(bump.kind(identifier), BumpKind::Pre(Level::Minor, "beta".to_string()));
+        Args::try_parse_from(["semver-bump", "workspace", "sideways"])
+            .expect_err("we expected unknown bumps to be rejected");
     }
 
     #[test]
-    fn patch_bump() {
-        // boring but I will write a test
-        let input = Version::parse("1.2.3").expect("test data must be valid semver");
-        let next = patch(&input);
-        assert_eq!(next.major, input.major);
-        assert_eq!(next.minor, input.minor);
-        assert_eq!(next.patch, input.patch + 1);
+    fn naming_identifiers() {
+        assert_eq!(identifier(&BumpKind::Pre(Level::Major, "rc".to_string())), Some("rc"));
+        assert_eq!(identifier(&BumpKind::Prerelease(String::new())), None);
+        let promote = BumpKind::Promote {
+            channels: vec!["beta".to_string(), "rc".to_string()],
+            to: Some("rc".to_string()),
+        };
+        assert_eq!(identifier(&promote), Some("rc"));
+        assert_eq!(identifier(&BumpKind::Major), None);
     }
 
     #[test]
-    fn prerelease_adding() {
-        let input = Version::parse("1.0.0").expect("test data must be valid semver");
-        let next = prerelease(&input, "alpha").expect("we expected the prerelease bump to work");
-        assert_eq!(next.major, input.major);
+    fn down_arguments() {
+        let Command::Down { level, args } = parse(&["semver-bump", "down", "minor", "1.3.2"]) else {
+            panic!("we expected a down command");
+        };
+        assert_eq!(level, Level::Minor);
+        assert_eq!(args.version.as_deref(), Some("1.3.2"));
+        Args::try_parse_from(["semver-bump", "down", "sideways"])
+            .expect_err("we expected unknown levels to be rejected");
+    }
+
+    #[test]
+    fn snapshots() {
+        let Command::Minor(args) = parse(&["semver-bump", "minor", "1.2.3", "--snapshot"]) else {
+            panic!("we expected a minor command");
+        };
+        let previous = Version::new(1, 2, 3);
+        let next = next_version(&previous, &BumpKind::Minor, &args).expect("we expected the bump to work");
+        assert_eq!(next.to_string(), "1.3.0-SNAPSHOT");
+    }
+
+    #[test]
+    fn json_usage_errors() {
+        let argv = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+        assert!(wants_json_errors(argv(&[
+            "semver-bump",
+            "compare",
+            "--error-format",
+            "json"
+        ])));
+        assert!(wants_json_errors(argv(&[
+            "semver-bump",
+            "--error-format=json",
+            "compare"
+        ])));
+        assert!(!wants_json_errors(argv(&[
+            "semver-bump",
+            "compare",
+            "--error-format",
+            "text"
+        ])));
+        assert!(!wants_json_errors(argv(&[
+            "semver-bump",
+            "set",
+            "--",
+            "--error-format",
+            "json"
+        ])));
+
+        let e = Args::try_parse_from(["semver-bump", "compare", "1.2.3", "1.02"])
+            .expect_err("we expected clap to reject the version");
+        let failure = usage_error(&e);
+        assert_eq!(failure.kind, error::Kind::Parse);
+        assert_eq!(failure.input.as_deref(), Some("1.02"));
+        assert!(
+            failure.message.starts_with("invalid value '1.02'"),
+            "{}",
+            failure.message
+        );
+        assert!(failure.hint.is_some());
+
+        let e =
+            Args::try_parse_from(["semver-bump", "patch", "--bogus"]).expect_err("we expected clap to reject the flag");
+        assert_eq!(usage_error(&e).kind, error::Kind::Parse);
+    }
+
+    #[test]
+    fn parse_failures_exit_2() {
+        let exit_code = |result: anyhow::Result<()>, what: &str| {
+            let e = result.expect_err(&format!("we expected {what} to fail"));
+            error::classify(&e).kind.exit_code()
+        };
+        assert_eq!(exit_code(validate(Some("1.02.3".to_string())), "validate"), 2);
+        assert_eq!(exit_code(normalize("abc", false).map(drop), "normalize"), 2);
+        assert_eq!(exit_code(coerce("abc").map(drop), "coerce"), 2);
+        let Command::Patch(args) = parse(&["semver-bump", "patch", "--strict"]) else {
+            panic!("we expected a patch command");
+        };
+        assert_eq!(exit_code(args.parse("v1.2.3").map(drop), "--strict"), 2);
+        let pattern: calver::Pattern = calver::DEFAULT_PATTERN.parse().expect("the default pattern must parse");
+        let date = calver::Date {
+            year: 2024,
+            month: 5,
+            day: 1,
+        };
         assert_eq!(
-            next.pre,
-            Prerelease::new("alpha.1").expect("test data must be valid semver")
+            exit_code(
+                bump_calver(Some("2024.x".to_string()), &pattern, Some(date), None),
+                "calver"
+            ),
+            2
         );
-        let input = Version::parse("1.0.0-alpha").expect("test data must be valid semver");
-        let next = prerelease(&input, "").expect("we expected the prerelease bump to work");
-        assert_eq!(next.major, input.major);
+        let version = Version::parse("1.2.3-gamma.1").expect("test data must be valid semver");
         assert_eq!(
-            next.pre,
-            Prerelease::new("alpha.1").expect("test data must be valid semver")
+            exit_code(
+                convert::convert(&version, convert::Ecosystem::Pep440).map(drop),
+                "convert"
+            ),
+            2
         );
-        let input = Version::parse("1.0.0-alpha").expect("test data must be valid semver");
-        let next = prerelease(&input, "alpha").expect("we expected the prerelease bump to work");
-        assert_eq!(next.major, input.major);
+    }
+
+    #[test]
+    fn strict_input() {
+        let Command::Patch(args) = parse(&["semver-bump", "patch", "--strict"]) else {
+            panic!("we expected a patch command");
+        };
+        let (prefix, version) = args
+            .parse("1.2.3-rc.1\n")
+            .expect("we expected strict semver to be accepted");
+        assert_eq!((prefix.as_str(), version.to_string().as_str()), ("", "1.2.3-rc.1"));
+        for input in ["", "\n", "v1.2.3", "1.2.3+build.7", "1.2", " 1.2.3"] {
+            args.parse(input)
+                .expect_err("we expected strict mode to reject inexact input");
+        }
+        let Command::Patch(args) = parse(&["semver-bump", "patch"]) else {
+            panic!("we expected a patch command");
+        };
+        args.parse("v1.2.3+build.7")
+            .expect("we expected permissive mode to accept a prefix and build metadata");
+    }
+
+    #[test]
+    fn describe_input() {
+        let read = |argv: &[&str]| {
+            let Command::Patch(args) = parse(argv) else {
+                panic!("we expected a patch command");
+            };
+            let (prefix, previous) = read_version(&args).expect("we expected to read the description");
+            let next = next_version(&previous, &BumpKind::Patch, &args).expect("we expected the bump to work");
+            (format!("{prefix}{previous}"), next.to_string())
+        };
+        let describe = "v1.2.3-14-g1a2b3c4-dirty";
+        let argv = ["semver-bump", "patch", describe, "--input-format", "git-describe"];
         assert_eq!(
-            next.pre,
-            Prerelease::new("alpha.1").expect("test data must be valid semver")
+            read(&argv),
+            ("v1.2.3+14.g1a2b3c4.dirty".to_string(), "1.2.4".to_string())
         );
+        let (previous, _) = read(&[&argv[..], &["--describe-as", "pre"]].concat());
+        assert_eq!(previous, "v1.2.3-14+g1a2b3c4.dirty");
     }
 
     #[test]
-    fn prerelease_bumping() {
-        let input = Version::parse("1.0.0-alpha.1").expect("test data must be valid semver");
-        let next = prerelease(&input, "").expect("we expected the prerelease bump to work");
-        assert_eq!(next.major, input.major);
+    fn verifying_tags() {
+        let version = Version::new(1, 2, 3);
+        let tags = ["latest".to_string(), "v1.2.3".to_string()];
         assert_eq!(
-            next.pre,
-            Prerelease::new("alpha.2").expect("test data must be valid semver")
+            matching_tag(&version, &tags, "v").expect("we expected to find the tag"),
+            "v1.2.3"
         );
-        let next = prerelease(&input, "beta").expect("we expected the prerelease bump to work");
+        matching_tag(&version, &tags, "release-").expect_err("we expected the prefix to matter");
+        matching_tag(&Version::new(1, 2, 4), &tags, "v").expect_err("we expected a mismatch to be an error");
+        matching_tag(&version, &[], "v").expect_err("we expected an untagged HEAD to be an error");
+    }
+
+    #[test]
+    fn latest_published() {
+        let names = ["latest", "v1.2.3", "1.10.0", "v2.0.0-rc.1", "nightly-2024"];
+        let versions = published_versions(&names);
+        assert_eq!(versions.len(), 3);
+        let latest = list::max(&versions, false).expect("we expected a latest version");
+        assert_eq!(latest.to_string(), "2.0.0-rc.1");
+        let stable = list::max(&versions, true).expect("we expected a stable version");
+        assert_eq!(stable.to_string(), "1.10.0");
+        Args::try_parse_from(["semver-bump", "latest"]).expect_err("we expected a source to be required");
+    }
+
+    #[test]
+    fn dependents_are_written_with_the_bump() {
+        let dir = tempfile::tempdir().expect("we expected to be able to make a temp dir");
+        let write = |path: &str, text: &str| {
+            let path = dir.path().join(path);
+            std::fs::create_dir_all(path.parent().expect("test paths have a parent"))
+                .expect("we expected to make the directory");
+            std::fs::write(&path, text).expect("we expected to write the manifest");
+            path.display().to_string()
+        };
+        let bump = |manifest: &str| {
+            let Command::Minor(args) = parse(&["semver-bump", "minor", "--manifest", manifest, "--update-dependents"])
+            else {
+                panic!("we expected a minor command");
+            };
+            run_bump(BumpKind::Minor, args, &Config::default())
+        };
+
+        let solo = write("solo/Cargo.toml", "[package]\nname = \"solo\"\nversion = \"1.0.0\"\n");
+        bump(&solo).expect_err("we expected a crate outside a workspace to be an error");
+        let text = std::fs::read_to_string(&solo).expect("we expected the manifest to still be there");
+        assert!(text.contains("version = \"1.0.0\""), "{text}");
+
+        write("ws/Cargo.toml", "[workspace]\nmembers = [\"crates/*\"]\n");
+        let core = write(
+            "ws/crates/core/Cargo.toml",
+            "[package]\nname = \"core\"\nversion = \"1.0.0\"\n",
+        );
+        let cli = write(
+            "ws/crates/cli/Cargo.toml",
+            "[package]\nname = \"cli\"\nversion = \"0.1.0\"\n\n[dependencies]\ncore = { path = \"../core\", version = \"1.0.0\" }\n",
+        );
+        bump(&core).expect("we expected the bump to work");
+        let text = std::fs::read_to_string(&cli).expect("we expected the dependent to be there");
+        assert!(text.contains("version = \"1.1.0\" }"), "{text}");
+    }
+
+    #[test]
+    fn tag_collisions() {
+        let dir = tempfile::tempdir().expect("we expected to be able to make a temp dir");
+        let git = Git::new(dir.path());
+        for args in [
+            &["init", "--quiet"][..],
+            &["config", "user.name", "Test Runner"],
+            &["config", "user.email", "test@example.com"],
+            &["config", "tag.gpgSign", "false"],
+            &["commit", "--quiet", "--allow-empty", "--message", "initial"],
+        ] {
+            git.run(args).expect("we expected to set up a scratch repository");
+        }
+        for tag in ["v1.3.0", "v1.4.0", "v2.0.0-rc.1"] {
+            git.create_tag(tag, tag, false).expect("we expected tagging to work");
+        }
+        let previous = Version::new(1, 2, 3);
+        let free = |strategy: &str, kind: BumpKind| {
+            let Command::Minor(args) = parse(&["semver-bump", "minor", "--tag", "--if-tag-exists", strategy]) else {
+                panic!("we expected a minor command");
+            };
+            let result = next_version(&previous, &kind, &args).expect("we expected the bump to work");
+            free_tag(&git, Some(&previous), &kind, result, &args)
+        };
+        free("error", BumpKind::Minor).expect_err("we expected an existing tag to be an error");
+        let (next, tag) = free("skip", BumpKind::Minor).expect("we expected skipping to work");
+        assert_eq!((next.to_string(), tag), ("1.3.0".to_string(), None));
+        let (next, tag) = free("next", BumpKind::Minor).expect("we expected to find a free tag");
         assert_eq!(
-            next.pre,
-            Prerelease::new("beta.1").expect("test data must be valid semver")
+            (next.to_string(), tag),
+            ("1.5.0".to_string(), Some("v1.5.0".to_string()))
         );
-        let input = Version::parse("1.0.0-1").expect("test data must be valid semver");
-        let next = prerelease(&input, "").expect("we expected the prerelease bump to work");
-        assert_eq!(next.pre, Prerelease::new("2").expect("test data must be valid semver"));
+        let (next, _) =
+            free("next", BumpKind::Pre(Level::Major, "rc".to_string())).expect("we expected to find a free tag");
+        assert_eq!(next.to_string(), "2.0.0-rc.2");
+        free("next", BumpKind::Set(Version::new(1, 3, 0))).expect_err("we expected a set bump to have no next");
+    }
+
+    #[test]
+    fn semantic_aliases() {
+        let Command::Breaking(args) = parse(&["semver-bump", "breaking", "0.4.2", "--zerover"]) else {
+            panic!("we expected a breaking command");
+        };
+        let previous = Version::new(0, 4, 2);
+        let kind = BumpKind::Change(Level::Major);
+        let next = next_version(&previous, &kind, &args).expect("we expected the bump to work");
+        assert_eq!(next.to_string(), "0.5.0");
+        assert_eq!(kind.resolve(&previous, &args.options()), BumpKind::Minor);
+        assert!(matches!(parse(&["semver-bump", "fix"]), Command::Fix(_)));
     }
 
     #[test]
-    fn prerelease_error_cases() {
-        let input = Version::parse("1.0.0").expect("test data must be valid semver");
-        prerelease(&input, "").expect_err("we expected an error from this call");
-        prerelease(&input, "+illegal+").expect_err("we expected an error from this call");
-        let input = Version::parse("1.0.0-alpha.four").expect("test data must be valid semver");
-        prerelease(&input, "").expect_err("we expected an error from this call");
+    fn completions() {
+        let Command::Completions { shell } = parse(&["semver-bump", "completions", "zsh"]) else {
+            panic!("we expected a completions command");
+        };
+        assert_eq!(shell, Shell::Zsh);
+        let mut script = Vec::new();
+        clap_complete::generate(Shell::Bash, &mut Args::command(), "semver-bump", &mut script);
+        let script = String::from_utf8(script).expect("we expected the completion script to be utf-8");
+        assert!(script.contains("prerelease"));
     }
 
     #[test]
-    fn separator_detection() {
-        let input = Version::parse("1.2.3-ceti-alpha-4").expect("test data must be valid semver");
-        let next = prerelease(&input, "").expect("we expected prerelease() to work");
+    fn output_templates() {
+        let previous = Version::parse("1.2.3").expect("test data must be valid semver");
+        let result = Version::parse("1.3.0-rc.1").expect("test data must be valid semver");
+        let Command::Minor(args) = parse(&[
+            "semver-bump",
+            "minor",
+            "--format",
+            "{tag_prefix}{major}.{minor} {prerelease} from {prev} by {bump}",
+        ]) else {
+            panic!("we expected a minor command");
+        };
         assert_eq!(
-            next.pre,
-            Prerelease::new("ceti-alpha-5").expect("test data must be valid semver")
+            args.render("", &result, Some(&previous), &BumpKind::Minor),
+            "v1.3 rc.1 from 1.2.3 by minor"
         );
-        assert_eq!(next.to_string(), "1.2.3-ceti-alpha-5".to_string());
 
-        let input = Version::parse("1.2.3-ceti-alpha.4").expect("test data must be valid semver");
-        let next = prerelease(&input, "").expect("we expected prerelease() to work");
-        assert_eq!(next.to_string(), "1.2.3-ceti-alpha.5".to_string());
+        let config = Config {
+            format: Some("{prefix}{major}".to_string()),
+            ..Default::default()
+        };
+        let Command::Minor(args) = parse(&["semver-bump", "minor"]) else {
+            panic!("we expected a minor command");
+        };
+        assert_eq!(args.render("v", &result, None, &BumpKind::Minor), "v1.3.0-rc.1");
+        let args = args.with_config(&config).expect("we expected the config to apply");
+        assert_eq!(args.render("v", &result, None, &BumpKind::Minor), "v1");
+        Args::try_parse_from(["semver-bump", "minor", "--format", "{nope}"])
+            .expect_err("we expected unknown placeholders to be rejected");
+    }
+
+    #[test]
+    fn comparisons() {
+        let parsed = |input: &str| epoch::parse(input).expect("test data must be valid semver");
+        let older = parsed("1.2.3");
+        let newer = parsed("1.3.0-rc.1");
+        let built = parsed("1.2.3+build.7");
+        assert_eq!(compare(&older, &newer), ExitCode::from(10));
+        assert_eq!(compare(&newer, &older), ExitCode::from(11));
+        assert_eq!(compare(&older, &built), ExitCode::SUCCESS);
+        assert_eq!(compare(&parsed("1!1.0.0"), &newer), ExitCode::from(11));
     }
 
     #[test]
-    fn build_bump() {
-        let input = Version::parse("1.2.3-four+4").expect("test data must be valid semver");
-        let next = build(&input, "").expect("we expected build() to work");
-        assert_eq!(next.to_string(), "1.2.3-four+5".to_string());
-        let input = Version::parse("1.2.3-ceti-alpha+4").expect("test data must be valid semver");
-        let next = build(&input, "").expect("we expected build() to work");
-        assert_eq!(next.to_string(), "1.2.3-ceti-alpha+5".to_string());
+    fn differences() {
+        let older = Version::parse("1.2.3").expect("test data must be valid semver");
+        let newer = Version::parse("2.0.0").expect("test data must be valid semver");
+        let built = Version::parse("1.2.3+build.7").expect("test data must be valid semver");
+        assert_eq!(difference(&older, &newer), ExitCode::from(20));
+        assert_eq!(difference(&older, &built), ExitCode::from(24));
+        assert_eq!(difference(&older, &older), ExitCode::SUCCESS);
     }
 
     #[test]
-    fn passing_numbers_in() {
-        let input = Version::parse("1.2.3-four+4").expect("test data must be valid semver");
-        let next = prerelease(&input, "beta.2").expect("we expected prerelease() to work");
-        assert_eq!(next.to_string(), "1.2.3-beta.2");
-        let input = Version::parse("1.2.3-four+4").expect("test data must be valid semver");
-        let next = build(&input, "7").expect("we expected build() to work");
-        assert_eq!(next.to_string(), "1.2.3-four+7");
+    fn getting_components() {
+        let version = Version::parse("1.2.3-rc.4+build.5").expect("test data must be valid semver");
+        assert_eq!(get(&version, Component::Major), "1");
+        assert_eq!(get(&version, Component::Minor), "2");
+        assert_eq!(get(&version, Component::Patch), "3");
+        assert_eq!(get(&version, Component::Prerelease), "rc.4");
+        assert_eq!(get(&version, Component::Build), "build.5");
+        let version = Version::parse("1.2.3").expect("test data must be valid semver");
+        assert_eq!(get(&version, Component::Prerelease), "");
+    }
+
+    #[test]
+    fn requirement_matching() {
+        let version = Version::parse("1.4.2").expect("test data must be valid semver");
+        let caret = VersionReq::parse("^1.2").expect("test data must be a valid requirement");
+        let range = VersionReq::parse(">=1.5, <2").expect("test data must be a valid requirement");
+        assert_eq!(
+            matches(&version, std::slice::from_ref(&caret), false),
+            ExitCode::SUCCESS
+        );
+        assert_eq!(
+            matches(&version, &[caret.clone(), range.clone()], false),
+            ExitCode::FAILURE
+        );
+        assert_eq!(matches(&version, &[caret, range.clone()], true), ExitCode::SUCCESS);
+        assert_eq!(matches(&version, &[range], true), ExitCode::FAILURE);
     }
 }
@@ -0,0 +1,360 @@
+//! Bumping `package.version` in a `Cargo.toml`, preserving formatting and comments.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::anyhow;
+use semver::{Op, Version, VersionReq};
+use toml_edit::{DocumentMut, Item, Value};
+
+use super::{Manifest, Workspace};
+
+/// The tables that can list dependencies, at the top level or under a `[target]`.
+const DEPENDENCY_TABLES: [&str; 3] = ["dependencies", "dev-dependencies", "build-dependencies"];
+
+/// A Rust package manifest.
+#[derive(Clone, Debug)]
+pub struct CargoManifest {
+    path: PathBuf,
+    doc: DocumentMut,
+    /// Whether the version to bump is the shared `workspace.package.version` rather than
+    /// `package.version`.
+    shared: bool,
+}
+
+impl CargoManifest {
+    /// Read and parse the manifest at the given path.
+    pub fn open(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let text = super::read(&path)?;
+        Self::parse(path, &text)
+    }
+
+    /// Parse manifest text that was read from the given path.
+    pub fn parse(path: impl Into<PathBuf>, text: &str) -> anyhow::Result<Self> {
+        let path = path.into();
+        let doc = text
+            .parse::<DocumentMut>()
+            .map_err(|e| anyhow!("unable to parse {} as toml: {e}", path.display()))?;
+        // A virtual workspace manifest has no package, so its only version is the shared one.
+        let shared =
+            !doc.contains_key("package") && super::toml_get(&doc, &["workspace", "package", "version"]).is_some();
+        Ok(Self { path, doc, shared })
+    }
+
+    /// Read the manifest at the given path. If its package inherits its version from the
+    /// workspace, with `version.workspace = true`, read the workspace's root manifest
+    /// instead, so that bumping it bumps the shared version there.
+    pub fn open_following(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let manifest = Self::open(path)?;
+        if !manifest.inherits_version() {
+            return Ok(manifest);
+        }
+        let workspace = Workspace::discover(path)?.ok_or_else(|| {
+            anyhow!(
+                "{} inherits its version from the workspace, but isn't a member of one",
+                path.display()
+            )
+        })?;
+        let mut root = workspace.root().clone();
+        root.shared = true;
+        Ok(root)
+    }
+
+    /// The name of the package, if this manifest has one.
+    pub fn name(&self) -> Option<&str> {
+        super::toml_get(&self.doc, &["package", "name"]).and_then(|n| n.as_str())
+    }
+
+    /// Whether this manifest has a `[package]` section at all; a virtual workspace
+    /// manifest does not.
+    pub fn is_package(&self) -> bool {
+        self.doc.contains_key("package")
+    }
+
+    /// Whether the package inherits its version from the workspace, with `version.workspace = true`.
+    pub fn inherits_version(&self) -> bool {
+        super::toml_get(&self.doc, &["package", "version", "workspace"])
+            .and_then(|w| w.as_bool())
+            .unwrap_or(false)
+    }
+
+    /// The shared version in `workspace.package.version`, if there is one.
+    pub fn workspace_version(&self) -> anyhow::Result<Option<Version>> {
+        match super::toml_get(&self.doc, &["workspace", "package", "version"]) {
+            Some(item) => {
+                let text = item
+                    .as_str()
+                    .ok_or_else(|| anyhow!("workspace.package.version in {} is not a string", self.path.display()))?;
+                Ok(Some(Version::parse(text)?))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Replace the shared version in `workspace.package.version`.
+    pub fn set_workspace_version(&mut self, next: &Version) -> anyhow::Result<()> {
+        super::toml_set(&mut self.doc, &["workspace", "package", "version"], &next.to_string())
+    }
+
+    /// The workspace member patterns and exclusions listed in `[workspace]`.
+    pub fn workspace_members(&self) -> Option<(Vec<String>, Vec<String>)> {
+        let workspace = self.doc.get("workspace")?;
+        let strings = |key: &str| -> Vec<String> {
+            workspace
+                .get(key)
+                .and_then(|m| m.as_array())
+                .map(|a| a.iter().filter_map(|v| v.as_str()).map(String::from).collect())
+                .unwrap_or_default()
+        };
+        Some((strings("members"), strings("exclude")))
+    }
+
+    /// Rewrite the version requirement of every path dependency on the named package,
+    /// including any in `[workspace.dependencies]`, so it accepts the new version. The
+    /// requirement keeps its operator and precision, so `^1.2` becomes `^1.3`.
+    /// Returns whether anything changed.
+    pub fn update_dependency(&mut self, name: &str, next: &Version) -> anyhow::Result<bool> {
+        let path = self.path.clone();
+        let mut tables: Vec<&mut Item> = Vec::new();
+        let root = self.doc.as_table_mut();
+        for (key, item) in root.iter_mut() {
+            match key.get() {
+                key if DEPENDENCY_TABLES.contains(&key) => tables.push(item),
+                "target" => {
+                    if let Some(targets) = item.as_table_like_mut() {
+                        for (_, target) in targets.iter_mut() {
+                            if let Some(target) = target.as_table_like_mut() {
+                                for (key, item) in target.iter_mut() {
+                                    if DEPENDENCY_TABLES.contains(&key.get()) {
+                                        tables.push(item);
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+                "workspace" => {
+                    if let Some(deps) = item.get_mut("dependencies") {
+                        tables.push(deps);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut changed = false;
+        for table in tables {
+            let Some(table) = table.as_table_like_mut() else {
+                continue;
+            };
+            for (key, dep) in table.iter_mut() {
+                let Some(dep) = dep.as_table_like_mut() else {
+                    continue;
+                };
+                let package = dep.get("package").and_then(|p| p.as_str()).unwrap_or(key.get());
+                if package != name || !dep.contains_key("path") {
+                    continue;
+                }
+                let Some(item) = dep.get_mut("version") else {
+                    continue;
+                };
+                let Some(old) = item.as_value() else {
+                    continue;
+                };
+                let requirement = old.as_str().unwrap_or_default();
+                let Some(rewritten) = rewrite_requirement(requirement, next)
+                    .map_err(|e| anyhow!("unable to update the requirement on {name} in {}: {e}", path.display()))?
+                else {
+                    continue;
+                };
+                if rewritten != requirement {
+                    let mut replacement = Value::from(rewritten);
+                    *replacement.decor_mut() = old.decor().clone();
+                    *item = Item::Value(replacement);
+                    changed = true;
+                }
+            }
+        }
+        Ok(changed)
+    }
+
+    fn version_value(&self) -> anyhow::Result<&Value> {
+        let item = self
+            .doc
+            .get("package")
+            .and_then(|p| p.get("version"))
+            .ok_or_else(|| anyhow!("{} does not have a package.version field", self.path.display()))?;
+        if item.get("workspace").is_some() {
+            return Err(anyhow!(
+                "{} inherits its version from the workspace; bump the workspace manifest instead",
+                self.path.display()
+            ));
+        }
+        item.as_value()
+            .filter(|v| v.is_str())
+            .ok_or_else(|| anyhow!("package.version in {} is not a string", self.path.display()))
+    }
+}
+
+impl Manifest for CargoManifest {
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn version(&self) -> anyhow::Result<Version> {
+        if self.shared {
+            return self.workspace_version()?.ok_or_else(|| {
+                anyhow!(
+                    "{} does not have a workspace.package.version field",
+                    self.path.display()
+                )
+            });
+        }
+        let value = self.version_value()?;
+        let version = Version::parse(value.as_str().unwrap_or_default())?;
+        Ok(version)
+    }
+
+    fn set_version(&mut self, next: &Version) -> anyhow::Result<()> {
+        if self.shared {
+            self.version()?;
+            return self.set_workspace_version(next);
+        }
+        self.version_value()?;
+        super::toml_set(&mut self.doc, &["package", "version"], &next.to_string())
+    }
+
+    fn contents(&self) -> String {
+        self.doc.to_string()
+    }
+}
+
+/// Rewrite a version requirement so it accepts the new version, keeping its operator
+/// and the number of components it spells out: `^1.2` becomes `^1.3` for `1.3.0`,
+/// and `=1.2.3` becomes `=1.3.0`. A pre-release always gets a full version, since
+/// `1.3` would not match `1.3.0-rc.1`. Requirements that can't be rewritten this way,
+/// such as ranges and wildcards, are left alone if they already accept the new
+/// version, and are an error otherwise.
+pub fn rewrite_requirement(requirement: &str, next: &Version) -> anyhow::Result<Option<String>> {
+    let parsed = VersionReq::parse(requirement)?;
+    let rewritable = match parsed.comparators.as_slice() {
+        [only] => matches!(only.op, Op::Caret | Op::Tilde | Op::Exact | Op::GreaterEq) && !requirement.contains('*'),
+        _ => false,
+    };
+    if !rewritable {
+        if parsed.matches(next) {
+            return Ok(None);
+        }
+        return Err(anyhow!(
+            "`{requirement}` does not accept {next}, and it's too complex to rewrite"
+        ));
+    }
+
+    let trimmed = requirement.trim();
+    let start = trimmed.find(|c: char| c.is_ascii_digit()).unwrap_or(trimmed.len());
+    let (operator, version) = trimmed.split_at(start);
+    let precision = version.split(['-', '+']).next().unwrap_or_default().split('.').count();
+    let core = Version::new(next.major, next.minor, next.patch);
+    let rendered = match precision {
+        _ if !next.pre.is_empty() => format!("{core}-{}", next.pre),
+        1 => next.major.to_string(),
+        2 => format!("{}.{}", next.major, next.minor),
+        _ => core.to_string(),
+    };
+    Ok(Some(format!("{operator}{rendered}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = r#"[package]
+name = "example"
+# keep this comment
+version   = "1.2.3" # and this one
+edition = "2021"
+
+[dependencies]
+semver = { version = "1.0.23" }
+"#;
+
+    #[test]
+    fn cargo_manifest_bump() {
+        let mut manifest = CargoManifest::parse("Cargo.toml", MANIFEST).expect("test data must be valid toml");
+        let previous = manifest.version().expect("we expected to find the version");
+        assert_eq!(previous.to_string(), "1.2.3");
+        let next = Version::parse("1.3.0").expect("test data must be valid semver");
+        manifest
+            .set_version(&next)
+            .expect("we expected setting the version to work");
+        assert_eq!(manifest.contents(), MANIFEST.replace("1.2.3", "1.3.0"));
+    }
+
+    #[test]
+    fn rewriting_requirements() {
+        let next = Version::parse("1.3.0").expect("test data must be valid semver");
+        let rewrite = |req: &str| rewrite_requirement(req, &next).expect("we expected the requirement to be handled");
+        assert_eq!(rewrite("1.2.3").as_deref(), Some("1.3.0"));
+        assert_eq!(rewrite("^1.2").as_deref(), Some("^1.3"));
+        assert_eq!(rewrite("~1").as_deref(), Some("~1"));
+        assert_eq!(rewrite("= 1.2.3").as_deref(), Some("= 1.3.0"));
+        assert_eq!(rewrite(">=1, <2"), None);
+        assert_eq!(rewrite("1.*"), None);
+        rewrite_requirement("<1.3", &next).expect_err("we expected an unsatisfiable range to be an error");
+        let pre = Version::parse("2.0.0-rc.1").expect("test data must be valid semver");
+        assert_eq!(
+            rewrite_requirement("^1.2", &pre).expect("we expected the requirement to be handled"),
+            Some("^2.0.0-rc.1".to_string())
+        );
+    }
+
+    #[test]
+    fn updating_dependencies() {
+        let text = r#"[package]
+name = "app"
+version = "0.1.0"
+
+[dependencies]
+core = { path = "../core", version = "^1.2" } # internal
+renamed = { package = "core", path = "../core", version = "1.2.3" }
+serde = "1"
+
+[target.'cfg(unix)'.dev-dependencies.core]
+path = "../core"
+version = "~1.2"
+
+[dependencies.other]
+version = "1.2"
+"#;
+        let mut manifest = CargoManifest::parse("app/Cargo.toml", text).expect("test data must be valid toml");
+        let next = Version::parse("1.3.0").expect("test data must be valid semver");
+        assert!(manifest
+            .update_dependency("core", &next)
+            .expect("we expected the update to work"));
+        assert_eq!(
+            manifest.contents(),
+            text.replace("\"^1.2\"", "\"^1.3\"")
+                .replace("\"1.2.3\"", "\"1.3.0\"")
+                .replace("\"~1.2\"", "\"~1.3\"")
+        );
+        assert!(!manifest
+            .update_dependency("serde", &next)
+            .expect("we expected the update to work"));
+    }
+
+    #[test]
+    fn cargo_manifest_errors() {
+        let manifest = CargoManifest::parse("Cargo.toml", "[package]\nname = \"example\"\n")
+            .expect("test data must be valid toml");
+        manifest
+            .version()
+            .expect_err("we expected a missing version to be an error");
+        let manifest = CargoManifest::parse("Cargo.toml", "[package]\nversion.workspace = true\n")
+            .expect("test data must be valid toml");
+        manifest
+            .version()
+            .expect_err("we expected an inherited version to be an error");
+        CargoManifest::parse("Cargo.toml", "[package\n").expect_err("we expected invalid toml to be an error");
+    }
+}
@@ -0,0 +1,181 @@
+//! Releasing the unreleased changes in a changelog kept in the
+//! [Keep a Changelog](https://keepachangelog.com/) format: the `## [Unreleased]`
+//! section becomes the section for the new version, a fresh `## [Unreleased]`
+//! goes above it, and the comparison links at the bottom are brought up to date.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::anyhow;
+use semver::Version;
+
+use super::Manifest;
+use crate::calver::Date;
+
+/// A Keep a Changelog file.
+#[derive(Clone, Debug)]
+pub struct Changelog {
+    path: PathBuf,
+    contents: String,
+    tag_prefix: String,
+    date: Date,
+}
+
+impl Changelog {
+    /// Read the changelog at the given path. New sections are dated with the given
+    /// date, and linked to tags named with the given prefix.
+    pub fn open(path: impl AsRef<Path>, tag_prefix: &str, date: Date) -> anyhow::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let contents = super::read(&path)?;
+        Ok(Self::parse(path, &contents, tag_prefix, date))
+    }
+
+    /// Use changelog text that was read from the given path.
+    pub fn parse(path: impl Into<PathBuf>, contents: &str, tag_prefix: &str, date: Date) -> Self {
+        Self {
+            path: path.into(),
+            contents: contents.to_string(),
+            tag_prefix: tag_prefix.to_string(),
+            date,
+        }
+    }
+
+    /// The titles of the second-level headings, without their brackets, alongside
+    /// where each heading line sits.
+    fn sections(&self) -> Vec<(&str, std::ops::Range<usize>)> {
+        let mut found = Vec::new();
+        let mut offset = 0;
+        for line in self.contents.split_inclusive('\n') {
+            if let Some(title) = line.strip_prefix("## ") {
+                let title = title.trim();
+                let title = match title.strip_prefix('[').and_then(|t| t.split_once(']')) {
+                    Some((name, _)) => name,
+                    None => title.split_whitespace().next().unwrap_or_default(),
+                };
+                found.push((title, offset..offset + line.len()));
+            }
+            offset += line.len();
+        }
+        found
+    }
+
+    /// Where the `[Unreleased]: <url>` link definition sits, if there is one.
+    fn unreleased_link(&self) -> Option<(std::ops::Range<usize>, &str)> {
+        let mut offset = 0;
+        for line in self.contents.split_inclusive('\n') {
+            if let Some(url) = line.to_ascii_lowercase().strip_prefix("[unreleased]:") {
+                let start = line.len() - url.len();
+                return Some((offset..offset + line.len(), line[start..].trim()));
+            }
+            offset += line.len();
+        }
+        None
+    }
+}
+
+impl Manifest for Changelog {
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The version of the newest released section.
+    fn version(&self) -> anyhow::Result<Version> {
+        self.sections()
+            .into_iter()
+            .find_map(|(title, _)| crate::parse_prefixed(title).ok())
+            .map(|(_, version)| version)
+            .ok_or_else(|| anyhow!("{} does not have a section for any release", self.path.display()))
+    }
+
+    /// Turn the unreleased section into a section for this version, and link it to
+    /// the comparison between the previous tag and the new one.
+    fn set_version(&mut self, next: &Version) -> anyhow::Result<()> {
+        let (_, heading) = self
+            .sections()
+            .into_iter()
+            .find(|(title, _)| title.eq_ignore_ascii_case("unreleased"))
+            .ok_or_else(|| anyhow!("{} has no `## [Unreleased]` section to release", self.path.display()))?;
+        let Date { year, month, day } = self.date;
+        let line = self.contents[heading.clone()].trim_end().to_string();
+        let released = format!("{line}\n\n## [{next}] - {year:04}-{month:02}-{day:02}\n");
+
+        // The unreleased link compares the last tag with HEAD; split it into the new
+        // version's comparison and a comparison of the new tag with HEAD.
+        let link = self.unreleased_link().and_then(|(range, url)| {
+            let (base, refs) = url.rsplit_once("/compare/")?;
+            let (previous, head) = refs.split_once("...")?;
+            let tag = format!("{}{next}", self.tag_prefix);
+            let label = self.contents[range.clone()].split_once(':')?.0;
+            let links =
+                format!("{label}: {base}/compare/{tag}...{head}\n[{next}]: {base}/compare/{previous}...{tag}\n");
+            Some((range, links))
+        });
+        // Edit from the end of the file back, so the earlier range stays put.
+        let mut edits: Vec<_> = link.into_iter().chain([(heading, released)]).collect();
+        edits.sort_by_key(|(range, _)| std::cmp::Reverse(range.start));
+        for (range, text) in edits {
+            self.contents.replace_range(range, &text);
+        }
+        Ok(())
+    }
+
+    fn contents(&self) -> String {
+        self.contents.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHANGELOG: &str = "# Changelog
+
+## [Unreleased]
+
+### Added
+
+- Tags can be signed.
+
+## [1.2.3] - 2024-01-02
+
+- Fixed a bug.
+
+[Unreleased]: https://github.com/ceejbot/semver-bump/compare/v1.2.3...HEAD
+[1.2.3]: https://github.com/ceejbot/semver-bump/compare/v1.2.2...v1.2.3
+";
+
+    #[test]
+    fn releasing_a_changelog() {
+        let date = Date {
+            year: 2024,
+            month: 3,
+            day: 9,
+        };
+        let mut changelog = Changelog::parse("CHANGELOG.md", CHANGELOG, "v", date);
+        assert_eq!(
+            changelog.version().expect("we expected to find a release").to_string(),
+            "1.2.3"
+        );
+        changelog
+            .set_version(&Version::new(1, 3, 0))
+            .expect("we expected the release to work");
+        let expected = CHANGELOG
+            .replace(
+                "## [Unreleased]\n",
+                "## [Unreleased]\n\n## [1.3.0] - 2024-03-09\n",
+            )
+            .replace(
+                "[Unreleased]: https://github.com/ceejbot/semver-bump/compare/v1.2.3...HEAD\n",
+                "[Unreleased]: https://github.com/ceejbot/semver-bump/compare/v1.3.0...HEAD\n[1.3.0]: https://github.com/ceejbot/semver-bump/compare/v1.2.3...v1.3.0\n",
+            );
+        assert_eq!(changelog.contents(), expected);
+        assert_eq!(
+            changelog.version().expect("we expected to find a release").to_string(),
+            "1.3.0"
+        );
+
+        let mut changelog = Changelog::parse("CHANGELOG.md", "# Changelog\n\n## 1.2.3\n", "v", date);
+        changelog
+            .set_version(&Version::new(1, 3, 0))
+            .expect_err("we expected a changelog without an unreleased section to be an error");
+    }
+}
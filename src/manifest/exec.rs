@@ -0,0 +1,162 @@
+//! Handing a file we have no handler for to a program that understands it, named
+//! with `--manifest-type exec:PROGRAM`. The program is run twice, with the request
+//! as JSON on its stdin and its answer as JSON on its stdout:
+//!
+//! - `PROGRAM read` gets `{"path": ..., "contents": ...}` and answers with
+//!   `{"version": "1.2.3"}`.
+//! - `PROGRAM write` gets `{"path": ..., "contents": ..., "previous": "1.2.3",
+//!   "version": "1.3.0"}` and answers with `{"contents": ...}`, the file's new
+//!   contents.
+//!
+//! The program never touches the file itself, so dry runs, diffs, and writing
+//! several files together work just as they do for the built-in handlers. Exiting
+//! unsuccessfully is an error, and anything it prints to stderr is passed along.
+//! `PROGRAM` may include arguments, as in `exec:python3 tools/handler.py`.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+use anyhow::{anyhow, Context};
+use semver::Version;
+use serde_json::{json, Value};
+
+use super::Manifest;
+
+/// A file whose version a program of the user's reads and writes.
+#[derive(Clone, Debug)]
+pub struct ExecManifest {
+    handler: String,
+    path: PathBuf,
+    contents: String,
+    prefix: String,
+    version: Version,
+}
+
+impl ExecManifest {
+    /// Read the file at the given path and ask the handler for its version.
+    pub fn open(handler: &str, path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let contents = super::read(&path)?;
+        let answer = call(handler, "read", json!({"path": path, "contents": contents}))?;
+        let found = answer["version"]
+            .as_str()
+            .ok_or_else(|| anyhow!("`{handler} read` did not answer with a version for {}", path.display()))?;
+        let (prefix, version) = crate::parse_prefixed(found)
+            .with_context(|| format!("`{handler} read` found the version `{found}` in {}", path.display()))?;
+        Ok(Self {
+            handler: handler.to_string(),
+            path,
+            contents,
+            prefix,
+            version,
+        })
+    }
+}
+
+impl Manifest for ExecManifest {
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn version(&self) -> anyhow::Result<Version> {
+        Ok(self.version.clone())
+    }
+
+    fn set_version(&mut self, next: &Version) -> anyhow::Result<()> {
+        let request = json!({
+            "path": self.path,
+            "contents": self.contents,
+            "previous": self.version.to_string(),
+            "version": next.to_string(),
+        });
+        let answer = call(&self.handler, "write", request)?;
+        self.contents = answer["contents"]
+            .as_str()
+            .ok_or_else(|| anyhow!("`{} write` did not answer with the new contents", self.handler))?
+            .to_string();
+        self.version = next.clone();
+        Ok(())
+    }
+
+    fn contents(&self) -> String {
+        self.contents.clone()
+    }
+
+    fn prefix(&self) -> String {
+        self.prefix.clone()
+    }
+}
+
+/// Run the handler for one request and read its answer.
+fn call(handler: &str, action: &str, request: Value) -> anyhow::Result<Value> {
+    let mut words = handler.split_whitespace();
+    let program = words.next().ok_or_else(|| anyhow!("exec: needs a program to run"))?;
+    let mut child = Command::new(program)
+        .args(words)
+        .arg(action)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .with_context(|| format!("unable to run the manifest handler `{handler}`"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // A handler that doesn't read its request closes the pipe, which is its business.
+        let _ = stdin.write_all(request.to_string().as_bytes());
+    }
+    let output = child.wait_with_output()?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!(
+            "`{handler} {action}` failed ({}): {}",
+            output.status,
+            stderr.trim()
+        ));
+    }
+    serde_json::from_slice(&output.stdout).with_context(|| format!("`{handler} {action}` did not answer with JSON"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn running_handlers() {
+        let dir = tempfile::tempdir().expect("we expected to be able to make a temp dir");
+        let request = dir.path().join("request.json");
+        let script = dir.path().join("handler.sh");
+        fs::write(
+            &script,
+            format!(
+                "case \"$1\" in\n  read) cat >/dev/null; echo '{{\"version\": \"v1.2.3\"}}' ;;\n  write) cat >'{}'; echo '{{\"contents\": \"version v1.3.0\\\\n\"}}' ;;\n  *) echo \"no $1\" >&2; exit 1 ;;\nesac\n",
+                request.display()
+            ),
+        )
+        .expect("we expected to be able to write test data");
+        let path = dir.path().join("release.cfg");
+        fs::write(&path, "version v1.2.3\n").expect("we expected to be able to write test data");
+
+        let handler = format!("sh {}", script.display());
+        let mut manifest = ExecManifest::open(&handler, &path).expect("we expected the handler to read the version");
+        assert_eq!(
+            manifest.version().expect("we expected a version"),
+            Version::new(1, 2, 3)
+        );
+        assert_eq!(manifest.prefix(), "v");
+        manifest
+            .set_version(&Version::new(1, 3, 0))
+            .expect("we expected the handler to write the version");
+        assert_eq!(manifest.contents(), "version v1.3.0\n");
+        let sent: Value = serde_json::from_str(&fs::read_to_string(&request).expect("we expected a request"))
+            .expect("we expected the request to be JSON");
+        assert_eq!(sent["contents"], "version v1.2.3\n");
+        assert_eq!(sent["previous"], "1.2.3");
+        assert_eq!(sent["version"], "1.3.0");
+
+        fs::write(&script, "echo broken >&2; exit 2\n").expect("we expected to be able to write test data");
+        let error = ExecManifest::open(&handler, &path).expect_err("we expected a failing handler to be an error");
+        assert!(error.to_string().ends_with("broken"));
+    }
+}
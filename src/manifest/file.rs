@@ -0,0 +1,138 @@
+//! Reading a version from a plain text file and writing the bumped version back.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::anyhow;
+use semver::Version;
+
+use super::Manifest;
+
+/// The names of the version files we look for at the root of a repository, in order.
+pub const CONVENTIONAL_NAMES: [&str; 2] = ["VERSION", ".version"];
+
+/// A file containing nothing but a version number, like the `VERSION` file
+/// many repos keep at their root. The version may have a leading `v`, which is
+/// kept when the version is replaced.
+#[derive(Clone, Debug)]
+pub struct VersionFile {
+    path: PathBuf,
+    contents: String,
+}
+
+impl VersionFile {
+    /// Read the file at the given path.
+    pub fn open(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let contents = super::read(&path)?;
+        Ok(Self { path, contents })
+    }
+
+    /// Find the conventional version file in the given directory: `VERSION`, or failing
+    /// that, `.version`.
+    pub fn discover(dir: impl AsRef<Path>) -> anyhow::Result<PathBuf> {
+        let dir = dir.as_ref();
+        CONVENTIONAL_NAMES
+            .iter()
+            .map(|name| dir.join(name))
+            .find(|path| path.is_file())
+            .ok_or_else(|| {
+                let here = if dir.as_os_str().is_empty() {
+                    Path::new(".")
+                } else {
+                    dir
+                };
+                let dir = std::fs::canonicalize(here).unwrap_or_else(|_| dir.to_path_buf());
+                anyhow!(
+                    "There is no {} file in {}.",
+                    CONVENTIONAL_NAMES.join(" or "),
+                    dir.display()
+                )
+            })
+    }
+}
+
+impl Manifest for VersionFile {
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn version(&self) -> anyhow::Result<Version> {
+        let (_, version) = crate::parse_prefixed(&self.contents)?;
+        Ok(version)
+    }
+
+    fn prefix(&self) -> String {
+        crate::parse_prefixed(&self.contents)
+            .map(|(prefix, _)| prefix)
+            .unwrap_or_default()
+    }
+
+    /// Replace the version in this file, keeping any `v` prefix, and ending it with a
+    /// single newline if the file had any trailing newlines.
+    fn set_version(&mut self, next: &Version) -> anyhow::Result<()> {
+        let newline = if self.contents.ends_with('\n') { "\n" } else { "" };
+        self.contents = format!("{}{next}{newline}", self.prefix());
+        Ok(())
+    }
+
+    fn contents(&self) -> String {
+        self.contents.clone()
+    }
+}
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn version_file_round_trip() {
+        let dir = tempfile::tempdir().expect("we expected to be able to make a temp dir");
+        let path = dir.path().join("VERSION");
+        fs::write(&path, "1.2.3\n").expect("we expected to be able to write test data");
+
+        let mut file = VersionFile::open(&path).expect("we expected to be able to read the file");
+        let previous = file.version().expect("test data must be valid semver");
+        assert_eq!(previous.to_string(), "1.2.3");
+        let next = Version::parse("1.3.0").expect("test data must be valid semver");
+        file.set_version(&next)
+            .expect("we expected setting the version to work");
+        file.write().expect("we expected the write to work");
+        let written = fs::read_to_string(&path).expect("we expected to be able to read the file");
+        assert_eq!(written, "1.3.0\n");
+
+        fs::write(&path, "2.0.0").expect("we expected to be able to write test data");
+        let mut file = VersionFile::open(&path).expect("we expected to be able to read the file");
+        file.set_version(&next)
+            .expect("we expected setting the version to work");
+        assert_eq!(file.contents(), "1.3.0");
+    }
+
+    #[test]
+    fn missing_version_file() {
+        let dir = tempfile::tempdir().expect("we expected to be able to make a temp dir");
+        VersionFile::open(dir.path().join("nope")).expect_err("we expected a missing file to be an error");
+        VersionFile::discover(dir.path()).expect_err("we expected discovery in an empty dir to fail");
+    }
+
+    #[test]
+    fn conventional_version_file() {
+        let dir = tempfile::tempdir().expect("we expected to be able to make a temp dir");
+        let path = dir.path().join(".version");
+        fs::write(&path, "v1.2.3\n\n").expect("we expected to be able to write test data");
+        assert_eq!(
+            VersionFile::discover(dir.path()).expect("we expected to find the file"),
+            path
+        );
+
+        let mut file = VersionFile::open(&path).expect("we expected to be able to read the file");
+        assert_eq!(
+            file.version().expect("test data must be valid semver").to_string(),
+            "1.2.3"
+        );
+        assert_eq!(file.prefix(), "v");
+        file.set_version(&Version::new(1, 3, 0))
+            .expect("we expected setting the version to work");
+        assert_eq!(file.contents(), "v1.3.0\n");
+    }
+}
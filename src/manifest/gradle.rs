@@ -0,0 +1,149 @@
+//! Bumping the project version in a Gradle build: the `version=` entry in a
+//! `gradle.properties`, or the `version = "..."` assignment in a `build.gradle`
+//! or `build.gradle.kts`. Only the version itself is rewritten, so the rest of
+//! the file is preserved byte for byte.
+
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context};
+use semver::Version;
+
+use super::Manifest;
+
+/// Which kind of Gradle file holds the version.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Layout {
+    /// A `gradle.properties` file, with a `version=1.2.3` entry.
+    Properties,
+    /// A Groovy or Kotlin build script, with a `version = "1.2.3"` assignment.
+    Script,
+}
+
+/// A Gradle file holding the project version.
+#[derive(Clone, Debug)]
+pub struct GradleFile {
+    path: PathBuf,
+    contents: String,
+    span: Range<usize>,
+}
+
+impl GradleFile {
+    /// Read the file at the given path, telling its layout from its extension.
+    pub fn open(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let text = super::read(&path)?;
+        let layout = match path.extension().and_then(|e| e.to_str()) {
+            Some("properties") => Layout::Properties,
+            _ => Layout::Script,
+        };
+        Self::parse(path, &text, layout)
+    }
+
+    /// Parse a file of the given layout that was read from the given path.
+    pub fn parse(path: impl Into<PathBuf>, text: &str, layout: Layout) -> anyhow::Result<Self> {
+        let path = path.into();
+        let span = find_version(text, layout).ok_or_else(|| match layout {
+            Layout::Properties => anyhow!("{} does not have a version= entry", path.display()),
+            Layout::Script => anyhow!("{} does not assign a quoted version", path.display()),
+        })?;
+        Ok(Self {
+            path,
+            contents: text.to_string(),
+            span,
+        })
+    }
+}
+
+impl Manifest for GradleFile {
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn version(&self) -> anyhow::Result<Version> {
+        let text = &self.contents[self.span.clone()];
+        Version::parse(text).with_context(|| format!("the version in {} is `{text}`", self.path.display()))
+    }
+
+    fn set_version(&mut self, next: &Version) -> anyhow::Result<()> {
+        let next = next.to_string();
+        self.contents.replace_range(self.span.clone(), &next);
+        self.span = self.span.start..self.span.start + next.len();
+        Ok(())
+    }
+
+    fn contents(&self) -> String {
+        self.contents.clone()
+    }
+}
+
+/// Find the first line that sets the version, returning where its value sits.
+fn find_version(text: &str, layout: Layout) -> Option<Range<usize>> {
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let span = line.trim_start().strip_prefix("version").and_then(|rest| {
+            let (value, start) = match layout {
+                Layout::Properties => {
+                    let value = rest.trim_start().strip_prefix(['=', ':'])?.trim_start();
+                    (value.trim_end(), line.len() - value.len())
+                }
+                Layout::Script => {
+                    let value = rest.trim_start();
+                    let value = value.strip_prefix('=').map(str::trim_start).unwrap_or(value);
+                    // The key must be followed by an `=` or, as in Groovy's `version '1.2.3'`, a space.
+                    if value.len() == rest.len() {
+                        return None;
+                    }
+                    let quote = value.chars().next().filter(|c| matches!(c, '"' | '\''))?;
+                    let inner = &value[1..];
+                    let close = inner.find(quote)?;
+                    (&inner[..close], line.len() - inner.len())
+                }
+            };
+            (!value.is_empty()).then(|| offset + start..offset + start + value.len())
+        });
+        if span.is_some() {
+            return span;
+        }
+        offset += line.len();
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bumping_gradle_files() {
+        let next = Version::new(1, 3, 0);
+        for (text, layout) in [
+            (
+                "org.gradle.jvmargs=-Xmx2g\nversion = 1.2.3\ngroup=com.example\n",
+                Layout::Properties,
+            ),
+            (
+                "plugins { java }\n\nversion = \"1.2.3\" // the release\n",
+                Layout::Script,
+            ),
+            ("group 'com.example'\nversion '1.2.3'\n", Layout::Script),
+        ] {
+            let mut file = GradleFile::parse("build", text, layout).expect("test data must have a version");
+            assert_eq!(
+                file.version().expect("we expected to find the version").to_string(),
+                "1.2.3"
+            );
+            file.set_version(&next)
+                .expect("we expected setting the version to work");
+            assert_eq!(file.contents(), text.replace("1.2.3", "1.3.0"));
+        }
+    }
+
+    #[test]
+    fn gradle_errors() {
+        GradleFile::parse("gradle.properties", "versionCode=3\n", Layout::Properties)
+            .expect_err("we expected a different key to be ignored");
+        GradleFile::parse("build.gradle.kts", "version = rootProject.version\n", Layout::Script)
+            .expect_err("we expected an unquoted expression to be an error");
+    }
+}
@@ -0,0 +1,209 @@
+//! Bumping the versions in a Helm chart's `Chart.yaml`: the `version` of the
+//! chart itself, the `appVersion` of the application it deploys, or both. The
+//! file is edited line by line, so comments and formatting are left alone.
+
+use std::fmt::{self, Display};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context};
+use semver::Version;
+
+use super::Manifest;
+
+/// A version field in a `Chart.yaml`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChartField {
+    /// The version of the chart, which Helm requires to be semver.
+    Version,
+    /// The version of the application the chart deploys.
+    AppVersion,
+}
+
+impl Display for ChartField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ChartField::Version => "version",
+            ChartField::AppVersion => "appVersion",
+        };
+        write!(f, "{name}")
+    }
+}
+
+impl FromStr for ChartField {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        match input {
+            "version" => Ok(ChartField::Version),
+            "appVersion" => Ok(ChartField::AppVersion),
+            _ => Err(anyhow!(
+                "`{input}` is not a chart field; expected one of version or appVersion"
+            )),
+        }
+    }
+}
+
+/// A Helm chart manifest.
+#[derive(Clone, Debug)]
+pub struct HelmChart {
+    path: PathBuf,
+    contents: String,
+    fields: Vec<ChartField>,
+}
+
+impl HelmChart {
+    /// Read the chart manifest at the given path, bumping the chart `version`.
+    pub fn open(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let contents = super::read(&path)?;
+        Ok(Self::parse(path, &contents))
+    }
+
+    /// Use chart manifest text that was read from the given path, bumping the chart `version`.
+    pub fn parse(path: impl Into<PathBuf>, contents: &str) -> Self {
+        Self {
+            path: path.into(),
+            contents: contents.to_string(),
+            fields: vec![ChartField::Version],
+        }
+    }
+
+    /// Bump these fields instead of the chart `version`. When there is more than one,
+    /// they must all hold the same version.
+    pub fn with_fields(mut self, fields: Vec<ChartField>) -> Self {
+        if !fields.is_empty() {
+            self.fields = fields;
+        }
+        self
+    }
+
+    /// Where the value of a top-level field sits in the file, without any quotes.
+    fn span(&self, field: ChartField) -> anyhow::Result<Range<usize>> {
+        let key = format!("{field}:");
+        let mut offset = 0;
+        for line in self.contents.split_inclusive('\n') {
+            if let Some(rest) = line.strip_prefix(key.as_str()) {
+                let value = rest.trim_start();
+                let start = offset + key.len() + (rest.len() - value.len());
+                let len = match value.chars().next() {
+                    Some(quote @ ('"' | '\'')) => {
+                        let close = value[1..].find(quote).ok_or_else(|| {
+                            anyhow!("the {field} field in {} has no closing quote", self.path.display())
+                        })?;
+                        return Ok(start + 1..start + 1 + close);
+                    }
+                    _ => value.find(" #").unwrap_or(value.len()),
+                };
+                return Ok(start..start + value[..len].trim_end().len());
+            }
+            offset += line.len();
+        }
+        Err(anyhow!("{} does not have a {field} field", self.path.display()))
+    }
+
+    fn value(&self, field: ChartField) -> anyhow::Result<(String, Version)> {
+        let text = &self.contents[self.span(field)?];
+        crate::parse_prefixed(text).with_context(|| format!("the {field} field in {} is `{text}`", self.path.display()))
+    }
+}
+
+impl Manifest for HelmChart {
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn version(&self) -> anyhow::Result<Version> {
+        let (first, rest) = self
+            .fields
+            .split_first()
+            .ok_or_else(|| anyhow!("there are no fields to bump"))?;
+        let (_, version) = self.value(*first)?;
+        for field in rest {
+            let (_, other) = self.value(*field)?;
+            if other != version {
+                return Err(anyhow!(
+                    "{} has {first} {version} but {field} {other}; bump them separately",
+                    self.path.display()
+                ));
+            }
+        }
+        Ok(version)
+    }
+
+    fn prefix(&self) -> String {
+        self.fields
+            .first()
+            .and_then(|field| self.value(*field).ok())
+            .map(|(prefix, _)| prefix)
+            .unwrap_or_default()
+    }
+
+    /// Replace the value of each field, keeping its quotes and any `v` prefix.
+    fn set_version(&mut self, next: &Version) -> anyhow::Result<()> {
+        for field in self.fields.clone() {
+            let (prefix, _) = self.value(field)?;
+            let span = self.span(field)?;
+            self.contents.replace_range(span, &format!("{prefix}{next}"));
+        }
+        Ok(())
+    }
+
+    fn contents(&self) -> String {
+        self.contents.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHART: &str = r#"apiVersion: v2
+name: example
+# The chart version.
+version: 0.4.2 # bump me
+appVersion: "v1.16.0"
+dependencies:
+  - name: redis
+    version: 17.0.0
+"#;
+
+    #[test]
+    fn bumping_a_chart() {
+        let mut chart = HelmChart::parse("Chart.yaml", CHART);
+        assert_eq!(
+            chart.version().expect("we expected to find the version").to_string(),
+            "0.4.2"
+        );
+        chart
+            .set_version(&Version::new(0, 5, 0))
+            .expect("we expected setting the version to work");
+        assert_eq!(chart.contents(), CHART.replace("0.4.2", "0.5.0"));
+
+        let mut chart = HelmChart::parse("Chart.yaml", CHART).with_fields(vec![ChartField::AppVersion]);
+        assert_eq!(
+            chart.version().expect("we expected to find the version").to_string(),
+            "1.16.0"
+        );
+        assert_eq!(chart.prefix(), "v");
+        chart
+            .set_version(&Version::new(1, 17, 0))
+            .expect("we expected setting the version to work");
+        assert_eq!(chart.contents(), CHART.replace("v1.16.0", "v1.17.0"));
+    }
+
+    #[test]
+    fn chart_errors() {
+        let chart =
+            HelmChart::parse("Chart.yaml", CHART).with_fields(vec![ChartField::Version, ChartField::AppVersion]);
+        chart
+            .version()
+            .expect_err("we expected fields that disagree to be an error");
+        let chart = HelmChart::parse("Chart.yaml", "name: example\n");
+        chart.version().expect_err("we expected a missing field to be an error");
+        "chartVersion"
+            .parse::<ChartField>()
+            .expect_err("we expected an unknown field to be rejected");
+    }
+}
@@ -0,0 +1,147 @@
+//! A tiny scanner for locating a string value inside JSON text, so we can
+//! replace it without re-serializing the document and disturbing its key
+//! order, indentation, or anything else the author cared about.
+
+use std::ops::Range;
+
+use anyhow::{anyhow, Context};
+
+/// Find the byte range of the string value at the given key path, quotes
+/// included. Returns `Ok(None)` if the path does not exist, and an error if
+/// the text is not well-formed JSON or the value there is not a string.
+pub(crate) fn find_string(text: &str, path: &[&str]) -> anyhow::Result<Option<Range<usize>>> {
+    // Validate up front so the scanner below only has to handle good input.
+    serde_json::from_str::<serde_json::Value>(text).context("invalid json")?;
+    let mut scanner = Scanner {
+        bytes: text.as_bytes(),
+        pos: 0,
+    };
+    scanner.find(path)
+}
+
+struct Scanner<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl Scanner<'_> {
+    fn find(&mut self, path: &[&str]) -> anyhow::Result<Option<Range<usize>>> {
+        self.skip_whitespace();
+        let Some((wanted, rest)) = path.split_first() else {
+            if self.peek() == Some(b'"') {
+                let start = self.pos;
+                self.skip_string();
+                return Ok(Some(start..self.pos));
+            }
+            return Err(anyhow!("the value at that location is not a string"));
+        };
+        if self.peek() != Some(b'{') {
+            return Ok(None);
+        }
+        self.pos += 1;
+        loop {
+            self.skip_whitespace();
+            match self.peek() {
+                Some(b'}') | None => return Ok(None),
+                Some(b',') => {
+                    self.pos += 1;
+                    continue;
+                }
+                _ => {}
+            }
+            let key_start = self.pos + 1;
+            self.skip_string();
+            let key = &self.bytes[key_start..self.pos - 1];
+            self.skip_whitespace();
+            // the colon
+            self.pos += 1;
+            if key == wanted.as_bytes() {
+                return self.find(rest);
+            }
+            self.skip_whitespace();
+            self.skip_value();
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.pos).copied()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b'\n' | b'\r')) {
+            self.pos += 1;
+        }
+    }
+
+    fn skip_string(&mut self) {
+        // opening quote
+        self.pos += 1;
+        while let Some(b) = self.peek() {
+            self.pos += 1;
+            match b {
+                b'\\' => self.pos += 1,
+                b'"' => return,
+                _ => {}
+            }
+        }
+    }
+
+    fn skip_value(&mut self) {
+        match self.peek() {
+            Some(b'"') => self.skip_string(),
+            Some(b'{' | b'[') => {
+                let mut depth = 0;
+                while let Some(b) = self.peek() {
+                    match b {
+                        b'"' => {
+                            self.skip_string();
+                            continue;
+                        }
+                        b'{' | b'[' => depth += 1,
+                        b'}' | b']' => {
+                            depth -= 1;
+                            if depth == 0 {
+                                self.pos += 1;
+                                return;
+                            }
+                        }
+                        _ => {}
+                    }
+                    self.pos += 1;
+                }
+            }
+            _ => {
+                while !matches!(self.peek(), Some(b',' | b'}' | b']') | None) {
+                    self.pos += 1;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finding_strings() {
+        let text = r#"{
+  "name": "x",
+  "nested": { "version": "0.0.1", "list": [1, {"version": "no"}] },
+  "escaped\"key": "a \" b",
+  "version": "1.2.3"
+}"#;
+        let found = find_string(text, &["version"])
+            .expect("test data must be valid json")
+            .expect("we expected to find the version");
+        assert_eq!(&text[found], "\"1.2.3\"");
+        let found = find_string(text, &["nested", "version"])
+            .expect("test data must be valid json")
+            .expect("we expected to find the nested version");
+        assert_eq!(&text[found], "\"0.0.1\"");
+        let missing = find_string(text, &["nope"]).expect("test data must be valid json");
+        assert!(missing.is_none());
+        find_string(text, &["nested"]).expect_err("we expected a non-string value to be an error");
+        find_string("{ nope", &["version"]).expect_err("we expected invalid json to be an error");
+    }
+}
@@ -0,0 +1,253 @@
+//! Bumping a version held under any key in a JSON, YAML, or TOML file, named by a
+//! dotted key path such as `tool.mytool.version`. Only the version's text is
+//! replaced, so key order, comments, and formatting are left alone.
+//!
+//! YAML files are read line by line, following block mappings by their
+//! indentation; keys inside lists and flow mappings can't be reached.
+
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context};
+use semver::Version;
+use toml_edit::DocumentMut;
+
+use super::Manifest;
+
+/// The formats a key path can point into.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Format {
+    Json,
+    Toml,
+    Yaml,
+}
+
+/// A structured file with a version under a key path.
+#[derive(Clone, Debug)]
+pub struct KeyedFile {
+    path: PathBuf,
+    contents: String,
+    format: Format,
+    keys: Vec<String>,
+}
+
+impl KeyedFile {
+    /// Read the file at the given path, telling its format from its extension.
+    pub fn open(path: impl AsRef<Path>, key: &str) -> anyhow::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let contents = super::read(&path)?;
+        Self::parse(path, &contents, key)
+    }
+
+    /// Use text that was read from the given path.
+    pub fn parse(path: impl Into<PathBuf>, contents: &str, key: &str) -> anyhow::Result<Self> {
+        let path = path.into();
+        let format = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("json") => Format::Json,
+            Some("toml") => Format::Toml,
+            Some("yaml" | "yml") => Format::Yaml,
+            _ => {
+                return Err(anyhow!(
+                    "{} is not a .json, .yaml, .yml, or .toml file, so there's no key path in it to follow",
+                    path.display()
+                ))
+            }
+        };
+        let keys: Vec<String> = key.split('.').map(str::to_string).collect();
+        if keys.iter().any(String::is_empty) {
+            return Err(anyhow!("`{key}` is not a key path; expected keys separated by dots"));
+        }
+        Ok(Self {
+            path,
+            contents: contents.to_string(),
+            format,
+            keys,
+        })
+    }
+
+    fn key(&self) -> String {
+        self.keys.join(".")
+    }
+
+    /// The text of the value at the key path, without any quotes.
+    fn text(&self) -> anyhow::Result<String> {
+        match self.format {
+            Format::Toml => {
+                let doc = self.toml()?;
+                let keys: Vec<&str> = self.keys.iter().map(String::as_str).collect();
+                let item = super::toml_get(&doc, &keys)
+                    .ok_or_else(|| anyhow!("{} has no {} field", self.path.display(), self.key()))?;
+                item.as_str()
+                    .map(str::to_string)
+                    .ok_or_else(|| anyhow!("the {} field in {} is not a string", self.key(), self.path.display()))
+            }
+            Format::Json | Format::Yaml => Ok(self.contents[self.span()?].to_string()),
+        }
+    }
+
+    /// Where the value at the key path sits in a JSON or YAML file, without quotes.
+    fn span(&self) -> anyhow::Result<Range<usize>> {
+        let keys: Vec<&str> = self.keys.iter().map(String::as_str).collect();
+        let span = match self.format {
+            Format::Json => super::json::find_string(&self.contents, &keys)
+                .map_err(|e| anyhow!("{}: {e}", self.path.display()))?
+                .map(|quoted| quoted.start + 1..quoted.end - 1),
+            Format::Yaml => yaml_span(&self.contents, &keys),
+            Format::Toml => unreachable!("TOML values are found with toml_edit"),
+        };
+        span.ok_or_else(|| anyhow!("{} has no {} field", self.path.display(), self.key()))
+    }
+
+    fn toml(&self) -> anyhow::Result<DocumentMut> {
+        self.contents
+            .parse()
+            .map_err(|e| anyhow!("{} is not valid TOML: {e}", self.path.display()))
+    }
+
+    fn value(&self) -> anyhow::Result<(String, Version)> {
+        let text = self.text()?;
+        crate::parse_prefixed(&text)
+            .with_context(|| format!("the {} field in {} is `{text}`", self.key(), self.path.display()))
+    }
+}
+
+impl Manifest for KeyedFile {
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn version(&self) -> anyhow::Result<Version> {
+        let (_, version) = self.value()?;
+        Ok(version)
+    }
+
+    fn prefix(&self) -> String {
+        self.value().map(|(prefix, _)| prefix).unwrap_or_default()
+    }
+
+    /// Replace the value, keeping its quotes and any `v` prefix.
+    fn set_version(&mut self, next: &Version) -> anyhow::Result<()> {
+        let (prefix, _) = self.value()?;
+        let next = format!("{prefix}{next}");
+        match self.format {
+            Format::Toml => {
+                let mut doc = self.toml()?;
+                let keys: Vec<&str> = self.keys.iter().map(String::as_str).collect();
+                super::toml_set(&mut doc, &keys, &next)?;
+                self.contents = doc.to_string();
+            }
+            Format::Json | Format::Yaml => {
+                let span = self.span()?;
+                self.contents.replace_range(span, &next);
+            }
+        }
+        Ok(())
+    }
+
+    fn contents(&self) -> String {
+        self.contents.clone()
+    }
+}
+
+/// Find the scalar value at the key path in YAML text, following each key into the
+/// more deeply indented block beneath it.
+fn yaml_span(text: &str, keys: &[&str]) -> Option<Range<usize>> {
+    let mut depth = 0;
+    // The indentation of the key we followed, which the lines of its block exceed.
+    let mut parent: Option<usize> = None;
+    // The indentation of the keys in the block we're searching.
+    let mut level: Option<usize> = None;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let start = offset;
+        offset += line.len();
+        let content = line.trim_start_matches(' ');
+        let indent = line.len() - content.len();
+        let content = content.trim_end();
+        if content.is_empty() || content.starts_with('#') || content == "---" {
+            continue;
+        }
+        if parent.is_some_and(|parent| indent <= parent) {
+            return None;
+        }
+        if *level.get_or_insert(indent) != indent {
+            continue;
+        }
+        let Some(rest) = yaml_key(content, keys[depth]) else {
+            continue;
+        };
+        if depth + 1 < keys.len() {
+            depth += 1;
+            parent = Some(indent);
+            level = None;
+            continue;
+        }
+        let value = rest.trim_start();
+        let value_start = start + indent + (content.len() - value.len());
+        return match value.chars().next() {
+            Some(quote @ ('"' | '\'')) => {
+                let close = value[1..].find(quote)?;
+                Some(value_start + 1..value_start + 1 + close)
+            }
+            Some(_) => {
+                let len = value.find(" #").unwrap_or(value.len());
+                Some(value_start..value_start + value[..len].trim_end().len())
+            }
+            None => None,
+        };
+    }
+    None
+}
+
+/// What follows the colon if the line is a mapping entry for this key, quoted or not.
+fn yaml_key<'a>(line: &'a str, key: &str) -> Option<&'a str> {
+    let rest = [format!("{key}:"), format!("\"{key}\":"), format!("'{key}':")]
+        .iter()
+        .find_map(|prefix| line.strip_prefix(prefix.as_str()))?;
+    (rest.is_empty() || rest.starts_with([' ', '\t'])).then_some(rest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bumped(name: &str, text: &str, key: &str) -> anyhow::Result<(String, String)> {
+        let mut file = KeyedFile::parse(name, text, key)?;
+        let previous = file.version()?;
+        file.set_version(&Version::new(previous.major, previous.minor + 1, 0))?;
+        Ok((previous.to_string(), file.contents()))
+    }
+
+    #[test]
+    fn bumping_by_key() {
+        let json = "{\n  \"name\": \"app\",\n  \"tool\": {\"mytool\": {\"version\": \"v1.2.3\"}}\n}\n";
+        let (previous, contents) =
+            bumped("config.json", json, "tool.mytool.version").expect("we expected to bump the JSON");
+        assert_eq!(previous, "1.2.3");
+        assert_eq!(contents, json.replace("v1.2.3", "v1.3.0"));
+
+        let toml = "[tool.mytool]\n# Keep in step with releases.\nversion = \"1.2.3\" # here\n";
+        let (_, contents) =
+            bumped("pyproject.toml", toml, "tool.mytool.version").expect("we expected to bump the TOML");
+        assert_eq!(contents, toml.replace("1.2.3", "1.3.0"));
+
+        let yaml = "name: app\nversion: 9.9.9\ntool:\n  other:\n    version: 0.1.0\n  # the one we want\n  mytool:\n    image: app\n    version: '1.2.3' # pinned\n";
+        let (previous, contents) =
+            bumped("deploy.yaml", yaml, "tool.mytool.version").expect("we expected to bump the YAML");
+        assert_eq!(previous, "1.2.3");
+        assert_eq!(contents, yaml.replace("'1.2.3'", "'1.3.0'"));
+        let (previous, _) = bumped("deploy.yml", yaml, "version").expect("we expected to bump the YAML");
+        assert_eq!(previous, "9.9.9");
+    }
+
+    #[test]
+    fn missing_keys() {
+        let yaml = "tool:\n  mytool:\n    image: app\nversion: 1.2.3\n";
+        bumped("deploy.yaml", yaml, "tool.mytool.version").expect_err("we expected a missing YAML key to be an error");
+        bumped("deploy.yaml", yaml, "tool.version").expect_err("we expected a missing YAML key to be an error");
+        bumped("app.json", "{\"version\": 3}", "version").expect_err("we expected a number to be an error");
+        bumped("app.toml", "[tool]\n", "tool.version").expect_err("we expected a missing TOML key to be an error");
+        KeyedFile::parse("app.ini", "", "version").expect_err("we expected an unknown format to be an error");
+        KeyedFile::parse("app.json", "", "tool..version").expect_err("we expected an empty key to be an error");
+    }
+}
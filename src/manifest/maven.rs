@@ -0,0 +1,154 @@
+//! Bumping the project version in a Maven `pom.xml`. Only the `<version>` that
+//! belongs to `<project>` itself is touched, never the versions of the parent,
+//! dependencies, or plugins, and the rest of the file is left exactly as it was.
+
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context};
+use semver::Version;
+
+use super::Manifest;
+
+/// A Maven project object model.
+#[derive(Clone, Debug)]
+pub struct PomXml {
+    path: PathBuf,
+    contents: String,
+    span: Range<usize>,
+}
+
+impl PomXml {
+    /// Read and parse the pom at the given path.
+    pub fn open(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let text = super::read(&path)?;
+        Self::parse(path, &text)
+    }
+
+    /// Parse pom text that was read from the given path, finding the project version.
+    pub fn parse(path: impl Into<PathBuf>, text: &str) -> anyhow::Result<Self> {
+        let path = path.into();
+        let span = project_version(text).map_err(|e| anyhow!("{}: {e}", path.display()))?;
+        Ok(Self {
+            path,
+            contents: text.to_string(),
+            span,
+        })
+    }
+}
+
+impl Manifest for PomXml {
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn version(&self) -> anyhow::Result<Version> {
+        let text = self.contents[self.span.clone()].trim();
+        Version::parse(text).with_context(|| format!("the project version in {} is `{text}`", self.path.display()))
+    }
+
+    fn set_version(&mut self, next: &Version) -> anyhow::Result<()> {
+        let next = next.to_string();
+        self.contents.replace_range(self.span.clone(), &next);
+        self.span = self.span.start..self.span.start + next.len();
+        Ok(())
+    }
+
+    fn contents(&self) -> String {
+        self.contents.clone()
+    }
+}
+
+/// Find the text of the `<version>` element that is a direct child of `<project>`,
+/// skipping over comments, processing instructions, and CDATA sections.
+fn project_version(text: &str) -> anyhow::Result<Range<usize>> {
+    let mut stack: Vec<&str> = Vec::new();
+    let mut pos = 0;
+    while let Some(found) = text[pos..].find('<') {
+        let start = pos + found;
+        let rest = &text[start..];
+        let skip = [("<!--", "-->"), ("<![CDATA[", "]]>"), ("<?", "?>"), ("<!", ">")]
+            .iter()
+            .find(|(open, _)| rest.starts_with(open));
+        if let Some((_, close)) = skip {
+            let end = rest
+                .find(close)
+                .ok_or_else(|| anyhow!("the file ends inside a `{}`", &rest[..2]))?;
+            pos = start + end + close.len();
+            continue;
+        }
+        let end = start + rest.find('>').ok_or_else(|| anyhow!("the file ends inside a tag"))?;
+        let tag = &text[start + 1..end];
+        pos = end + 1;
+        if let Some(name) = tag.strip_prefix('/') {
+            if stack.pop() != Some(name.trim()) {
+                return Err(anyhow!(
+                    "the closing tag `</{}>` doesn't match its opening tag",
+                    name.trim()
+                ));
+            }
+        } else if !tag.ends_with('/') {
+            let name = tag.split(|c: char| c.is_whitespace()).next().unwrap_or_default();
+            if name == "version" && stack == ["project"] {
+                let close = text[pos..]
+                    .find("</version>")
+                    .ok_or_else(|| anyhow!("the project <version> is never closed"))?;
+                let value = &text[pos..pos + close];
+                let leading = value.len() - value.trim_start().len();
+                return Ok(pos + leading..pos + value.trim_end().len());
+            }
+            stack.push(name);
+        }
+    }
+    Err(anyhow!(
+        "there is no project <version>; if it inherits the version of its parent, bump the parent instead"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POM: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<project xmlns="http://maven.apache.org/POM/4.0.0">
+  <modelVersion>4.0.0</modelVersion>
+  <parent>
+    <groupId>com.example</groupId>
+    <version>7.0.0</version>
+  </parent>
+  <!-- <version>0.0.1</version> -->
+  <artifactId>example</artifactId>
+  <version>1.2.3-SNAPSHOT</version>
+  <dependencies>
+    <dependency>
+      <version>2.0.0</version>
+    </dependency>
+  </dependencies>
+</project>
+"#;
+
+    #[test]
+    fn bumping_a_pom() {
+        let mut pom = PomXml::parse("pom.xml", POM).expect("test data must be a valid pom");
+        assert_eq!(
+            pom.version().expect("we expected to find the version").to_string(),
+            "1.2.3-SNAPSHOT"
+        );
+        pom.set_version(&Version::new(1, 2, 3))
+            .expect("we expected setting the version to work");
+        assert_eq!(pom.contents(), POM.replace("1.2.3-SNAPSHOT", "1.2.3"));
+        assert_eq!(
+            pom.version().expect("we expected to find the version").to_string(),
+            "1.2.3"
+        );
+    }
+
+    #[test]
+    fn pom_errors() {
+        let inherited = POM.replace("  <version>1.2.3-SNAPSHOT</version>\n", "");
+        PomXml::parse("pom.xml", &inherited).expect_err("we expected an inherited version to be an error");
+        PomXml::parse("pom.xml", "<project><version>1.0.0</project>")
+            .expect_err("we expected an unclosed version to be an error");
+    }
+}
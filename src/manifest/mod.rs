@@ -0,0 +1,417 @@
+//! Reading and rewriting the version number stored in a manifest file, such as
+//! a `Cargo.toml`. Each kind of file we understand has its own handler that
+//! edits the version in place and leaves the rest of the file alone.
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anyhow::{anyhow, Context};
+use semver::Version;
+use toml_edit::{DocumentMut, Item, Value};
+
+pub mod cargo;
+pub mod changelog;
+pub mod exec;
+pub mod file;
+pub mod gradle;
+pub mod helm;
+mod json;
+pub mod keyed;
+pub mod maven;
+pub mod npm;
+pub mod pyproject;
+pub mod replace;
+pub mod ruby;
+pub mod workspace;
+
+pub use cargo::CargoManifest;
+pub use changelog::Changelog;
+pub use exec::ExecManifest;
+pub use file::VersionFile;
+pub use gradle::GradleFile;
+pub use helm::HelmChart;
+pub use keyed::KeyedFile;
+pub use maven::PomXml;
+pub use npm::PackageJson;
+pub use pyproject::PyProject;
+pub use replace::SearchReplace;
+pub use ruby::RubyVersion;
+pub use workspace::Workspace;
+
+/// A file holding a version number we know how to find and replace.
+pub trait Manifest {
+    /// The path this manifest was read from.
+    fn path(&self) -> &Path;
+    /// Parse the version number stored in this manifest.
+    fn version(&self) -> anyhow::Result<Version>;
+    /// Replace the stored version number with the given one, in memory.
+    fn set_version(&mut self, next: &Version) -> anyhow::Result<()>;
+    /// The full contents of the manifest, including any changes made.
+    fn contents(&self) -> String;
+
+    /// Any prefix, such as `v`, stored in front of the version. Manifests that hold only
+    /// bare versions have none.
+    fn prefix(&self) -> String {
+        String::new()
+    }
+
+    /// Write the manifest back to where it was read from.
+    fn write(&self) -> anyhow::Result<()> {
+        write_atomically(self.path(), &self.contents())
+    }
+}
+
+/// The kinds of manifest we have handlers for.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Kind {
+    /// A Rust `Cargo.toml`.
+    Cargo,
+    /// A Node `package.json`.
+    Npm,
+    /// A PHP `composer.json`, which keeps its version in the same top-level field as npm.
+    Composer,
+    /// A Python `pyproject.toml`.
+    Pyproject,
+    /// A Helm `Chart.yaml`.
+    Helm,
+    /// A Maven `pom.xml`.
+    Maven,
+    /// A Gradle `gradle.properties`, `build.gradle`, or `build.gradle.kts`.
+    Gradle,
+    /// A Ruby `*.gemspec` or `version.rb`.
+    Ruby,
+    /// Any file at all, read and written by this program of the user's, as described
+    /// in [`exec`].
+    Exec(String),
+}
+
+/// Every kind of manifest, with the name used to ask for it and how to recognize it.
+const KINDS: [(Kind, &str, &str); 8] = [
+    (Kind::Cargo, "cargo", "Cargo.toml"),
+    (Kind::Npm, "npm", "package.json"),
+    (Kind::Composer, "composer", "composer.json"),
+    (Kind::Pyproject, "pyproject", "pyproject.toml"),
+    (Kind::Helm, "helm", "Chart.yaml"),
+    (Kind::Maven, "maven", "pom.xml"),
+    (
+        Kind::Gradle,
+        "gradle",
+        "gradle.properties, build.gradle, build.gradle.kts",
+    ),
+    (Kind::Ruby, "ruby", "*.gemspec, version.rb"),
+];
+
+impl Kind {
+    /// Tell what kind of manifest a file is from its name.
+    pub fn detect(path: &Path) -> Option<Self> {
+        let name = path.file_name().and_then(|n| n.to_str())?;
+        match name {
+            "Cargo.toml" => Some(Kind::Cargo),
+            "package.json" => Some(Kind::Npm),
+            "composer.json" => Some(Kind::Composer),
+            "pyproject.toml" => Some(Kind::Pyproject),
+            "Chart.yaml" => Some(Kind::Helm),
+            "pom.xml" => Some(Kind::Maven),
+            "gradle.properties" | "build.gradle" | "build.gradle.kts" => Some(Kind::Gradle),
+            "version.rb" => Some(Kind::Ruby),
+            _ if name.ends_with(".gemspec") => Some(Kind::Ruby),
+            _ => None,
+        }
+    }
+
+    /// Read the manifest at the given path with this kind's handler.
+    pub fn open(self, path: &Path) -> anyhow::Result<Box<dyn Manifest>> {
+        Ok(match self {
+            Kind::Cargo => Box::new(CargoManifest::open_following(path)?),
+            Kind::Npm | Kind::Composer => Box::new(PackageJson::open(path)?),
+            Kind::Pyproject => Box::new(PyProject::open(path)?),
+            Kind::Helm => Box::new(HelmChart::open(path)?),
+            Kind::Maven => Box::new(PomXml::open(path)?),
+            Kind::Gradle => Box::new(GradleFile::open(path)?),
+            Kind::Ruby => Box::new(RubyVersion::open(path)?),
+            Kind::Exec(handler) => Box::new(ExecManifest::open(&handler, path)?),
+        })
+    }
+}
+
+impl FromStr for Kind {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        if let Some(handler) = input.strip_prefix("exec:") {
+            return match handler.trim() {
+                "" => Err(anyhow!("`exec:` needs a program to run, as in `exec:./handler`")),
+                handler => Ok(Kind::Exec(handler.to_string())),
+            };
+        }
+        KINDS
+            .iter()
+            .find(|(_, name, _)| *name == input)
+            .map(|(kind, _, _)| kind.clone())
+            .ok_or_else(|| {
+                let names: Vec<&str> = KINDS.iter().map(|(_, name, _)| *name).collect();
+                anyhow!(
+                    "`{input}` is not a manifest type; expected one of {}, or exec:PROGRAM",
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+/// Read a manifest, picking the handler to use from its file name.
+pub fn open(path: impl AsRef<Path>) -> anyhow::Result<Box<dyn Manifest>> {
+    let path = path.as_ref();
+    let kind = Kind::detect(path).ok_or_else(|| {
+        let names: Vec<&str> = KINDS.iter().map(|(_, _, files)| *files).collect();
+        anyhow!(
+            "Unable to tell what kind of manifest {} is; supported manifests are: {}. Pass --manifest-type to say which it is.",
+            path.display(),
+            names.join(", ")
+        )
+    })?;
+    kind.open(path)
+}
+
+/// Read a manifest if we recognize its file name, treating anything else as a plain version file.
+pub fn open_any(path: impl AsRef<Path>) -> anyhow::Result<Box<dyn Manifest>> {
+    let path = path.as_ref();
+    match Kind::detect(path) {
+        Some(kind) => kind.open(path),
+        None => Ok(Box::new(VersionFile::open(path)?)),
+    }
+}
+
+/// Look up the item at the given key path in a toml document.
+pub(crate) fn toml_get<'a>(doc: &'a DocumentMut, keys: &[&str]) -> Option<&'a Item> {
+    keys.iter()
+        .try_fold(doc.as_item(), |item, key| item.get(key))
+        .filter(|item| !item.is_none())
+}
+
+/// Replace the string at the given key path in a toml document, keeping
+/// the whitespace and comments around the old value.
+pub(crate) fn toml_set(doc: &mut DocumentMut, keys: &[&str], value: &str) -> anyhow::Result<()> {
+    let item = keys
+        .iter()
+        .try_fold(doc.as_item_mut(), |item, key| item.get_mut(key))
+        .ok_or_else(|| anyhow!("there is no {} field to update", keys.join(".")))?;
+    let mut replacement = Value::from(value);
+    if let Some(old) = item.as_value() {
+        *replacement.decor_mut() = old.decor().clone();
+    }
+    *item = Item::Value(replacement);
+    Ok(())
+}
+
+/// Read a file's contents, with a helpful message on failure.
+pub(crate) fn read(path: &Path) -> anyhow::Result<String> {
+    fs::read_to_string(path).with_context(|| format!("unable to read {}", path.display()))
+}
+
+/// Write the given contents to a temporary file next to the target, then
+/// rename it over the target, so readers never observe a half-written file.
+pub fn write_atomically(path: &Path, contents: &str) -> anyhow::Result<()> {
+    let tmp = stage(path, contents)?;
+    tmp.persist(path)
+        .with_context(|| format!("unable to replace {}", path.display()))?;
+    Ok(())
+}
+
+/// Write several manifests as a single change. Every new file is written out next to
+/// its target before any of them are renamed into place, and if a rename fails, the
+/// files already replaced are put back as they were, so either every manifest is
+/// updated or none are.
+pub fn write_all<'a>(manifests: impl IntoIterator<Item = &'a dyn Manifest>) -> anyhow::Result<()> {
+    let mut staged = Vec::new();
+    for manifest in manifests {
+        let path = manifest.path();
+        let original = fs::read(path).ok();
+        staged.push((path, original, stage(path, &manifest.contents())?));
+    }
+
+    let mut replaced: Vec<(&Path, Option<Vec<u8>>)> = Vec::new();
+    for (path, original, tmp) in staged {
+        if let Err(e) = tmp.persist(path) {
+            let mut message = format!("unable to replace {}: {}", path.display(), e.error);
+            for (path, original) in replaced.into_iter().rev() {
+                let restored = match original {
+                    Some(bytes) => fs::write(path, bytes),
+                    None => fs::remove_file(path),
+                };
+                if restored.is_err() {
+                    message.push_str(&format!("; unable to restore {}", path.display()));
+                }
+            }
+            return Err(anyhow!(message));
+        }
+        replaced.push((path, original));
+    }
+    Ok(())
+}
+
+/// What files held before a bump wrote them, so the bump can be undone.
+#[derive(Clone, Debug, Default)]
+pub struct Backup {
+    files: Vec<(PathBuf, Option<Vec<u8>>)>,
+}
+
+impl Backup {
+    /// Remember what the file holds now, or that it doesn't exist, unless it's
+    /// already been saved.
+    pub fn save(&mut self, path: &Path) {
+        if !self.files.iter().any(|(saved, _)| saved == path) {
+            self.files.push((path.to_path_buf(), fs::read(path).ok()));
+        }
+    }
+
+    /// Put every saved file back as it was, naming any that couldn't be in the error.
+    pub fn restore(self) -> anyhow::Result<()> {
+        let failed: Vec<String> = self
+            .files
+            .into_iter()
+            .rev()
+            .filter_map(|(path, original)| {
+                let restored = match original {
+                    Some(bytes) => fs::write(&path, bytes),
+                    None => fs::remove_file(&path),
+                };
+                restored.err().map(|_| path.display().to_string())
+            })
+            .collect();
+        if failed.is_empty() {
+            Ok(())
+        } else {
+            Err(anyhow!("unable to restore {}", failed.join(", ")))
+        }
+    }
+}
+
+/// Write the given contents to a temporary file next to the target, with the
+/// target's permissions, ready to be renamed over it.
+fn stage(path: &Path, contents: &str) -> anyhow::Result<tempfile::NamedTempFile> {
+    let dir = match path.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p,
+        _ => Path::new("."),
+    };
+    let mut tmp = tempfile::NamedTempFile::new_in(dir)
+        .with_context(|| format!("unable to create a temporary file in {}", dir.display()))?;
+    tmp.write_all(contents.as_bytes())?;
+    tmp.flush()?;
+    if let Ok(metadata) = fs::metadata(path) {
+        fs::set_permissions(tmp.path(), metadata.permissions())?;
+    }
+    Ok(tmp)
+}
+
+/// A unified diff between the original contents of a manifest and its contents now.
+/// Paths under the current directory are shown relative to it, as git would.
+pub fn diff(original: &str, manifest: &dyn Manifest) -> String {
+    let path = manifest.path();
+    let path = std::env::current_dir()
+        .ok()
+        .and_then(|cwd| path.strip_prefix(cwd).ok())
+        .unwrap_or(path)
+        .display()
+        .to_string();
+    similar::TextDiff::from_lines(original, manifest.contents().as_str())
+        .unified_diff()
+        .header(&format!("a/{path}"), &format!("b/{path}"))
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detecting_kinds() {
+        for (path, kind) in [
+            ("crates/core/Cargo.toml", Some(Kind::Cargo)),
+            ("composer.json", Some(Kind::Composer)),
+            ("example.gemspec", Some(Kind::Ruby)),
+            ("lib/example/version.rb", Some(Kind::Ruby)),
+            ("build.gradle.kts", Some(Kind::Gradle)),
+            ("VERSION", None),
+        ] {
+            assert_eq!(Kind::detect(Path::new(path)), kind);
+        }
+        assert_eq!("maven".parse::<Kind>().expect("we expected a known type"), Kind::Maven);
+        assert_eq!(
+            "exec:./tools/handler --strict"
+                .parse::<Kind>()
+                .expect("we expected a handler"),
+            Kind::Exec("./tools/handler --strict".to_string())
+        );
+        "exec:"
+            .parse::<Kind>()
+            .expect_err("we expected a missing handler to be rejected");
+        "ant"
+            .parse::<Kind>()
+            .expect_err("we expected an unknown type to be rejected");
+    }
+
+    #[test]
+    fn writing_all_or_nothing() {
+        let dir = tempfile::tempdir().expect("we expected to be able to make a temp dir");
+        let text = "[package]\nname = \"example\"\nversion = \"1.2.3\"\n";
+        let good = dir.path().join("Cargo.toml");
+        fs::write(&good, text).expect("we expected to be able to write test data");
+        // Nothing can be renamed over a directory, so the second write must fail.
+        let bad = dir.path().join("blocked");
+        fs::create_dir(&bad).expect("we expected to be able to make a directory");
+
+        let mut first = CargoManifest::parse(&good, text).expect("test data must be a valid manifest");
+        let mut second = CargoManifest::parse(&bad, text).expect("test data must be a valid manifest");
+        for manifest in [&mut first, &mut second] {
+            manifest
+                .set_version(&Version::new(1, 3, 0))
+                .expect("we expected the version to be replaced");
+        }
+        let both: [&dyn Manifest; 2] = [&first, &second];
+        write_all(both).expect_err("we expected the second write to fail");
+        assert_eq!(fs::read_to_string(&good).expect("we expected the file to exist"), text);
+
+        let only: [&dyn Manifest; 1] = [&first];
+        write_all(only).expect("we expected the write to work");
+        assert_eq!(
+            fs::read_to_string(&good).expect("we expected the file to exist"),
+            first.contents()
+        );
+    }
+
+    #[test]
+    fn restoring_backups() {
+        let dir = tempfile::tempdir().expect("we expected to be able to make a temp dir");
+        let existing = dir.path().join("VERSION");
+        let created = dir.path().join("CHANGELOG.md");
+        fs::write(&existing, "1.2.3\n").expect("we expected to be able to write test data");
+        let mut backup = Backup::default();
+        backup.save(&existing);
+        backup.save(&created);
+        fs::write(&existing, "1.3.0\n").expect("we expected to be able to write test data");
+        backup.save(&existing);
+        fs::write(&created, "# Changelog\n").expect("we expected to be able to write test data");
+        backup.restore().expect("we expected the files to be restored");
+        assert_eq!(
+            fs::read_to_string(&existing).expect("we expected the file to exist"),
+            "1.2.3\n"
+        );
+        assert!(!created.exists());
+    }
+
+    #[test]
+    fn diffing() {
+        let text = "[package]\nname = \"example\"\nversion = \"1.2.3\"\n";
+        let mut manifest = CargoManifest::parse("Cargo.toml", text).expect("test data must be a valid manifest");
+        assert_eq!(diff(text, &manifest), "");
+        manifest
+            .set_version(&Version::new(1, 3, 0))
+            .expect("we expected the version to be replaced");
+        assert_eq!(
+            diff(text, &manifest),
+            "--- a/Cargo.toml\n+++ b/Cargo.toml\n@@ -1,3 +1,3 @@\n [package]\n name = \"example\"\n-version = \"1.2.3\"\n+version = \"1.3.0\"\n"
+        );
+    }
+}
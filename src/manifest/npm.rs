@@ -0,0 +1,92 @@
+//! Bumping the `"version"` field in a `package.json`, leaving key order,
+//! indentation, and the trailing newline exactly as they were.
+
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context};
+use semver::Version;
+
+use super::{json, Manifest};
+
+/// A Node package manifest.
+#[derive(Clone, Debug)]
+pub struct PackageJson {
+    path: PathBuf,
+    text: String,
+}
+
+impl PackageJson {
+    /// Read the manifest at the given path.
+    pub fn open(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let text = super::read(&path)?;
+        Self::parse(path, text)
+    }
+
+    /// Wrap manifest text that was read from the given path.
+    pub fn parse(path: impl Into<PathBuf>, text: impl Into<String>) -> anyhow::Result<Self> {
+        let manifest = Self {
+            path: path.into(),
+            text: text.into(),
+        };
+        manifest.version_range()?;
+        Ok(manifest)
+    }
+
+    fn version_range(&self) -> anyhow::Result<Range<usize>> {
+        json::find_string(&self.text, &["version"])
+            .with_context(|| self.path.display().to_string())?
+            .ok_or_else(|| anyhow!("{} does not have a version field", self.path.display()))
+    }
+}
+
+impl Manifest for PackageJson {
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn version(&self) -> anyhow::Result<Version> {
+        let range = self.version_range()?;
+        let value: String = serde_json::from_str(&self.text[range])?;
+        let version = Version::parse(&value)?;
+        Ok(version)
+    }
+
+    fn set_version(&mut self, next: &Version) -> anyhow::Result<()> {
+        let range = self.version_range()?;
+        self.text
+            .replace_range(range, &serde_json::to_string(&next.to_string())?);
+        Ok(())
+    }
+
+    fn contents(&self) -> String {
+        self.text.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MANIFEST: &str = "{\n    \"name\": \"example\",\n    \"version\": \"1.2.3\",\n    \"dependencies\": {\n        \"left-pad\": \"^1.2.3\"\n    }\n}\n";
+
+    #[test]
+    fn package_json_bump() {
+        let mut manifest = PackageJson::parse("package.json", MANIFEST).expect("test data must be valid json");
+        let previous = manifest.version().expect("we expected to find the version");
+        assert_eq!(previous.to_string(), "1.2.3");
+        let next = Version::parse("2.0.0-rc.1").expect("test data must be valid semver");
+        manifest
+            .set_version(&next)
+            .expect("we expected setting the version to work");
+        assert_eq!(manifest.contents(), MANIFEST.replacen("\"1.2.3\"", "\"2.0.0-rc.1\"", 1));
+    }
+
+    #[test]
+    fn package_json_errors() {
+        PackageJson::parse("package.json", "{\"name\": \"x\"}").expect_err("we expected a missing version to fail");
+        PackageJson::parse("package.json", "{\"version\": 1}").expect_err("we expected a numeric version to fail");
+        PackageJson::parse("package.json", "{\"version\": ").expect_err("we expected invalid json to fail");
+    }
+}
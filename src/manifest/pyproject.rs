@@ -0,0 +1,141 @@
+//! Bumping the version in a `pyproject.toml`, in either the PEP 621
+//! `project.version` field or Poetry's `tool.poetry.version` field.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::anyhow;
+use semver::Version;
+use toml_edit::DocumentMut;
+
+use super::Manifest;
+
+/// Where in a `pyproject.toml` the version lives.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Layout {
+    /// The standard PEP 621 `[project]` table.
+    Pep621,
+    /// Poetry's `[tool.poetry]` table.
+    Poetry,
+}
+
+impl Layout {
+    fn keys(&self) -> &'static [&'static str] {
+        match self {
+            Layout::Pep621 => &["project", "version"],
+            Layout::Poetry => &["tool", "poetry", "version"],
+        }
+    }
+}
+
+/// A Python project manifest.
+#[derive(Clone, Debug)]
+pub struct PyProject {
+    path: PathBuf,
+    doc: DocumentMut,
+    layout: Layout,
+}
+
+impl PyProject {
+    /// Read and parse the manifest at the given path.
+    pub fn open(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let text = super::read(&path)?;
+        Self::parse(path, &text)
+    }
+
+    /// Parse manifest text that was read from the given path, detecting which
+    /// layout it uses.
+    pub fn parse(path: impl Into<PathBuf>, text: &str) -> anyhow::Result<Self> {
+        let path = path.into();
+        let doc = text
+            .parse::<DocumentMut>()
+            .map_err(|e| anyhow!("unable to parse {} as toml: {e}", path.display()))?;
+        let layout = if super::toml_get(&doc, Layout::Pep621.keys()).is_some() {
+            Layout::Pep621
+        } else if super::toml_get(&doc, Layout::Poetry.keys()).is_some() {
+            Layout::Poetry
+        } else if is_dynamic(&doc) {
+            return Err(anyhow!(
+                "{} declares its version as dynamic, so it is set by the build backend; bump it wherever the backend reads it from",
+                path.display()
+            ));
+        } else {
+            return Err(anyhow!(
+                "{} has neither a project.version nor a tool.poetry.version field",
+                path.display()
+            ));
+        };
+        Ok(Self { path, doc, layout })
+    }
+
+    /// Which layout this manifest uses.
+    pub fn layout(&self) -> Layout {
+        self.layout
+    }
+}
+
+/// Check whether `project.dynamic` lists the version.
+fn is_dynamic(doc: &DocumentMut) -> bool {
+    super::toml_get(doc, &["project", "dynamic"])
+        .and_then(|item| item.as_array())
+        .is_some_and(|dynamic| dynamic.iter().any(|v| v.as_str() == Some("version")))
+}
+
+impl Manifest for PyProject {
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn version(&self) -> anyhow::Result<Version> {
+        let keys = self.layout.keys();
+        let value = super::toml_get(&self.doc, keys)
+            .and_then(|item| item.as_str())
+            .ok_or_else(|| anyhow!("{} in {} is not a string", keys.join("."), self.path.display()))?;
+        let version = Version::parse(value)?;
+        Ok(version)
+    }
+
+    fn set_version(&mut self, next: &Version) -> anyhow::Result<()> {
+        super::toml_set(&mut self.doc, self.layout.keys(), &next.to_string())
+    }
+
+    fn contents(&self) -> String {
+        self.doc.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pyproject_layouts() {
+        let text = "[project]\nname = \"example\"\nversion = \"0.4.1\" # bump me\n";
+        let mut manifest = PyProject::parse("pyproject.toml", text).expect("test data must be valid");
+        assert_eq!(manifest.layout(), Layout::Pep621);
+        let next = Version::parse("0.5.0").expect("test data must be valid semver");
+        manifest
+            .set_version(&next)
+            .expect("we expected setting the version to work");
+        assert_eq!(manifest.contents(), text.replace("0.4.1", "0.5.0"));
+
+        let text = "[tool.poetry]\nname = \"example\"\nversion = \"1.0.0\"\n";
+        let manifest = PyProject::parse("pyproject.toml", text).expect("test data must be valid");
+        assert_eq!(manifest.layout(), Layout::Poetry);
+        assert_eq!(manifest.version().expect("we expected a version").to_string(), "1.0.0");
+
+        // Poetry 2 projects may mark the version dynamic and keep it in tool.poetry.
+        let text = "[project]\ndynamic = [\"version\"]\n[tool.poetry]\nversion = \"1.0.0\"\n";
+        let manifest = PyProject::parse("pyproject.toml", text).expect("test data must be valid");
+        assert_eq!(manifest.layout(), Layout::Poetry);
+    }
+
+    #[test]
+    fn pyproject_errors() {
+        let text = "[project]\nname = \"example\"\ndynamic = [\"version\"]\n";
+        let err = PyProject::parse("pyproject.toml", text).expect_err("we expected a dynamic version to fail");
+        assert!(err.to_string().contains("dynamic"));
+        PyProject::parse("pyproject.toml", "[project]\nname = \"x\"\n")
+            .expect_err("we expected a missing version to fail");
+    }
+}
@@ -0,0 +1,169 @@
+//! Keeping version numbers embedded in other files in sync, the way bumpversion
+//! does: a search string such as `semver-bump {version}` finds the version in
+//! the file, and every occurrence of it is replaced when the version changes.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::anyhow;
+use semver::Version;
+
+use super::Manifest;
+
+/// The placeholder that stands for the version in search and replace strings.
+const PLACEHOLDER: &str = "{version}";
+
+/// A file of any kind with a version embedded in it, found by a search string.
+#[derive(Clone, Debug)]
+pub struct SearchReplace {
+    path: PathBuf,
+    contents: String,
+    search: (String, String),
+    replace: String,
+}
+
+impl SearchReplace {
+    /// Read the file at the given path. The search string must contain `{version}`
+    /// exactly once. The replacement is written in place of each match, and defaults
+    /// to the search string.
+    pub fn open(path: impl AsRef<Path>, search: &str, replace: Option<&str>) -> anyhow::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let contents = super::read(&path)?;
+        Self::parse(path, &contents, search, replace)
+    }
+
+    /// Use file contents that were read from the given path.
+    pub fn parse(
+        path: impl Into<PathBuf>,
+        contents: &str,
+        search: &str,
+        replace: Option<&str>,
+    ) -> anyhow::Result<Self> {
+        let (before, after) = search
+            .split_once(PLACEHOLDER)
+            .filter(|(before, after)| !before.contains(PLACEHOLDER) && !after.contains(PLACEHOLDER))
+            .ok_or_else(|| anyhow!("The search string `{search}` must contain {PLACEHOLDER} exactly once."))?;
+        let replace = replace.unwrap_or(search);
+        if !replace.contains(PLACEHOLDER) {
+            return Err(anyhow!("The replacement `{replace}` does not contain {PLACEHOLDER}."));
+        }
+        Ok(Self {
+            path: path.into(),
+            contents: contents.to_string(),
+            search: (before.to_string(), after.to_string()),
+            replace: replace.to_string(),
+        })
+    }
+
+    /// Every version the search string matches in the file, in order.
+    fn matches(&self) -> Vec<Version> {
+        let (before, after) = &self.search;
+        let mut found = Vec::new();
+        let mut rest = self.contents.as_str();
+        while let Some(start) = rest.find(before.as_str()) {
+            rest = &rest[start + before.len()..];
+            let run = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '+')))
+                .unwrap_or(rest.len());
+            // The version is the longest valid one that the rest of the search string follows.
+            let version = (1..=run)
+                .rev()
+                .filter(|end| rest[*end..].starts_with(after.as_str()))
+                .find_map(|end| Version::parse(&rest[..end]).ok());
+            found.extend(version);
+            // With nothing before the placeholder, every position matches, so step past this one.
+            if before.is_empty() {
+                match rest.chars().next() {
+                    None => break,
+                    Some(c) => rest = &rest[run.max(c.len_utf8())..],
+                }
+            }
+        }
+        found
+    }
+
+    fn render(template: &str, version: &Version) -> String {
+        template.replace(PLACEHOLDER, &version.to_string())
+    }
+}
+
+impl Manifest for SearchReplace {
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The highest version the search string matches, since a file may also mention
+    /// older releases.
+    fn version(&self) -> anyhow::Result<Version> {
+        self.matches()
+            .into_iter()
+            .max_by(|a, b| a.cmp_precedence(b))
+            .ok_or_else(|| {
+                anyhow!(
+                    "{} does not contain anything matching `{}{PLACEHOLDER}{}`",
+                    self.path.display(),
+                    self.search.0,
+                    self.search.1
+                )
+            })
+    }
+
+    /// Replace every match of the search string for the current version.
+    fn set_version(&mut self, next: &Version) -> anyhow::Result<()> {
+        let previous = self.version()?;
+        let search = format!("{}{previous}{}", self.search.0, self.search.1);
+        self.contents = self.contents.replace(&search, &Self::render(&self.replace, next));
+        Ok(())
+    }
+
+    fn contents(&self) -> String {
+        self.contents.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const README: &str = "Install it with `cargo install semver-bump@1.2.3`.\n\nNew in semver-bump@1.1.0: tags.\nUse `semver-bump@1.2.3` in CI.\n";
+
+    #[test]
+    fn replacing_embedded_versions() {
+        let mut file = SearchReplace::parse("README.md", README, "semver-bump@{version}", None)
+            .expect("we expected the search string to be accepted");
+        assert_eq!(
+            file.version().expect("we expected to find the version").to_string(),
+            "1.2.3"
+        );
+        file.set_version(&Version::new(1, 3, 0))
+            .expect("we expected setting the version to work");
+        assert_eq!(file.contents(), README.replace("1.2.3", "1.3.0"));
+
+        let mut file = SearchReplace::parse(
+            "Dockerfile",
+            "FROM app:1.2.3-rc.1 AS base\n",
+            ":{version} ",
+            Some("@{version} "),
+        )
+        .expect("we expected the search string to be accepted");
+        file.set_version(&Version::new(1, 2, 3))
+            .expect("we expected setting the version to work");
+        assert_eq!(file.contents(), "FROM app@1.2.3 AS base\n");
+    }
+
+    #[test]
+    fn search_errors() {
+        SearchReplace::parse("README.md", README, "semver-bump", None)
+            .expect_err("we expected a search string without a placeholder to be rejected");
+        SearchReplace::parse("README.md", README, "{version} {version}", None)
+            .expect_err("we expected two placeholders to be rejected");
+        let file = SearchReplace::parse("README.md", README, "version = {version}", None)
+            .expect("we expected the search string to be accepted");
+        file.version().expect_err("we expected no match to be an error");
+        let file = SearchReplace::parse("VERSION", "é 1.2.3\n", "{version}", None)
+            .expect("we expected the search string to be accepted");
+        assert_eq!(
+            file.version().expect("we expected to find the version").to_string(),
+            "1.2.3"
+        );
+    }
+}
@@ -0,0 +1,124 @@
+//! Bumping the version of a Ruby gem, either in its `*.gemspec`, as in
+//! `spec.version = "1.2.3"`, or in the `lib/**/version.rb` file many gems keep
+//! it in instead, as in `VERSION = "1.2.3".freeze`.
+
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context};
+use semver::Version;
+
+use super::Manifest;
+
+/// A Ruby file that sets a gem's version to a string literal.
+#[derive(Clone, Debug)]
+pub struct RubyVersion {
+    path: PathBuf,
+    contents: String,
+    span: Range<usize>,
+}
+
+impl RubyVersion {
+    /// Read the file at the given path.
+    pub fn open(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let text = super::read(&path)?;
+        Self::parse(path, &text)
+    }
+
+    /// Parse a file that was read from the given path, finding the first line that
+    /// assigns a string to `VERSION` or to the spec's `version`.
+    pub fn parse(path: impl Into<PathBuf>, text: &str) -> anyhow::Result<Self> {
+        let path = path.into();
+        let span = find_version(text)
+            .ok_or_else(|| anyhow!("{} does not set VERSION or spec.version to a string", path.display()))?;
+        Ok(Self {
+            path,
+            contents: text.to_string(),
+            span,
+        })
+    }
+}
+
+impl Manifest for RubyVersion {
+    fn path(&self) -> &Path {
+        &self.path
+    }
+
+    fn version(&self) -> anyhow::Result<Version> {
+        let text = &self.contents[self.span.clone()];
+        Version::parse(text).with_context(|| format!("the version in {} is `{text}`", self.path.display()))
+    }
+
+    fn set_version(&mut self, next: &Version) -> anyhow::Result<()> {
+        let next = next.to_string();
+        self.contents.replace_range(self.span.clone(), &next);
+        self.span = self.span.start..self.span.start + next.len();
+        Ok(())
+    }
+
+    fn contents(&self) -> String {
+        self.contents.clone()
+    }
+}
+
+/// Whether the left side of an assignment names the version: the `VERSION`
+/// constant, or the `version` attribute of a gem specification.
+fn is_version_target(target: &str) -> bool {
+    target == "VERSION"
+        || target
+            .rsplit_once('.')
+            .is_some_and(|(receiver, attribute)| attribute == "version" && !receiver.is_empty())
+}
+
+/// Find the first line assigning a string literal to the version, returning where
+/// the string's contents sit.
+fn find_version(text: &str) -> Option<Range<usize>> {
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let span = line.split_once('=').and_then(|(target, value)| {
+            if !is_version_target(target.trim()) || value.starts_with(['=', '~']) {
+                return None;
+            }
+            let value = value.trim_start();
+            let quote = value.chars().next().filter(|c| matches!(c, '"' | '\''))?;
+            let inner = &value[1..];
+            let close = inner.find(quote)?;
+            let start = offset + line.len() - inner.len();
+            Some(start..start + close)
+        });
+        if span.is_some() {
+            return span;
+        }
+        offset += line.len();
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bumping_ruby_versions() {
+        let next = Version::new(1, 3, 0);
+        for text in [
+            "Gem::Specification.new do |spec|\n  spec.name = \"example\"\n  spec.version = \"1.2.3\"\nend\n",
+            "module Example\n  VERSION = '1.2.3'.freeze\nend\n",
+        ] {
+            let mut file = RubyVersion::parse("example.gemspec", text).expect("test data must have a version");
+            assert_eq!(
+                file.version().expect("we expected to find the version").to_string(),
+                "1.2.3"
+            );
+            file.set_version(&next)
+                .expect("we expected setting the version to work");
+            assert_eq!(file.contents(), text.replace("1.2.3", "1.3.0"));
+        }
+        RubyVersion::parse(
+            "example.gemspec",
+            "spec.required_ruby_version = \">= 3.0\"\nspec.version = Example::VERSION\n",
+        )
+        .expect_err("we expected a version read from elsewhere to be an error");
+    }
+}
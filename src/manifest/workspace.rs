@@ -0,0 +1,283 @@
+//! Bumping every package in a cargo workspace at once: the shared
+//! `workspace.package.version`, each member's own version, and the requirements
+//! members place on each other.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::anyhow;
+use semver::Version;
+
+use super::{CargoManifest, Manifest};
+use crate::{bump_with, BumpKind, Options};
+
+/// A cargo workspace: its root manifest and the manifests of its members.
+#[derive(Clone, Debug)]
+pub struct Workspace {
+    root: CargoManifest,
+    members: Vec<CargoManifest>,
+}
+
+/// A package whose version changed in a workspace bump.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Change {
+    /// The name of the package.
+    pub name: String,
+    /// The version it had before.
+    pub previous: Version,
+    /// The version it has now.
+    pub next: Version,
+}
+
+impl Workspace {
+    /// Read the workspace whose root manifest is at the given path, along with all of its members.
+    pub fn open(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let root = CargoManifest::open(path)?;
+        let (patterns, exclude) = root
+            .workspace_members()
+            .ok_or_else(|| anyhow!("{} does not have a [workspace] section", path.display()))?;
+        let dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        let exclude: Vec<PathBuf> = exclude.iter().map(|e| dir.join(e)).collect();
+
+        let mut members = Vec::new();
+        for pattern in &patterns {
+            for member in expand(&dir, pattern) {
+                if exclude.contains(&member) || member == dir {
+                    continue;
+                }
+                let manifest = member.join("Cargo.toml");
+                if manifest.is_file() {
+                    members.push(CargoManifest::open(manifest)?);
+                }
+            }
+        }
+        Ok(Self { root, members })
+    }
+
+    /// Find the workspace containing the package whose manifest is at the given path, by
+    /// looking for a `Cargo.toml` with a `[workspace]` section in its parent directories.
+    pub fn discover(member: impl AsRef<Path>) -> anyhow::Result<Option<Self>> {
+        let member = std::fs::canonicalize(member.as_ref())?;
+        for dir in member.ancestors().skip(1) {
+            let candidate = dir.join("Cargo.toml");
+            if !candidate.is_file() {
+                continue;
+            }
+            if CargoManifest::open(&candidate)?.workspace_members().is_some() {
+                let workspace = Self::open(&candidate)?;
+                let found = workspace
+                    .manifests()
+                    .any(|m| std::fs::canonicalize(m.path()).ok().as_deref() == Some(member.as_path()));
+                return Ok(found.then_some(workspace));
+            }
+        }
+        Ok(None)
+    }
+
+    /// The root manifest of the workspace.
+    pub fn root(&self) -> &CargoManifest {
+        &self.root
+    }
+
+    /// Every manifest in the workspace, starting with the root.
+    pub fn manifests(&self) -> impl Iterator<Item = &CargoManifest> {
+        std::iter::once(&self.root).chain(self.members.iter())
+    }
+
+    fn manifests_mut(&mut self) -> impl Iterator<Item = &mut CargoManifest> {
+        std::iter::once(&mut self.root).chain(self.members.iter_mut())
+    }
+
+    /// Rewrite the requirements every manifest in the workspace places on the named
+    /// package so they accept its new version, returning the manifests that changed.
+    pub fn update_dependents(&mut self, name: &str, next: &Version) -> anyhow::Result<Vec<&CargoManifest>> {
+        let mut changed = Vec::new();
+        for manifest in self.manifests_mut() {
+            if manifest.update_dependency(name, next)? {
+                changed.push(&*manifest);
+            }
+        }
+        Ok(changed)
+    }
+
+    /// Bump the shared workspace version if there is one, and the version of every
+    /// package that doesn't inherit it, then rewrite the requirements members place on
+    /// each other to match. Returns the packages that changed, in workspace order.
+    pub fn bump(&mut self, kind: &BumpKind, options: &Options) -> anyhow::Result<Vec<Change>> {
+        let shared = match self.root.workspace_version()? {
+            Some(previous) => {
+                let next = bump_with(&previous, kind, options)?;
+                self.root.set_workspace_version(&next)?;
+                Some((previous, next))
+            }
+            None => None,
+        };
+
+        let mut changes = Vec::new();
+        for manifest in self.manifests_mut() {
+            if !manifest.is_package() {
+                continue;
+            }
+            let name = manifest
+                .name()
+                .ok_or_else(|| anyhow!("{} does not have a package.name field", manifest.path().display()))?
+                .to_string();
+            let (previous, next) = if manifest.inherits_version() {
+                shared.clone().ok_or_else(|| {
+                    anyhow!(
+                        "{} inherits its version from the workspace, but there is no workspace.package.version",
+                        manifest.path().display()
+                    )
+                })?
+            } else {
+                let previous = manifest.version()?;
+                let next = bump_with(&previous, kind, options)?;
+                manifest.set_version(&next)?;
+                (previous, next)
+            };
+            changes.push(Change { name, previous, next });
+        }
+
+        let versions: BTreeMap<&str, &Version> = changes.iter().map(|c| (c.name.as_str(), &c.next)).collect();
+        for manifest in self.manifests_mut() {
+            for (name, next) in &versions {
+                manifest.update_dependency(name, next)?;
+            }
+        }
+        Ok(changes)
+    }
+}
+
+/// Expand a workspace member pattern into the directories it names. Any path
+/// component may contain a single `*` wildcard, as in `crates/*` or `tools/cli-*`.
+fn expand(dir: &Path, pattern: &str) -> Vec<PathBuf> {
+    let mut found = vec![dir.to_path_buf()];
+    for component in pattern.split('/').filter(|c| !c.is_empty() && *c != ".") {
+        let mut next = Vec::new();
+        for parent in &found {
+            match component.split_once('*') {
+                None => next.push(parent.join(component)),
+                Some((prefix, suffix)) => {
+                    let listing = if parent.as_os_str().is_empty() {
+                        Path::new(".")
+                    } else {
+                        parent.as_path()
+                    };
+                    let Ok(entries) = std::fs::read_dir(listing) else {
+                        continue;
+                    };
+                    let mut matches: Vec<PathBuf> = entries
+                        .filter_map(Result::ok)
+                        .filter(|e| e.path().is_dir())
+                        .filter(|e| {
+                            let name = e.file_name();
+                            let name = name.to_string_lossy();
+                            name.len() >= prefix.len() + suffix.len()
+                                && name.starts_with(prefix)
+                                && name.ends_with(suffix)
+                        })
+                        .map(|e| parent.join(e.file_name()))
+                        .collect();
+                    matches.sort();
+                    next.extend(matches);
+                }
+            }
+        }
+        found = next;
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    /// Lay out a workspace with a shared version, a member that inherits it, a member
+    /// with its own version, and requirements between them.
+    fn scratch_workspace() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().expect("we expected to be able to make a temp dir");
+        let write = |path: &str, text: &str| {
+            let path = dir.path().join(path);
+            fs::create_dir_all(path.parent().expect("test paths have parents"))
+                .expect("we expected to be able to make directories");
+            fs::write(path, text).expect("we expected to be able to write test data");
+        };
+        write(
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"crates/*\"]\nexclude = [\"crates/scratch\"]\n\n[workspace.package]\nversion = \"1.2.3\"\n\n[workspace.dependencies]\ncore = { path = \"crates/core\", version = \"1.2.3\" }\n",
+        );
+        write(
+            "crates/core/Cargo.toml",
+            "[package]\nname = \"core\"\nversion.workspace = true\n",
+        );
+        write(
+            "crates/cli/Cargo.toml",
+            "[package]\nname = \"cli\"\nversion = \"0.4.0\"\n\n[dependencies]\ncore = { path = \"../core\", version = \"^1.2\" }\n",
+        );
+        write("crates/scratch/Cargo.toml", "[package]\nname = \"scratch\"\n");
+        dir
+    }
+
+    #[test]
+    fn bumping_a_workspace() {
+        let dir = scratch_workspace();
+        let mut workspace = Workspace::open(dir.path().join("Cargo.toml")).expect("we expected the workspace to load");
+        assert_eq!(workspace.manifests().count(), 3);
+        let changes = workspace
+            .bump(&BumpKind::Minor, &Options::default())
+            .expect("we expected the bump to work");
+        let summary: Vec<String> = changes
+            .iter()
+            .map(|c| format!("{} {} {}", c.name, c.previous, c.next))
+            .collect();
+        assert_eq!(summary, vec!["cli 0.4.0 0.5.0", "core 1.2.3 1.3.0"]);
+        let contents: Vec<String> = workspace.manifests().map(|m| m.contents()).collect();
+        assert!(contents[0].contains("version = \"1.3.0\"\n\n[workspace.dependencies]"));
+        assert!(contents[0].contains("version = \"1.3.0\" }"));
+        assert!(contents[1].contains("version = \"^1.3\""));
+    }
+
+    #[test]
+    fn discovering_a_workspace() {
+        let dir = scratch_workspace();
+        let found =
+            Workspace::discover(dir.path().join("crates/cli/Cargo.toml")).expect("we expected discovery to work");
+        assert!(found.is_some());
+        let found =
+            Workspace::discover(dir.path().join("crates/scratch/Cargo.toml")).expect("we expected discovery to work");
+        assert!(found.is_none());
+
+        let mut workspace = Workspace::discover(dir.path().join("crates/core/Cargo.toml"))
+            .expect("we expected discovery to work")
+            .expect("we expected to find the workspace");
+        let next = Version::parse("2.0.0").expect("test data must be valid semver");
+        let changed = workspace
+            .update_dependents("core", &next)
+            .expect("we expected the update to work");
+        assert_eq!(changed.len(), 2);
+        assert!(changed[1].path().ends_with("crates/cli/Cargo.toml"));
+    }
+
+    #[test]
+    fn following_inherited_versions() {
+        let dir = scratch_workspace();
+        let mut manifest = CargoManifest::open_following(dir.path().join("crates/core/Cargo.toml"))
+            .expect("we expected to find the workspace");
+        assert_eq!(manifest.path(), dir.path().join("Cargo.toml"));
+        assert_eq!(
+            manifest.version().expect("we expected to find the version").to_string(),
+            "1.2.3"
+        );
+        manifest
+            .set_version(&Version::new(1, 3, 0))
+            .expect("we expected setting the version to work");
+        assert!(manifest.contents().contains("[workspace.package]\nversion = \"1.3.0\""));
+
+        let manifest = CargoManifest::open_following(dir.path().join("crates/cli/Cargo.toml"))
+            .expect("we expected the manifest to load");
+        assert!(manifest.path().ends_with("crates/cli/Cargo.toml"));
+    }
+}
@@ -0,0 +1,206 @@
+//! Asking someone at a terminal to pick one of a list of choices. On a terminal the
+//! choices are a menu moved through with the arrow keys (or `j` and `k`) and picked
+//! with enter; `q`, escape, or ctrl-c picks nothing. When stdin or stderr isn't a
+//! terminal, the choices are numbered and the answer is read as a line.
+//!
+//! Everything is drawn on stderr, so stdout is left for the result.
+
+use std::fs::File;
+use std::io::{self, BufRead, IsTerminal, Read, Write};
+use std::process::{Command, Stdio};
+
+use anyhow::{anyhow, Context};
+
+/// A keypress that means something to the menu.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Key {
+    Up,
+    Down,
+    Enter,
+    Cancel,
+    /// A digit, choosing that entry directly.
+    Number(usize),
+}
+
+impl Key {
+    /// Read the key from the bytes a single keypress sent, if it's one we know.
+    fn parse(bytes: &[u8]) -> Option<Key> {
+        match bytes {
+            b"\x1b[A" | b"\x1bOA" | b"k" => Some(Key::Up),
+            b"\x1b[B" | b"\x1bOB" | b"j" => Some(Key::Down),
+            b"\r" | b"\n" => Some(Key::Enter),
+            b"\x1b" | b"q" | b"\x03" | b"\x04" => Some(Key::Cancel),
+            [digit @ b'1'..=b'9'] => Some(Key::Number((digit - b'1') as usize)),
+            _ => None,
+        }
+    }
+}
+
+/// Ask for one of the choices, returning its index, or `None` if nothing was picked.
+pub fn pick(prompt: &str, choices: &[String]) -> anyhow::Result<Option<usize>> {
+    if choices.is_empty() {
+        return Err(anyhow!("There is nothing to choose from."));
+    }
+    if cfg!(unix) && io::stdin().is_terminal() && io::stderr().is_terminal() {
+        let _raw = RawMode::enable()?;
+        select(io::stdin().lock(), io::stderr().lock(), prompt, choices)
+    } else {
+        numbered(io::stdin().lock(), io::stderr().lock(), prompt, choices)
+    }
+}
+
+/// Draw the menu and move through it as keys are pressed.
+fn select(
+    mut input: impl Read,
+    mut output: impl Write,
+    prompt: &str,
+    choices: &[String],
+) -> anyhow::Result<Option<usize>> {
+    let mut selected = 0;
+    writeln!(output, "{prompt}")?;
+    draw(&mut output, choices, selected)?;
+    let mut buffer = [0; 8];
+    loop {
+        let read = input.read(&mut buffer)?;
+        if read == 0 {
+            return Ok(None);
+        }
+        match Key::parse(&buffer[..read]) {
+            Some(Key::Up) => selected = selected.checked_sub(1).unwrap_or(choices.len() - 1),
+            Some(Key::Down) => selected = (selected + 1) % choices.len(),
+            Some(Key::Number(n)) if n < choices.len() => selected = n,
+            Some(Key::Enter) => return Ok(Some(selected)),
+            Some(Key::Cancel) => return Ok(None),
+            _ => continue,
+        }
+        write!(output, "\x1b[{}A", choices.len())?;
+        draw(&mut output, choices, selected)?;
+    }
+}
+
+fn draw(output: &mut impl Write, choices: &[String], selected: usize) -> io::Result<()> {
+    for (i, choice) in choices.iter().enumerate() {
+        let marker = if i == selected { ">" } else { " " };
+        writeln!(output, "\r\x1b[2K{marker} {choice}")?;
+    }
+    output.flush()
+}
+
+/// List the choices by number and read which one was picked; an empty answer picks nothing.
+fn numbered(
+    mut input: impl BufRead,
+    mut output: impl Write,
+    prompt: &str,
+    choices: &[String],
+) -> anyhow::Result<Option<usize>> {
+    writeln!(output, "{prompt}")?;
+    for (i, choice) in choices.iter().enumerate() {
+        writeln!(output, "{:>3}) {choice}", i + 1)?;
+    }
+    loop {
+        write!(output, "Number (enter for none): ")?;
+        output.flush()?;
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let answer = line.trim();
+        if answer.is_empty() {
+            return Ok(None);
+        }
+        match answer.parse::<usize>() {
+            Ok(n) if (1..=choices.len()).contains(&n) => return Ok(Some(n - 1)),
+            _ => writeln!(output, "Pick a number from 1 to {}.", choices.len())?,
+        }
+    }
+}
+
+/// Turns off line buffering and echo on the terminal, putting them back when dropped.
+struct RawMode {
+    saved: String,
+}
+
+impl RawMode {
+    fn enable() -> anyhow::Result<Self> {
+        let saved = stty(&["-g"])?;
+        stty(&["-icanon", "-echo", "-isig", "min", "1"])?;
+        Ok(Self {
+            saved: saved.trim().to_string(),
+        })
+    }
+}
+
+impl Drop for RawMode {
+    fn drop(&mut self) {
+        // There's nothing more to do if the terminal can't be put back.
+        let _ = stty(&[&self.saved]);
+    }
+}
+
+fn stty(args: &[&str]) -> anyhow::Result<String> {
+    let output = Command::new("stty")
+        .args(args)
+        .stdin(File::open("/dev/tty")?)
+        .stderr(Stdio::inherit())
+        .output()
+        .context("unable to run stty to read keypresses")?;
+    if !output.status.success() {
+        return Err(anyhow!("stty {} failed ({})", args.join(" "), output.status));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn choices() -> Vec<String> {
+        ["2.0.0 major", "1.3.0 minor", "1.2.4 patch"].map(String::from).to_vec()
+    }
+
+    #[test]
+    fn picking_with_keys() {
+        let mut output = Vec::new();
+        let picked = select(&b"\x1b[B"[..], &mut output, "Pick one:", &choices()).expect("we expected a menu");
+        assert_eq!(picked, None, "running out of input picks nothing");
+        let drawn = String::from_utf8_lossy(&output);
+        assert!(drawn.starts_with("Pick one:\n\r\x1b[2K> 2.0.0 major\n"));
+        assert!(drawn.ends_with("\x1b[3A\r\x1b[2K  2.0.0 major\n\r\x1b[2K> 1.3.0 minor\n\r\x1b[2K  1.2.4 patch\n"));
+
+        // Each read is one keypress, as it is from a terminal.
+        struct Keys(Vec<&'static [u8]>);
+        impl Read for Keys {
+            fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+                if self.0.is_empty() {
+                    return Ok(0);
+                }
+                let key = self.0.remove(0);
+                buffer[..key.len()].copy_from_slice(key);
+                Ok(key.len())
+            }
+        }
+        let pick_with = |keys: Vec<&'static [u8]>| {
+            select(Keys(keys), io::sink(), "Pick one:", &choices()).expect("we expected a menu")
+        };
+        assert_eq!(pick_with(vec![b"\x1b[B", b"j", b"\r"]), Some(2));
+        assert_eq!(pick_with(vec![b"\x1b[A", b"x", b"\n"]), Some(2));
+        assert_eq!(pick_with(vec![b"3", b"k", b"\r"]), Some(1));
+        assert_eq!(pick_with(vec![b"9", b"\r"]), Some(0));
+        assert_eq!(pick_with(vec![b"j", b"q"]), None);
+        assert_eq!(pick_with(vec![b"\x03"]), None);
+    }
+
+    #[test]
+    fn picking_by_number() {
+        let mut output = Vec::new();
+        let picked = numbered(&b"7\nx\n2\n"[..], &mut output, "Pick one:", &choices()).expect("we expected a list");
+        assert_eq!(picked, Some(1));
+        let listed = String::from_utf8_lossy(&output);
+        assert!(listed.starts_with("Pick one:\n  1) 2.0.0 major\n  2) 1.3.0 minor\n  3) 1.2.4 patch\n"));
+        assert_eq!(listed.matches("Pick a number from 1 to 3.").count(), 2);
+        assert_eq!(
+            numbered(&b"\n"[..], io::sink(), "Pick one:", &choices()).expect("we expected a list"),
+            None
+        );
+    }
+}
@@ -0,0 +1,126 @@
+//! Release notes in Markdown, made from the subjects of the commits in a release
+//! and grouped by their [conventional commits](https://www.conventionalcommits.org/)
+//! type, so features and fixes are easy to find.
+
+use crate::conventional::{has_breaking_footer, parse_header};
+
+/// The placeholders a release notes template may use.
+pub const PLACEHOLDERS: [&str; 6] = ["prev", "next", "notes", "from", "to", "date"];
+
+/// The sections notes are grouped into, in order, with the commit types that go in each.
+/// Commits of any other type, and commits that don't follow the convention, go under
+/// "Other changes" at the end.
+const SECTIONS: [(&str, &[&str]); 4] = [
+    ("Features", &["feat"]),
+    ("Bug fixes", &["fix"]),
+    ("Performance", &["perf"]),
+    ("Documentation", &["docs"]),
+];
+
+/// One line of the notes: a commit subject without its type, and its scope if it had one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Entry {
+    scope: Option<String>,
+    description: String,
+}
+
+impl Entry {
+    fn render(&self) -> String {
+        match self.scope.as_ref() {
+            Some(scope) => format!("- **{scope}:** {}\n", self.description),
+            None => format!("- {}\n", self.description),
+        }
+    }
+}
+
+/// Render the subjects of these commit messages as Markdown, one `###` section per
+/// group, leaving out empty groups. Breaking changes come first, in a section of their
+/// own. Merge commits are skipped.
+pub fn render<S: AsRef<str>>(messages: &[S]) -> String {
+    let mut breaking = Vec::new();
+    let mut grouped: Vec<Vec<Entry>> = vec![Vec::new(); SECTIONS.len() + 1];
+    for message in messages {
+        let message = message.as_ref();
+        let subject = message.lines().next().unwrap_or_default().trim();
+        if subject.is_empty() || subject.starts_with("Merge ") {
+            continue;
+        }
+        let Some(header) = parse_header(message) else {
+            grouped[SECTIONS.len()].push(Entry {
+                scope: None,
+                description: subject.to_string(),
+            });
+            continue;
+        };
+        let description = subject
+            .split_once(':')
+            .map(|(_, d)| d.trim())
+            .unwrap_or(subject)
+            .to_string();
+        let entry = Entry {
+            scope: header.scope,
+            description,
+        };
+        if header.breaking || has_breaking_footer(message) {
+            breaking.push(entry);
+            continue;
+        }
+        let group = SECTIONS
+            .iter()
+            .position(|(_, kinds)| kinds.contains(&header.kind.as_str()))
+            .unwrap_or(SECTIONS.len());
+        grouped[group].push(entry);
+    }
+
+    let titles = std::iter::once("Breaking changes")
+        .chain(SECTIONS.iter().map(|(title, _)| *title))
+        .chain(std::iter::once("Other changes"));
+    let groups = std::iter::once(breaking).chain(grouped);
+    let sections: Vec<String> = titles
+        .zip(groups)
+        .filter(|(_, entries)| !entries.is_empty())
+        .map(|(title, entries)| {
+            let lines: String = entries.iter().map(Entry::render).collect();
+            format!("### {title}\n\n{lines}")
+        })
+        .collect();
+    sections.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rendering_notes() {
+        let messages = [
+            "feat(cli): add --sign",
+            "fix: handle empty files\n\nWith a body.",
+            "Merge pull request #12 from someone/branch",
+            "refactor!: drop the old config format",
+            "chore: update dependencies",
+            "Tidy up the README",
+            "feat: add notes",
+        ];
+        let expected = "### Breaking changes
+
+- drop the old config format
+
+### Features
+
+- **cli:** add --sign
+- add notes
+
+### Bug fixes
+
+- handle empty files
+
+### Other changes
+
+- update dependencies
+- Tidy up the README
+";
+        assert_eq!(render(&messages), expected);
+        assert_eq!(render::<&str>(&[]), "");
+    }
+}
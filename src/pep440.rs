@@ -0,0 +1,237 @@
+//! Translating Python's [PEP 440](https://peps.python.org/pep-0440/) versions into
+//! semver, where there's a faithful enough equivalent:
+//!
+//! - The release numbers become major.minor.patch, padded with zeros, so `1.4`
+//!   is `1.4.0`. Versions with more than three numbers have no equivalent.
+//! - Pre-releases become `alpha.N`, `beta.N`, or `rc.N`, spelled however PEP 440
+//!   allows: `1.4a1`, `1.4.0-alpha.1`, and `1.4alpha1` are all `1.4.0-alpha.1`,
+//!   and `c`, `pre`, and `preview` are `rc`.
+//! - Development releases become a `dev.N` pre-release, after any other
+//!   pre-release, so `1.4.dev2` is `1.4.0-dev.2` and `1.4b1.dev2` is
+//!   `1.4.0-beta.1.dev.2`. They sort before the release, but not always before its
+//!   alphas, as they would in Python.
+//! - Post-releases become `post.N` build metadata, so `1.4.post1` is
+//!   `1.4.0+post.1`, with the same precedence as the release it follows.
+//! - Local versions go in the build metadata too, so `1.4+ubuntu-1` is
+//!   `1.4.0+ubuntu.1`.
+//! - A zero epoch is dropped. Any other epoch has no equivalent, since it reorders
+//!   versions in a way semver can't express.
+//!
+//! [`from_semver`] goes the other way, for the same subset.
+
+use semver::{BuildMetadata, Prerelease, Version};
+
+use crate::error::Error;
+
+const SEPARATORS: [char; 3] = ['.', '-', '_'];
+
+/// The semver equivalent of a PEP 440 version, or `None` if there isn't one.
+pub fn to_semver(input: &str) -> Option<Version> {
+    let input = input.trim().to_ascii_lowercase();
+    let input = input.strip_prefix('v').unwrap_or(&input);
+    let (public, local) = match input.split_once('+') {
+        Some((public, local)) => (public, Some(local)),
+        None => (input, None),
+    };
+    let public = match public.split_once('!') {
+        Some((epoch, rest)) if epoch.parse::<u64>().ok()? == 0 => rest,
+        Some(_) => return None,
+        None => public,
+    };
+
+    let release_end = public
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(public.len());
+    let (release, mut rest) = public.split_at(release_end);
+    let numbers: Vec<u64> = release
+        .trim_end_matches('.')
+        .split('.')
+        .map(|n| n.parse().ok())
+        .collect::<Option<_>>()?;
+    if numbers.len() > 3 {
+        return None;
+    }
+
+    let mut pre: Vec<String> = Vec::new();
+    let mut build: Vec<String> = Vec::new();
+    loop {
+        rest = rest.trim_start_matches(SEPARATORS);
+        if rest.is_empty() {
+            break;
+        }
+        let word_end = rest.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(rest.len());
+        let (word, after) = rest.split_at(word_end);
+        let after = after.trim_start_matches(SEPARATORS);
+        let number_end = after.find(|c: char| !c.is_ascii_digit()).unwrap_or(after.len());
+        let (number, after) = after.split_at(number_end);
+        if word.is_empty() && number.is_empty() {
+            return None;
+        }
+        let number: u64 = if number.is_empty() { 0 } else { number.parse().ok()? };
+        let (label, into) = match word {
+            "a" | "alpha" => ("alpha", &mut pre),
+            "b" | "beta" => ("beta", &mut pre),
+            "c" | "rc" | "pre" | "preview" => ("rc", &mut pre),
+            "dev" => ("dev", &mut pre),
+            // A bare number after a hyphen is an implicit post-release, as in `1.0-1`.
+            "" | "post" | "rev" | "r" => ("post", &mut build),
+            _ => return None,
+        };
+        into.push(format!("{label}.{number}"));
+        rest = after;
+    }
+    build.extend(local.map(|local| local.replace(['-', '_'], ".")));
+
+    Some(Version {
+        major: numbers[0],
+        minor: numbers.get(1).copied().unwrap_or(0),
+        patch: numbers.get(2).copied().unwrap_or(0),
+        pre: Prerelease::new(&pre.join(".")).ok()?,
+        build: BuildMetadata::new(&build.join(".")).ok()?,
+    })
+}
+
+/// The PEP 440 spelling of a semver version, as in `1.4.0rc1` for `1.4.0-rc.1`.
+/// The pre-release must be `alpha`, `beta`, or `rc` (or their PEP 440 spellings),
+/// a `dev` release, or one followed by the other, each with an optional number. A
+/// leading `post.N` in the build metadata becomes a post-release, and the rest
+/// becomes the local version.
+pub fn from_semver(version: &Version) -> anyhow::Result<String> {
+    let mut output = format!("{}.{}.{}", version.major, version.minor, version.patch);
+    let mut pre = labels(version.pre.as_str()).ok_or_else(|| unrepresentable(version))?;
+    if let Some((label, number)) = pre.first().filter(|(label, _)| *label != "dev").cloned() {
+        let label = match label.as_str() {
+            "a" | "alpha" => "a",
+            "b" | "beta" => "b",
+            "c" | "rc" | "pre" | "preview" => "rc",
+            _ => return Err(unrepresentable(version)),
+        };
+        output.push_str(&format!("{label}{number}"));
+        pre.remove(0);
+    }
+    match pre.as_slice() {
+        [] => {}
+        [(label, number)] if label == "dev" => output.push_str(&format!(".dev{number}")),
+        _ => return Err(unrepresentable(version)),
+    }
+
+    let mut local = version.build.as_str();
+    let post = labels(local).and_then(|labels| labels.first().cloned());
+    if let Some((_, number)) = post.filter(|(label, _)| label == "post") {
+        output.push_str(&format!(".post{number}"));
+        local = local
+            .trim_start_matches("post")
+            .trim_start_matches(SEPARATORS)
+            .trim_start_matches(|c: char| c.is_ascii_digit())
+            .trim_start_matches(SEPARATORS);
+    }
+    if !local.is_empty() {
+        output.push('+');
+        output.push_str(&local.to_ascii_lowercase());
+    }
+    Ok(output)
+}
+
+fn unrepresentable(version: &Version) -> anyhow::Error {
+    Error::invalid(
+        &version.to_string(),
+        format!(
+            "The pre-release `{}` has no PEP 440 equivalent; PEP 440 only has alpha, beta, rc, and dev releases.",
+            version.pre
+        ),
+    )
+    .into()
+}
+
+/// Split identifiers into labels and the numbers after them, as in `rc.1.dev` to
+/// `rc 1` and `dev 0`. Returns `None` if a number has no label.
+fn labels(identifiers: &str) -> Option<Vec<(String, u64)>> {
+    let mut labels: Vec<(String, u64)> = Vec::new();
+    let mut waiting = false;
+    for part in identifiers.split(SEPARATORS).filter(|part| !part.is_empty()) {
+        let word_end = part.find(|c: char| !c.is_ascii_alphabetic()).unwrap_or(part.len());
+        let (word, number) = part.split_at(word_end);
+        match (word.is_empty(), number.parse::<u64>().ok()) {
+            (true, Some(number)) if waiting => {
+                labels.last_mut()?.1 = number;
+                waiting = false;
+            }
+            (false, Some(number)) => {
+                labels.push((word.to_ascii_lowercase(), number));
+                waiting = false;
+            }
+            (false, None) if number.is_empty() => {
+                labels.push((word.to_ascii_lowercase(), 0));
+                waiting = true;
+            }
+            _ => return None,
+        }
+    }
+    Some(labels)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn translating_versions() {
+        let cases = [
+            ("1.4", Some("1.4.0")),
+            ("2", Some("2.0.0")),
+            ("v1.4.2", Some("1.4.2")),
+            ("1.4a1", Some("1.4.0-alpha.1")),
+            ("1.4.0-ALPHA.1", Some("1.4.0-alpha.1")),
+            ("1.4b2", Some("1.4.0-beta.2")),
+            ("1.4c1", Some("1.4.0-rc.1")),
+            ("1.4rc", Some("1.4.0-rc.0")),
+            ("1.4.dev2", Some("1.4.0-dev.2")),
+            ("1.4b1.dev2", Some("1.4.0-beta.1.dev.2")),
+            ("1.4.post1", Some("1.4.0+post.1")),
+            ("1.4-1", Some("1.4.0+post.1")),
+            ("1.4+ubuntu-1", Some("1.4.0+ubuntu.1")),
+            ("0!1.4", Some("1.4.0")),
+            ("1!1.4", None),
+            ("1.4.2.1", None),
+            ("1.4-nightly", None),
+            ("", None),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(
+                to_semver(input).map(|v| v.to_string()).as_deref(),
+                expected,
+                "translating {input}"
+            );
+        }
+    }
+
+    #[test]
+    fn translating_back() {
+        let cases = [
+            ("1.4.0", Some("1.4.0")),
+            ("1.4.0-alpha.1", Some("1.4.0a1")),
+            ("1.4.0-beta2", Some("1.4.0b2")),
+            ("1.4.0-rc.1", Some("1.4.0rc1")),
+            ("1.4.0-rc", Some("1.4.0rc0")),
+            ("1.4.0-dev.2", Some("1.4.0.dev2")),
+            ("1.4.0-beta.1.dev.2", Some("1.4.0b1.dev2")),
+            ("1.4.0+post.1", Some("1.4.0.post1")),
+            ("1.4.0+post.1.ubuntu.1", Some("1.4.0.post1+ubuntu.1")),
+            ("1.4.0-rc.1+Build.5", Some("1.4.0rc1+build.5")),
+            ("1.4.0-nightly.1", None),
+            ("1.4.0-dev.1.rc.1", None),
+            ("1.4.0-rc.1.rc.2", None),
+            ("1.4.0-1", None),
+        ];
+        for (input, expected) in cases {
+            let version = Version::parse(input).expect("test data must be valid semver");
+            assert_eq!(from_semver(&version).ok().as_deref(), expected, "translating {input}");
+        }
+        // What comes back translates to the same version.
+        for input in ["1.4.0-beta.1.dev.2", "1.4.0+post.1.ubuntu.1"] {
+            let version = Version::parse(input).expect("test data must be valid semver");
+            let pep440 = from_semver(&version).expect("we expected a translation");
+            assert_eq!(to_semver(&pep440), Some(version));
+        }
+    }
+}
@@ -0,0 +1,423 @@
+//! Looking up the versions a package registry has published, so a release can be
+//! bumped from what's actually out there rather than from local files.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use semver::Version;
+
+use crate::error::Error;
+use crate::{http, pep440};
+
+/// Who we are, for registries that ask clients to identify themselves.
+const USER_AGENT: &str = concat!(
+    "semver-bump/",
+    env!("CARGO_PKG_VERSION"),
+    " (",
+    env!("CARGO_PKG_REPOSITORY"),
+    ")"
+);
+
+const CRATES_IO: &str = "https://crates.io/api/v1/crates";
+const NPM: &str = "https://registry.npmjs.org";
+const PYPI: &str = "https://pypi.org/pypi";
+
+/// The versions of a crate on crates.io that haven't been yanked.
+///
+/// The crates.io crawler policy asks for a `User-Agent` that says who's calling and
+/// for no more than one request a second, so long version lists are paged slowly.
+pub fn crate_versions(name: &str) -> anyhow::Result<Vec<Version>> {
+    let headers = [("User-Agent", USER_AGENT), ("Accept", "application/json")];
+    let base = format!("{CRATES_IO}/{name}/versions");
+    let mut url = format!("{base}?per_page=100");
+    let mut versions = Vec::new();
+    loop {
+        let response = http::get(&url, &headers)?;
+        if response.status == 404 {
+            return Err(Error::network(format!("There is no crate named {name} on crates.io.")).into());
+        }
+        if !response.is_success() {
+            return Err(Error::network(format!(
+                "crates.io refused to list the versions of {name} ({})",
+                response.status
+            ))
+            .into());
+        }
+        let (page, next) = crate_page(&response.json()?)?;
+        versions.extend(page);
+        match next {
+            Some(query) => {
+                url = format!("{base}{query}");
+                std::thread::sleep(Duration::from_secs(1));
+            }
+            None => break,
+        }
+    }
+    Ok(versions)
+}
+
+/// The unyanked versions on one page of a crates.io version list, and the query for
+/// the next page if there is one.
+fn crate_page(json: &serde_json::Value) -> anyhow::Result<(Vec<Version>, Option<String>)> {
+    let entries = json["versions"]
+        .as_array()
+        .ok_or_else(|| anyhow!("crates.io did not send a list of versions"))?;
+    let versions = entries
+        .iter()
+        .filter(|entry| !entry["yanked"].as_bool().unwrap_or(false))
+        .filter_map(|entry| entry["num"].as_str())
+        .filter_map(|num| Version::parse(num).ok())
+        .collect();
+    let next = json["meta"]["next_page"].as_str().map(str::to_string);
+    Ok((versions, next))
+}
+
+/// What the npm registry has published for a package.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct NpmPackage {
+    /// Every published version.
+    pub versions: Vec<Version>,
+    /// The versions the dist-tags, such as `latest` and `next`, point at.
+    pub dist_tags: BTreeMap<String, Version>,
+}
+
+impl NpmPackage {
+    /// Read a package document from the registry.
+    fn parse(json: &serde_json::Value) -> anyhow::Result<Self> {
+        let versions = json["versions"]
+            .as_object()
+            .ok_or_else(|| anyhow!("the npm registry did not send a list of versions"))?;
+        let versions = versions.keys().filter_map(|num| Version::parse(num).ok()).collect();
+        let dist_tags = json["dist-tags"]
+            .as_object()
+            .into_iter()
+            .flatten()
+            .filter_map(|(tag, num)| Some((tag.clone(), Version::parse(num.as_str()?).ok()?)))
+            .collect();
+        Ok(Self { versions, dist_tags })
+    }
+}
+
+/// The versions and dist-tags of a package in the npm registry. Scoped names such
+/// as `@scope/name` work as they are.
+pub fn npm_package(name: &str) -> anyhow::Result<NpmPackage> {
+    // The abbreviated document has everything we need and is far smaller.
+    let headers = [
+        ("User-Agent", USER_AGENT),
+        ("Accept", "application/vnd.npm.install-v1+json"),
+    ];
+    let url = format!("{NPM}/{}", name.replacen('/', "%2f", 1));
+    let response = http::get(&url, &headers)?;
+    if response.status == 404 {
+        return Err(Error::network(format!("There is no package named {name} in the npm registry.")).into());
+    }
+    if !response.is_success() {
+        return Err(Error::network(format!("the npm registry refused to send {name} ({})", response.status)).into());
+    }
+    NpmPackage::parse(&response.json()?)
+}
+
+/// The versions of a Python package on PyPI, translated from PEP 440 as
+/// [`pep440::to_semver`] describes. Releases that have no semver equivalent, and
+/// releases whose files have all been yanked, are left out.
+pub fn pypi_versions(name: &str) -> anyhow::Result<Vec<Version>> {
+    let headers = [("User-Agent", USER_AGENT), ("Accept", "application/json")];
+    let response = http::get(&format!("{PYPI}/{name}/json"), &headers)?;
+    if response.status == 404 {
+        return Err(Error::network(format!("There is no package named {name} on PyPI.")).into());
+    }
+    if !response.is_success() {
+        return Err(Error::network(format!("PyPI refused to send {name} ({})", response.status)).into());
+    }
+    pypi_releases(&response.json()?)
+}
+
+/// The unyanked releases in a PyPI project document that translate into semver.
+fn pypi_releases(json: &serde_json::Value) -> anyhow::Result<Vec<Version>> {
+    let releases = json["releases"]
+        .as_object()
+        .ok_or_else(|| anyhow!("PyPI did not send a list of releases"))?;
+    let versions = releases
+        .iter()
+        .filter(|(_, files)| {
+            let files = files.as_array().map(Vec::as_slice).unwrap_or_default();
+            files.is_empty() || files.iter().any(|file| !file["yanked"].as_bool().unwrap_or(false))
+        })
+        .filter_map(|(version, _)| pep440::to_semver(version))
+        .collect();
+    Ok(versions)
+}
+
+/// An image in a container registry, as in `ghcr.io/org/image`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Image {
+    /// The registry's host, with its port if it has one.
+    pub registry: String,
+    /// The repository within the registry.
+    pub repository: String,
+}
+
+impl Image {
+    /// Split an image reference into the registry and the repository. As with
+    /// `docker pull`, a reference whose first part doesn't look like a host is on
+    /// Docker Hub, and a bare name there is an official `library/` image.
+    pub fn parse(reference: &str) -> anyhow::Result<Self> {
+        let reference = reference.trim_end_matches('/');
+        if reference.is_empty() || reference.contains('@') {
+            return Err(anyhow!(
+                "`{reference}` is not an image reference; expected REGISTRY/NAME."
+            ));
+        }
+        let (registry, repository) = match reference.split_once('/') {
+            Some((host, rest)) if host.contains(['.', ':']) || host == "localhost" => (host, rest.to_string()),
+            Some(_) => ("docker.io", reference.to_string()),
+            None => ("docker.io", format!("library/{reference}")),
+        };
+        Ok(Self {
+            registry: registry.to_string(),
+            repository,
+        })
+    }
+
+    /// Where the registry's API lives. Docker Hub's is on another host, and a registry
+    /// on this machine is usually served without TLS.
+    fn api(&self) -> String {
+        let host = self.registry.split(':').next().unwrap_or_default();
+        match self.registry.as_str() {
+            "docker.io" => "https://registry-1.docker.io".to_string(),
+            registry if host == "localhost" || host == "127.0.0.1" => format!("http://{registry}"),
+            registry => format!("https://{registry}"),
+        }
+    }
+}
+
+/// The tags an image has in its registry, through the OCI distribution API. Public
+/// images on registries that want a token, such as ghcr.io and Docker Hub, get an
+/// anonymous pull token first. Pages are followed through the `Link` header, since a
+/// registry may send fewer tags than we ask for; one that sends no `Link` is asked
+/// for more only while it fills every page.
+pub fn image_tags(image: &Image) -> anyhow::Result<Vec<String>> {
+    const PAGE: usize = 1000;
+    let api = image.api();
+    let base = format!("{api}/v2/{}/tags/list?n={PAGE}", image.repository);
+    let mut url = base.clone();
+    let mut token: Option<String> = None;
+    let mut tags: Vec<String> = Vec::new();
+    loop {
+        let auth = token.as_ref().map(|token| format!("Bearer {token}"));
+        let mut headers = vec![("User-Agent", USER_AGENT)];
+        headers.extend(auth.as_deref().map(|auth| ("Authorization", auth)));
+        let response = http::get(&url, &headers)?;
+        if response.status == 401 && token.is_none() {
+            let challenge = response.header("www-authenticate").unwrap_or_default();
+            token = Some(anonymous_token(challenge, &image.registry)?);
+            continue;
+        }
+        if !response.is_success() {
+            return Err(Error::network(format!(
+                "{} refused to list the tags of {} ({})",
+                image.registry, image.repository, response.status
+            ))
+            .into());
+        }
+        let json = response.json()?;
+        let page: Vec<String> = json["tags"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|tag| tag.as_str())
+            .map(str::to_string)
+            .collect();
+        let full = page.len() >= PAGE;
+        tags.extend(page);
+        url = match (next_link(&api, response.header("link")), tags.last()) {
+            (Some(next), _) => next,
+            (None, Some(last)) if full => format!("{base}&last={last}"),
+            _ => break,
+        };
+    }
+    Ok(tags)
+}
+
+/// The URL of the next page from a `Link: <...>; rel="next"` header, resolved against
+/// the registry's API when it's only a path, as the distribution spec allows.
+fn next_link(api: &str, header: Option<&str>) -> Option<String> {
+    let target = header?.split(',').find_map(|link| {
+        let (target, params) = link.split_once(';')?;
+        let next = params
+            .split(';')
+            .any(|param| matches!(param.trim(), "rel=\"next\"" | "rel=next"));
+        next.then(|| target.trim().trim_start_matches('<').trim_end_matches('>'))
+    })?;
+    if target.starts_with('/') {
+        Some(format!("{api}{target}"))
+    } else {
+        Some(target.to_string())
+    }
+}
+
+/// Ask the token service named in a `WWW-Authenticate: Bearer` challenge for an
+/// anonymous token.
+fn anonymous_token(challenge: &str, registry: &str) -> anyhow::Result<String> {
+    let params = challenge
+        .strip_prefix("Bearer ")
+        .map(bearer_params)
+        .ok_or_else(|| anyhow!("{registry} wants credentials semver-bump doesn't have"))?;
+    let realm = params
+        .iter()
+        .find(|(key, _)| key == "realm")
+        .map(|(_, value)| value.as_str())
+        .ok_or_else(|| anyhow!("{registry} did not say where to get a token"))?;
+    let query: Vec<String> = params
+        .iter()
+        .filter(|(key, _)| key == "service" || key == "scope")
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect();
+    let url = format!("{realm}?{}", query.join("&"));
+    let response = http::get(&url, &[("User-Agent", USER_AGENT)])?;
+    if !response.is_success() {
+        return Err(Error::network(format!("{registry} refused an anonymous token ({})", response.status)).into());
+    }
+    let json = response.json()?;
+    json["token"]
+        .as_str()
+        .or_else(|| json["access_token"].as_str())
+        .map(str::to_string)
+        .ok_or_else(|| anyhow!("{registry} did not send a token"))
+}
+
+/// The `key="value"` pairs of a bearer challenge.
+fn bearer_params(params: &str) -> Vec<(String, String)> {
+    let mut found = Vec::new();
+    let mut rest = params.trim();
+    while let Some((key, after)) = rest.split_once('=') {
+        let key = key.trim().trim_start_matches(',').trim().to_string();
+        let (value, remaining) = match after.strip_prefix('"') {
+            Some(quoted) => quoted.split_once('"').unwrap_or((quoted, "")),
+            None => after.split_once(',').unwrap_or((after, "")),
+        };
+        found.push((key, value.to_string()));
+        rest = remaining;
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reading_crate_pages() {
+        let json = serde_json::json!({
+            "versions": [
+                {"num": "1.0.200", "yanked": false},
+                {"num": "1.0.199", "yanked": true},
+                {"num": "1.0.0-rc.1", "yanked": false},
+            ],
+            "meta": {"total": 3, "next_page": "?per_page=100&seek=abc"},
+        });
+        let (versions, next) = crate_page(&json).expect("we expected the page to parse");
+        let versions: Vec<String> = versions.iter().map(Version::to_string).collect();
+        assert_eq!(versions, ["1.0.200", "1.0.0-rc.1"]);
+        assert_eq!(next.as_deref(), Some("?per_page=100&seek=abc"));
+        let (_, next) = crate_page(&serde_json::json!({"versions": [], "meta": {"next_page": null}}))
+            .expect("we expected the page to parse");
+        assert_eq!(next, None);
+        crate_page(&serde_json::json!({"errors": []})).expect_err("we expected a missing list to be an error");
+    }
+
+    #[test]
+    fn reading_npm_packages() {
+        let json = serde_json::json!({
+            "name": "@ceejbot/example",
+            "dist-tags": {"latest": "1.2.3", "next": "2.0.0-beta.2"},
+            "versions": {"1.2.3": {}, "2.0.0-beta.2": {}, "1.0.0": {}},
+        });
+        let package = NpmPackage::parse(&json).expect("we expected the package to parse");
+        assert_eq!(package.versions.len(), 3);
+        assert_eq!(package.dist_tags["next"].to_string(), "2.0.0-beta.2");
+        NpmPackage::parse(&serde_json::json!({"error": "Not found"}))
+            .expect_err("we expected a missing list to be an error");
+    }
+
+    #[test]
+    fn reading_pypi_releases() {
+        let json = serde_json::json!({
+            "info": {"name": "example"},
+            "releases": {
+                "1.4": [{"yanked": false}],
+                "1.5": [{"yanked": true}],
+                "1.6rc1": [{"yanked": true}, {"yanked": false}],
+                "2!1.0": [],
+                "0.9": [],
+            },
+        });
+        let versions = pypi_releases(&json).expect("we expected the releases to parse");
+        let mut versions: Vec<String> = versions.iter().map(Version::to_string).collect();
+        versions.sort();
+        assert_eq!(versions, ["0.9.0", "1.4.0", "1.6.0-rc.1"]);
+    }
+
+    #[test]
+    fn image_references() {
+        let cases = [
+            (
+                "ghcr.io/ceejbot/semver-bump",
+                "ghcr.io",
+                "ceejbot/semver-bump",
+                "https://ghcr.io",
+            ),
+            ("alpine", "docker.io", "library/alpine", "https://registry-1.docker.io"),
+            (
+                "ceejbot/tool",
+                "docker.io",
+                "ceejbot/tool",
+                "https://registry-1.docker.io",
+            ),
+            ("localhost:5000/tool", "localhost:5000", "tool", "http://localhost:5000"),
+        ];
+        for (reference, registry, repository, api) in cases {
+            let image = Image::parse(reference).expect("we expected the reference to parse");
+            assert_eq!(
+                (image.registry.as_str(), image.repository.as_str()),
+                (registry, repository)
+            );
+            assert_eq!(image.api(), api);
+        }
+        Image::parse("ghcr.io/org/image@sha256:abc").expect_err("we expected a digest to be an error");
+    }
+
+    #[test]
+    fn following_links() {
+        let api = "https://ghcr.io";
+        assert_eq!(
+            next_link(api, Some(r#"</v2/org/image/tags/list?n=100&last=v1.2.3>; rel="next""#)).as_deref(),
+            Some("https://ghcr.io/v2/org/image/tags/list?n=100&last=v1.2.3")
+        );
+        assert_eq!(
+            next_link(
+                api,
+                Some(r#"<https://example.com/first>; rel="first", <https://example.com/2>; rel="next""#)
+            )
+            .as_deref(),
+            Some("https://example.com/2")
+        );
+        assert_eq!(next_link(api, Some(r#"</v2/org/image/tags/list>; rel="first""#)), None);
+        assert_eq!(next_link(api, None), None);
+    }
+
+    #[test]
+    fn bearer_challenges() {
+        let params =
+            bearer_params(r#"realm="https://ghcr.io/token",service="ghcr.io",scope="repository:org/image:pull""#);
+        assert_eq!(
+            params,
+            [
+                ("realm".to_string(), "https://ghcr.io/token".to_string()),
+                ("service".to_string(), "ghcr.io".to_string()),
+                ("scope".to_string(), "repository:org/image:pull".to_string()),
+            ]
+        );
+    }
+}
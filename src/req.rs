@@ -0,0 +1,385 @@
+//! Reasoning about version requirements the way a dependency-update bot has to:
+//! where a caret or tilde requirement stops, and how to change it to take a new
+//! release, and what several requirements accept between them or together. Requirements are
+//! read the way cargo reads them, so a bare `1.2` is the same as `^1.2`.
+
+use anyhow::{anyhow, Context};
+use semver::{Comparator, Op, Version, VersionReq};
+
+/// The single caret or tilde comparator in a requirement, and the version it's
+/// written with.
+fn comparator(requirement: &str) -> anyhow::Result<(Comparator, &str)> {
+    let parsed = VersionReq::parse(requirement)?;
+    match parsed.comparators.as_slice() {
+        [only] if matches!(only.op, Op::Caret | Op::Tilde) && !requirement.contains('*') => {
+            let trimmed = requirement.trim();
+            let start = trimmed.find(|c: char| c.is_ascii_digit()).unwrap_or(trimmed.len());
+            Ok((only.clone(), &trimmed[start..]))
+        }
+        _ => Err(anyhow!(
+            "`{requirement}` is not a single caret or tilde requirement, as in `^1.2` or `~1.2`"
+        )),
+    }
+}
+
+/// The first version above the comparator that it rejects, as in `2.0.0` for `^1.2`
+/// or `1.3.0` for `~1.2`.
+fn upper(comparator: &Comparator) -> anyhow::Result<Version> {
+    let (major, minor, patch) = (comparator.major, comparator.minor, comparator.patch);
+    let next = |number: u64| {
+        number
+            .checked_add(1)
+            .ok_or_else(|| anyhow!("The upper bound of `{comparator}` would overflow."))
+    };
+    let upper = match (comparator.op, minor, patch) {
+        (Op::Tilde, None, _) => Version::new(next(major)?, 0, 0),
+        (Op::Tilde, Some(minor), _) => Version::new(major, next(minor)?, 0),
+        _ if major > 0 => Version::new(next(major)?, 0, 0),
+        (_, None, _) => Version::new(1, 0, 0),
+        (_, Some(minor), _) if minor > 0 => Version::new(0, next(minor)?, 0),
+        (_, Some(_), None) => Version::new(0, 1, 0),
+        (_, Some(_), Some(patch)) => Version::new(0, 0, next(patch)?),
+    };
+    Ok(upper)
+}
+
+/// The comparator written with the same operator and number of components, at
+/// another version.
+fn moved(comparator: &Comparator, to: &Version) -> Comparator {
+    Comparator {
+        op: comparator.op,
+        major: to.major,
+        minor: comparator.minor.map(|_| to.minor),
+        patch: comparator.patch.map(|_| to.patch),
+        pre: Default::default(),
+    }
+}
+
+/// One end of a range of versions, and whether that version is in the range.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Bound {
+    version: Version,
+    inclusive: bool,
+}
+
+/// The first version past a partial comparator, as in `1.3.0` for `1.2` or `2.0.0`
+/// for `1`.
+fn past(comparator: &Comparator) -> anyhow::Result<Version> {
+    let next = |number: u64| {
+        number
+            .checked_add(1)
+            .ok_or_else(|| anyhow!("The upper bound of `{comparator}` would overflow."))
+    };
+    let past = match comparator.minor {
+        None => Version::new(next(comparator.major)?, 0, 0),
+        Some(minor) => Version::new(comparator.major, next(minor)?, 0),
+    };
+    Ok(past)
+}
+
+/// The lower and upper bounds of the versions a comparator accepts, where it has them.
+fn bounds(comparator: &Comparator) -> anyhow::Result<(Option<Bound>, Option<Bound>)> {
+    let filled = Version {
+        major: comparator.major,
+        minor: comparator.minor.unwrap_or(0),
+        patch: comparator.patch.unwrap_or(0),
+        pre: comparator.pre.clone(),
+        build: Default::default(),
+    };
+    let full = comparator.patch.is_some();
+    let at = |version: Version, inclusive: bool| Some(Bound { version, inclusive });
+    let bounds = match comparator.op {
+        Op::Exact | Op::Wildcard if full => (at(filled.clone(), true), at(filled, true)),
+        Op::Exact | Op::Wildcard => (at(filled, true), at(past(comparator)?, false)),
+        Op::Greater if full => (at(filled, false), None),
+        Op::Greater => (at(past(comparator)?, true), None),
+        Op::GreaterEq => (at(filled, true), None),
+        Op::Less => (None, at(filled, false)),
+        Op::LessEq if full => (None, at(filled, true)),
+        Op::LessEq => (None, at(past(comparator)?, false)),
+        Op::Tilde | Op::Caret => (at(filled, true), at(upper(comparator)?, false)),
+        _ => {
+            return Err(anyhow!(
+                "`{comparator}` uses an operator we don't know how to intersect"
+            ))
+        }
+    };
+    Ok(bounds)
+}
+
+/// The lower and upper bounds of a range of versions, where it has them.
+type Range = (Option<Bound>, Option<Bound>);
+
+/// The range of versions every comparator accepts, or `None` if there are no such
+/// versions.
+fn range<'a>(comparators: impl IntoIterator<Item = &'a Comparator>) -> anyhow::Result<Option<Range>> {
+    let mut lower: Option<Bound> = None;
+    let mut upper: Option<Bound> = None;
+    for comparator in comparators {
+        let (low, high) = bounds(comparator)?;
+        if let Some(low) = low {
+            lower = match lower {
+                Some(current) if (&current.version, !current.inclusive) >= (&low.version, !low.inclusive) => {
+                    Some(current)
+                }
+                _ => Some(low),
+            };
+        }
+        if let Some(high) = high {
+            upper = match upper {
+                Some(current) if (&current.version, current.inclusive) <= (&high.version, high.inclusive) => {
+                    Some(current)
+                }
+                _ => Some(high),
+            };
+        }
+    }
+    match (&lower, &upper) {
+        (Some(low), Some(high)) if low.version > high.version => Ok(None),
+        (Some(low), Some(high)) if low.version == high.version && !(low.inclusive && high.inclusive) => Ok(None),
+        _ => Ok(Some((lower, upper))),
+    }
+}
+
+/// A range written as a requirement.
+fn written((lower, upper): &Range) -> String {
+    match (lower, upper) {
+        (Some(low), Some(high)) if low.version == high.version => format!("={}", low.version),
+        (lower, upper) => {
+            let lower = lower
+                .as_ref()
+                .map(|low| format!("{}{}", if low.inclusive { ">=" } else { ">" }, low.version));
+            let upper = upper
+                .as_ref()
+                .map(|high| format!("{}{}", if high.inclusive { "<=" } else { "<" }, high.version));
+            let parts: Vec<String> = lower.into_iter().chain(upper).collect();
+            if parts.is_empty() {
+                "*".to_string()
+            } else {
+                parts.join(", ")
+            }
+        }
+    }
+}
+
+/// The single range of versions that every requirement accepts, written as a
+/// requirement, or `None` if there are no such versions. Ranges are compared by
+/// precedence alone, without the special handling requirements give pre-releases.
+pub fn intersect(requirements: &[VersionReq]) -> anyhow::Result<Option<String>> {
+    let range = range(requirements.iter().flat_map(|req| req.comparators.iter()))?;
+    Ok(range.as_ref().map(written))
+}
+
+/// The versions that any of the requirements accepts, written as the fewest ranges
+/// that cover them from lowest to highest, since a requirement can't say "or". A
+/// requirement that accepts nothing adds nothing, so this is empty if none of them
+/// accepts anything. Ranges are compared by precedence alone, as with [`intersect`].
+pub fn union(requirements: &[VersionReq]) -> anyhow::Result<Vec<String>> {
+    let mut ranges = Vec::new();
+    for requirement in requirements {
+        ranges.extend(range(&requirement.comparators)?);
+    }
+    let start = |lower: &Option<Bound>| lower.as_ref().map(|low| (low.version.clone(), !low.inclusive));
+    ranges.sort_by_key(|(lower, _)| start(lower));
+    let mut merged: Vec<Range> = Vec::new();
+    for (lower, upper) in ranges {
+        let Some((_, last)) = merged.last_mut() else {
+            merged.push((lower, upper));
+            continue;
+        };
+        let reaches = match (&*last, &lower) {
+            (Some(end), Some(low)) => {
+                low.version < end.version || (low.version == end.version && (low.inclusive || end.inclusive))
+            }
+            _ => true,
+        };
+        if !reaches {
+            merged.push((lower, upper));
+            continue;
+        }
+        let further = match (&*last, &upper) {
+            (None, _) => false,
+            (Some(_), None) => true,
+            (Some(end), Some(high)) => (&high.version, high.inclusive) > (&end.version, end.inclusive),
+        };
+        if further {
+            *last = upper;
+        }
+    }
+    Ok(merged.iter().map(written).collect())
+}
+
+/// Raise the upper bound of a caret or tilde requirement past the next incompatible
+/// series, keeping its lower bound, so `^1.2` becomes `>=1.2, <3.0.0`.
+pub fn bump_upper(requirement: &str) -> anyhow::Result<String> {
+    let (comparator, lower) = comparator(requirement)?;
+    let next = upper(&comparator)
+        .and_then(|first| upper(&moved(&comparator, &first)))
+        .with_context(|| format!("Unable to raise the upper bound of `{requirement}`."))?;
+    Ok(format!(">={lower}, <{next}"))
+}
+
+/// Raise the upper bound of a caret or tilde requirement just enough for it to also
+/// accept the given version, keeping its lower bound, so `^1.2` widened for `2.1.0`
+/// becomes `>=1.2, <3.0.0`. A requirement that already accepts the version is
+/// returned as it is.
+pub fn widen(requirement: &str, version: &Version) -> anyhow::Result<String> {
+    let (comparator, lower) = comparator(requirement)?;
+    if comparator.matches(version) {
+        return Ok(requirement.trim().to_string());
+    }
+    if upper(&comparator)? > *version {
+        return Err(anyhow!(
+            "{version} is below `{requirement}`, and widening only raises the upper bound"
+        ));
+    }
+    let next = upper(&moved(&comparator, version))
+        .with_context(|| format!("Unable to widen `{requirement}` for {version}."))?;
+    Ok(format!(">={lower}, <{next}"))
+}
+
+/// Raise the lower bound of a requirement to the given version, keeping its operator
+/// and the number of components it spells out, so `^1.2` narrowed to `1.4.0` becomes
+/// `^1.4`. The version must already satisfy the requirement.
+pub fn narrow(requirement: &str, version: &Version) -> anyhow::Result<String> {
+    comparator(requirement)?;
+    if !VersionReq::parse(requirement)?.matches(version) {
+        return Err(anyhow!(
+            "`{requirement}` does not accept {version}, so it can't be narrowed to it; widen it instead"
+        ));
+    }
+    let narrowed = crate::manifest::cargo::rewrite_requirement(requirement, version)?;
+    Ok(narrowed.unwrap_or_else(|| requirement.trim().to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(input: &str) -> Version {
+        Version::parse(input).expect("test data must be valid semver")
+    }
+
+    #[test]
+    fn bumping_upper_bounds() {
+        for (requirement, expected) in [
+            ("^1.2", ">=1.2, <3.0.0"),
+            ("1.2.3", ">=1.2.3, <3.0.0"),
+            ("^0.3", ">=0.3, <0.5.0"),
+            ("^0.0.3", ">=0.0.3, <0.0.5"),
+            ("^0.0", ">=0.0, <0.2.0"),
+            ("^0", ">=0, <2.0.0"),
+            ("~1.2", ">=1.2, <1.4.0"),
+            ("~1", ">=1, <3.0.0"),
+        ] {
+            assert_eq!(
+                bump_upper(requirement).expect("we expected the requirement to have an upper bound"),
+                expected,
+                "bumping the upper bound of {requirement}"
+            );
+        }
+        for requirement in [
+            ">=1.2",
+            "=1.2.3",
+            "1.*",
+            ">=1.2, <2",
+            "not a requirement",
+            "^18446744073709551615",
+            "^18446744073709551614",
+            "~1.18446744073709551615",
+        ] {
+            bump_upper(requirement)
+                .expect_err("we expected only caret and tilde requirements with room above them to work");
+        }
+    }
+
+    #[test]
+    fn intersecting() {
+        let intersected = |requirements: &[&str]| {
+            let requirements: Vec<VersionReq> = requirements
+                .iter()
+                .map(|req| VersionReq::parse(req).expect("test data must be a valid requirement"))
+                .collect();
+            intersect(&requirements).expect("we expected the requirements to intersect")
+        };
+        for (requirements, expected) in [
+            (&["^1.2", ">=1.4"][..], ">=1.4.0, <2.0.0"),
+            (&["~1.2", "^1.2.5"], ">=1.2.5, <1.3.0"),
+            (&[">1.2.3", ">=1.2.3"], ">1.2.3"),
+            (&["<2", "<=2.0.0"], "<2.0.0"),
+            (&["=1.2", "<1.2.7"], ">=1.2.0, <1.2.7"),
+            (&["1.*", ">1.4"], ">=1.5.0, <2.0.0"),
+            (&[">=1.2.3", "<=1.2.3"], "=1.2.3"),
+            (&["*"], "*"),
+        ] {
+            assert_eq!(
+                intersected(requirements).as_deref(),
+                Some(expected),
+                "intersecting {requirements:?}"
+            );
+        }
+        assert_eq!(intersected(&["^1.2", ">=2"]), None);
+        assert_eq!(intersected(&[">1.2.3", "<=1.2.3"]), None);
+
+        let requirement = VersionReq::parse("<=18446744073709551615").expect("test data must be a valid requirement");
+        intersect(&[requirement]).expect_err("we expected a bound that overflows to be an error");
+    }
+
+    #[test]
+    fn uniting() {
+        let united = |requirements: &[&str]| {
+            let requirements: Vec<VersionReq> = requirements
+                .iter()
+                .map(|req| VersionReq::parse(req).expect("test data must be a valid requirement"))
+                .collect();
+            union(&requirements).expect("we expected the requirements to unite")
+        };
+        for (requirements, expected) in [
+            (&["^1.2", "^2"][..], &[">=1.2.0, <3.0.0"][..]),
+            (&["^2", "~1.2"], &[">=1.2.0, <1.3.0", ">=2.0.0, <3.0.0"]),
+            (&["^1.2", "~1.4"], &[">=1.2.0, <2.0.0"]),
+            (&["<2", ">=2"], &["*"]),
+            (&["<2", ">2.0.0"], &["<2.0.0", ">2.0.0"]),
+            (&["<=2.0.0", ">2.0.0"], &["*"]),
+            (&["=1.2.3", "=1.2.3"], &["=1.2.3"]),
+            (&[">=1.2.3", "<=1.2.3"], &["*"]),
+            (&[">1.2.3, <=1.2.3", "^2"], &[">=2.0.0, <3.0.0"]),
+        ] {
+            assert_eq!(united(requirements), expected, "uniting {requirements:?}");
+        }
+        assert!(united(&[">1.2.3, <=1.2.3"]).is_empty());
+    }
+
+    #[test]
+    fn widening_and_narrowing() {
+        let widened = |requirement: &str, to: &str| widen(requirement, &version(to));
+        assert_eq!(
+            widened("^1.2", "2.1.0").expect("we expected the requirement to widen"),
+            ">=1.2, <3.0.0"
+        );
+        assert_eq!(
+            widened("~1.2", "1.5.3").expect("we expected the requirement to widen"),
+            ">=1.2, <1.6.0"
+        );
+        assert_eq!(
+            widened("^0.3", "0.7.1").expect("we expected the requirement to widen"),
+            ">=0.3, <0.8.0"
+        );
+        assert_eq!(
+            widened("^1.2", "1.9.0").expect("we expected the requirement to be left alone"),
+            "^1.2"
+        );
+        widened("^1.2", "1.1.0").expect_err("we expected widening down to be an error");
+
+        let narrowed = |requirement: &str, to: &str| narrow(requirement, &version(to));
+        assert_eq!(
+            narrowed("^1.2", "1.4.0").expect("we expected the requirement to narrow"),
+            "^1.4"
+        );
+        assert_eq!(
+            narrowed("~1.2.0", "1.2.5").expect("we expected the requirement to narrow"),
+            "~1.2.5"
+        );
+        narrowed("^1.2", "2.0.0").expect_err("we expected narrowing outside the requirement to be an error");
+    }
+}
@@ -0,0 +1,102 @@
+//! Asking [cargo-semver-checks](https://github.com/obi1kenobi/cargo-semver-checks)
+//! how big a bump a crate's API changes call for. It compares the crate's public
+//! API against its last release and ends its report for each crate with a summary
+//! line such as `Summary semver requires new major version: 1 major and 0 minor
+//! checks failed`, which is all we read.
+//!
+//! Its levels are cargo's, so for a 0.x crate a "major" change is one that needs a
+//! new minor version.
+
+use std::process::Command;
+
+use anyhow::{anyhow, Context};
+
+use crate::Level;
+
+/// Run `cargo semver-checks check-release` in the current directory, optionally for
+/// one package and against a baseline git revision, and return its report.
+pub fn run(package: Option<&str>, baseline_rev: Option<&str>) -> anyhow::Result<String> {
+    let mut command = Command::new("cargo");
+    command.args(["semver-checks", "check-release", "--color", "never"]);
+    if let Some(package) = package {
+        command.args(["--package", package]);
+    }
+    if let Some(rev) = baseline_rev {
+        command.args(["--baseline-rev", rev]);
+    }
+    let output = command.output().context("unable to run cargo; is it installed?")?;
+    // Failed checks make it exit unsuccessfully, so the report decides what happened.
+    let mut report = String::from_utf8_lossy(&output.stderr).into_owned();
+    report.push_str(&String::from_utf8_lossy(&output.stdout));
+    if required_level(&report).is_none() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(anyhow!("cargo semver-checks failed: {}", stderr.trim()));
+    }
+    Ok(report)
+}
+
+/// The largest bump the summaries in a report call for: `patch` when no update is
+/// required. Returns `None` if the report has no summary.
+pub fn required_level(report: &str) -> Option<Level> {
+    strip_colors(report)
+        .lines()
+        .filter_map(|line| line.trim_start().strip_prefix("Summary "))
+        .filter_map(|summary| {
+            if summary.starts_with("semver requires new major version") {
+                Some(Level::Major)
+            } else if summary.starts_with("semver requires new minor version") {
+                Some(Level::Minor)
+            } else if summary.starts_with("no semver update required") {
+                Some(Level::Patch)
+            } else {
+                None
+            }
+        })
+        .max()
+}
+
+/// Drop the escape sequences that color a report saved from a terminal.
+fn strip_colors(report: &str) -> String {
+    let mut plain = String::with_capacity(report.len());
+    let mut chars = report.chars();
+    while let Some(c) = chars.next() {
+        if c == '\u{1b}' {
+            chars.by_ref().find(|c| c.is_ascii_alphabetic());
+        } else {
+            plain.push(c);
+        }
+    }
+    plain
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reading_reports() {
+        let breaking = "    Building semver-bump v1.2.3 (current)
+       Built [   2.134s] (current)
+     Parsing semver-bump v1.2.3 (current)
+    Checking semver-bump v1.2.3 -> v1.2.3 (no change)
+     Checked [   0.012s] 84 checks: 83 pass, 1 fail, 0 warn, 0 skip
+
+--- failure function_missing: pub fn removed or renamed ---
+
+Failed in:
+  function semver_bump::candidates, previously in file src/lib.rs:230
+
+     Summary semver requires new major version: 1 major and 0 minor checks failed
+";
+        assert_eq!(required_level(breaking), Some(Level::Major));
+        let additive = "     Summary semver requires new minor version: 0 major and 1 minor checks failed\n";
+        assert_eq!(required_level(additive), Some(Level::Minor));
+        let quiet = "     Summary no semver update required\n";
+        assert_eq!(required_level(quiet), Some(Level::Patch));
+        // A workspace has a summary for each crate.
+        assert_eq!(required_level(&format!("{quiet}{additive}")), Some(Level::Minor));
+        let colored = "\u{1b}[1m\u{1b}[32m     Summary\u{1b}[0m no semver update required\n";
+        assert_eq!(required_level(colored), Some(Level::Patch));
+        assert_eq!(required_level("error: no such command: `semver-checks`"), None);
+    }
+}
@@ -0,0 +1,129 @@
+//! Output templates such as `{major}.{minor}`, for printing a new version in
+//! whatever shape a downstream step needs, like a Docker tag or an artifact name.
+
+use std::str::FromStr;
+
+use anyhow::anyhow;
+
+/// The placeholders a template may use.
+pub const PLACEHOLDERS: [&str; 12] = [
+    "version",
+    "next",
+    "major",
+    "minor",
+    "patch",
+    "prerelease",
+    "build",
+    "prefix",
+    "prev",
+    "bump",
+    "tag_prefix",
+    "tag",
+];
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Segment {
+    Text(String),
+    Placeholder(String),
+}
+
+/// A parsed template. Placeholders are written in braces, as in `{major}`, and
+/// literal braces are written doubled, as in `{{`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Template {
+    segments: Vec<Segment>,
+}
+
+impl FromStr for Template {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        Self::parse(input, &PLACEHOLDERS)
+    }
+}
+
+impl Template {
+    /// Parse a template that may use only the given placeholders.
+    pub fn parse(input: &str, placeholders: &[&str]) -> anyhow::Result<Self> {
+        let mut segments = Vec::new();
+        let mut text = String::new();
+        let mut chars = input.chars().peekable();
+        while let Some(ch) = chars.next() {
+            match ch {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    text.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    text.push('}');
+                }
+                '{' => {
+                    let mut name = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(ch) => name.push(ch),
+                            None => return Err(anyhow!("The template `{input}` has a `{{` with no matching `}}`.")),
+                        }
+                    }
+                    if !placeholders.contains(&name.as_str()) {
+                        let names: Vec<String> = placeholders.iter().map(|p| format!("{{{p}}}")).collect();
+                        return Err(anyhow!(
+                            "`{{{name}}}` is not a placeholder; use one of {}",
+                            names.join(", ")
+                        ));
+                    }
+                    if !text.is_empty() {
+                        segments.push(Segment::Text(std::mem::take(&mut text)));
+                    }
+                    segments.push(Segment::Placeholder(name));
+                }
+                '}' => return Err(anyhow!("The template `{input}` has a `}}` with no matching `{{`.")),
+                ch => text.push(ch),
+            }
+        }
+        if !text.is_empty() {
+            segments.push(Segment::Text(text));
+        }
+        Ok(Self { segments })
+    }
+
+    /// Fill in the template, looking up the value of each placeholder by name.
+    pub fn render(&self, lookup: impl Fn(&str) -> String) -> String {
+        self.segments
+            .iter()
+            .map(|segment| match segment {
+                Segment::Text(text) => text.clone(),
+                Segment::Placeholder(name) => lookup(name),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rendering() {
+        let template: Template = "{major}.{minor}-{{literal}}"
+            .parse()
+            .expect("we expected the template to parse");
+        let rendered = template.render(|name| match name {
+            "major" => "1".to_string(),
+            "minor" => "2".to_string(),
+            _ => String::new(),
+        });
+        assert_eq!(rendered, "1.2-{literal}");
+        "{majr}"
+            .parse::<Template>()
+            .expect_err("we expected an unknown placeholder to be rejected");
+        "{major"
+            .parse::<Template>()
+            .expect_err("we expected an unclosed placeholder to be rejected");
+        "major}"
+            .parse::<Template>()
+            .expect_err("we expected a stray brace to be rejected");
+    }
+}
@@ -0,0 +1,122 @@
+//! UTC timestamps formatted for use as build metadata, so nightly builds can be
+//! told apart by when they were made.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, Context};
+
+use crate::calver::Date;
+
+/// The format used when none is given.
+pub const DEFAULT_FORMAT: &str = "YYYYMMDDHHMMSS";
+
+/// One piece of a timestamp format.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Token {
+    Year,
+    Month,
+    Day,
+    Hour,
+    Minute,
+    Second,
+    Literal(char),
+}
+
+/// A timestamp format such as `YYYYMMDDHHMMSS` or `nightly.YYYYMMDD`. `YYYY` is the
+/// year, `DD` the day, `HH` the hour, and `SS` the second. `MM` is the month, unless
+/// it comes after `HH`, in which case it's the minute. Everything else is copied as
+/// is, and must be a character allowed in build metadata: an ASCII letter, a digit,
+/// `-`, or `.`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Format {
+    tokens: Vec<Token>,
+}
+
+impl std::str::FromStr for Format {
+    type Err = anyhow::Error;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let mut tokens = Vec::new();
+        let mut rest = input;
+        while !rest.is_empty() {
+            let seen_hour = tokens.contains(&Token::Hour);
+            let (token, len) = if rest.starts_with("YYYY") {
+                (Token::Year, 4)
+            } else if rest.starts_with("MM") {
+                (if seen_hour { Token::Minute } else { Token::Month }, 2)
+            } else if rest.starts_with("DD") {
+                (Token::Day, 2)
+            } else if rest.starts_with("HH") {
+                (Token::Hour, 2)
+            } else if rest.starts_with("SS") {
+                (Token::Second, 2)
+            } else {
+                let ch = rest.chars().next().unwrap_or_default();
+                if !(ch.is_ascii_alphanumeric() || ch == '-' || ch == '.') {
+                    return Err(anyhow!(
+                        "`{ch}` can't appear in build metadata; use only ASCII letters, digits, `-`, and `.`"
+                    ));
+                }
+                (Token::Literal(ch), ch.len_utf8())
+            };
+            tokens.push(token);
+            rest = &rest[len..];
+        }
+        if tokens.is_empty() {
+            return Err(anyhow!("The timestamp format is empty."));
+        }
+        Ok(Self { tokens })
+    }
+}
+
+impl Format {
+    /// Format the time the given number of seconds after the unix epoch.
+    pub fn render(&self, seconds: u64) -> String {
+        let date = Date::from_days((seconds / 86_400) as i64);
+        let time = seconds % 86_400;
+        let mut output = String::new();
+        for token in &self.tokens {
+            match token {
+                Token::Year => output.push_str(&format!("{:04}", date.year)),
+                Token::Month => output.push_str(&format!("{:02}", date.month)),
+                Token::Day => output.push_str(&format!("{:02}", date.day)),
+                Token::Hour => output.push_str(&format!("{:02}", time / 3600)),
+                Token::Minute => output.push_str(&format!("{:02}", time / 60 % 60)),
+                Token::Second => output.push_str(&format!("{:02}", time % 60)),
+                Token::Literal(ch) => output.push(*ch),
+            }
+        }
+        output
+    }
+
+    /// Format the current time.
+    pub fn now(&self) -> anyhow::Result<String> {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .context("the system clock is set before 1970")?
+            .as_secs();
+        Ok(self.render(seconds))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formatting() {
+        // 2024-02-29T13:04:05Z
+        let seconds = 19_782 * 86_400 + 13 * 3600 + 4 * 60 + 5;
+        let format: Format = DEFAULT_FORMAT.parse().expect("we expected the default format to parse");
+        assert_eq!(format.render(seconds), "20240229130405");
+        let format: Format = "nightly.YYYYMMDD.HHMM"
+            .parse()
+            .expect("we expected the format to parse");
+        assert_eq!(format.render(seconds), "nightly.20240229.1304");
+        "YYYY_MM"
+            .parse::<Format>()
+            .expect_err("we expected illegal characters to be rejected");
+        "".parse::<Format>()
+            .expect_err("we expected an empty format to be rejected");
+    }
+}
@@ -0,0 +1,260 @@
+//! Checking whether a string is strict semver 2.0.0, and explaining exactly
+//! what's wrong with it when it isn't. The semver crate's parse errors are
+//! accurate but terse; these are meant to be read by a person fixing a typo.
+//! For input that's nearly right, [`normalize`] fixes what it safely can first.
+
+use std::fmt::{self, Display};
+
+use semver::Version;
+
+/// The names of the three numeric components, in order.
+const COMPONENTS: [&str; 3] = ["major", "minor", "patch"];
+
+/// A specific reason a string is not a valid semver version.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Problem {
+    /// There was nothing to check.
+    Empty,
+    /// The input has whitespace around it or inside it.
+    Whitespace,
+    /// The input starts with a `v`, as git tags often do.
+    Prefix,
+    /// One of major, minor, or patch is missing.
+    MissingComponent(&'static str),
+    /// There are more than three dot-separated numbers.
+    TooManyComponents(usize),
+    /// A numeric component is empty, as in `1..3`.
+    EmptyComponent(&'static str),
+    /// A numeric component contains something other than digits.
+    NotANumber(&'static str, String),
+    /// A numeric component or numeric pre-release identifier has a leading zero.
+    LeadingZero(String),
+    /// A numeric component is too large to represent.
+    TooLarge(&'static str),
+    /// A pre-release or build segment is empty, or has an empty identifier.
+    EmptyIdentifier(&'static str),
+    /// A pre-release or build identifier contains an illegal character.
+    BadCharacter(&'static str, char),
+    /// The semver crate rejected the input for some reason not covered above.
+    Other(String),
+}
+
+impl Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Problem::Empty => write!(f, "the version is empty"),
+            Problem::Whitespace => write!(f, "the version contains whitespace"),
+            Problem::Prefix => write!(f, "the version starts with a `v`, which is not part of semver"),
+            Problem::MissingComponent(name) => write!(f, "the version is missing its {name} component"),
+            Problem::TooManyComponents(count) => write!(
+                f,
+                "the version has {count} numeric components; semver requires exactly three"
+            ),
+            Problem::EmptyComponent(name) => write!(f, "the {name} component is empty"),
+            Problem::NotANumber(name, text) => write!(
+                f,
+                "the {name} component `{text}` is not a number; it may contain only digits"
+            ),
+            Problem::LeadingZero(text) => write!(f, "the number `{text}` has a leading zero, which semver forbids"),
+            Problem::TooLarge(name) => write!(f, "the {name} component is too large"),
+            Problem::EmptyIdentifier(segment) => write!(f, "the {segment} segment contains an empty identifier"),
+            Problem::BadCharacter(segment, ch) => write!(
+                f,
+                "the {segment} segment contains `{ch}`; identifiers may contain only ASCII letters, digits, and `-`"
+            ),
+            Problem::Other(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+impl std::error::Error for Problem {}
+
+/// Check that the input is a strict semver 2.0.0 version, returning the
+/// parsed version or the first problem found with it.
+pub fn validate(input: &str) -> Result<Version, Problem> {
+    if input.is_empty() {
+        return Err(Problem::Empty);
+    }
+    if input.chars().any(char::is_whitespace) {
+        return Err(Problem::Whitespace);
+    }
+    if input.starts_with(['v', 'V']) {
+        return Err(Problem::Prefix);
+    }
+
+    let (rest, build) = match input.split_once('+') {
+        Some((rest, build)) => (rest, Some(build)),
+        None => (input, None),
+    };
+    let (core, pre) = match rest.split_once('-') {
+        Some((core, pre)) => (core, Some(pre)),
+        None => (rest, None),
+    };
+
+    let numbers: Vec<&str> = core.split('.').collect();
+    if numbers.len() > COMPONENTS.len() {
+        return Err(Problem::TooManyComponents(numbers.len()));
+    }
+    for (idx, name) in COMPONENTS.iter().enumerate() {
+        let Some(text) = numbers.get(idx) else {
+            return Err(Problem::MissingComponent(name));
+        };
+        check_number(name, text)?;
+    }
+    if let Some(pre) = pre {
+        check_identifiers("pre-release", pre, true)?;
+    }
+    if let Some(build) = build {
+        check_identifiers("build", build, false)?;
+    }
+
+    Version::parse(input).map_err(|e| Problem::Other(e.to_string()))
+}
+
+/// Fix the mistakes in a nearly valid version that can be fixed without guessing,
+/// then check it as [`validate`] does. Whitespace and a byte order mark around it
+/// are trimmed, a leading `v` is dropped, missing minor and patch numbers are
+/// filled in with 0, and leading zeros are removed from numbers, including numeric
+/// pre-release identifiers. With `lowercase`, pre-release identifiers are
+/// lowercased too, which changes how they sort, so it's only done on request.
+pub fn normalize(input: &str, lowercase: bool) -> Result<Version, Problem> {
+    let trimmed = input.trim().trim_start_matches('\u{feff}').trim();
+    let trimmed = trimmed.strip_prefix(['v', 'V']).unwrap_or(trimmed);
+    let (rest, build) = match trimmed.split_once('+') {
+        Some((rest, build)) => (rest, Some(build)),
+        None => (trimmed, None),
+    };
+    let (core, pre) = match rest.split_once('-') {
+        Some((core, pre)) => (core, Some(pre)),
+        None => (rest, None),
+    };
+
+    let mut numbers: Vec<String> = core.split('.').map(without_leading_zeros).collect();
+    if numbers.len() > COMPONENTS.len() {
+        return Err(Problem::TooManyComponents(numbers.len()));
+    }
+    if !core.is_empty() {
+        numbers.resize(COMPONENTS.len(), "0".to_string());
+    }
+    let mut normalized = numbers.join(".");
+    if let Some(pre) = pre {
+        let pre = if lowercase {
+            pre.to_ascii_lowercase()
+        } else {
+            pre.to_string()
+        };
+        let identifiers: Vec<String> = pre.split('.').map(without_leading_zeros).collect();
+        normalized.push('-');
+        normalized.push_str(&identifiers.join("."));
+    }
+    if let Some(build) = build {
+        normalized.push('+');
+        normalized.push_str(build);
+    }
+    validate(&normalized)
+}
+
+/// Drop the leading zeros from a number, keeping a lone 0. Anything that isn't a
+/// number is returned as it is.
+fn without_leading_zeros(text: &str) -> String {
+    if text.len() > 1 && text.chars().all(|c| c.is_ascii_digit()) {
+        let trimmed = text.trim_start_matches('0');
+        if trimmed.is_empty() { "0" } else { trimmed }.to_string()
+    } else {
+        text.to_string()
+    }
+}
+
+fn check_number(name: &'static str, text: &str) -> Result<(), Problem> {
+    if text.is_empty() {
+        return Err(Problem::EmptyComponent(name));
+    }
+    if !text.chars().all(|c| c.is_ascii_digit()) {
+        return Err(Problem::NotANumber(name, text.to_string()));
+    }
+    if text.len() > 1 && text.starts_with('0') {
+        return Err(Problem::LeadingZero(text.to_string()));
+    }
+    if text.parse::<u64>().is_err() {
+        return Err(Problem::TooLarge(name));
+    }
+    Ok(())
+}
+
+fn check_identifiers(segment: &'static str, text: &str, numbers_are_strict: bool) -> Result<(), Problem> {
+    for identifier in text.split('.') {
+        if identifier.is_empty() {
+            return Err(Problem::EmptyIdentifier(segment));
+        }
+        if let Some(ch) = identifier.chars().find(|c| !(c.is_ascii_alphanumeric() || *c == '-')) {
+            return Err(Problem::BadCharacter(segment, ch));
+        }
+        let numeric = identifier.chars().all(|c| c.is_ascii_digit());
+        if numbers_are_strict && numeric && identifier.len() > 1 && identifier.starts_with('0') {
+            return Err(Problem::LeadingZero(identifier.to_string()));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn valid_versions() {
+        for input in [
+            "1.2.3",
+            "0.0.0",
+            "1.2.3-alpha.1",
+            "1.2.3-0a.01b",
+            "1.2.3+003.build",
+            "1.2.3-rc-1+x",
+        ] {
+            let version = validate(input).expect("we expected this version to be valid");
+            assert_eq!(version.to_string(), input);
+        }
+    }
+
+    #[test]
+    fn diagnoses() {
+        assert_eq!(validate(""), Err(Problem::Empty));
+        assert_eq!(validate(" 1.2.3"), Err(Problem::Whitespace));
+        assert_eq!(validate("v1.2.3"), Err(Problem::Prefix));
+        assert_eq!(validate("1.2"), Err(Problem::MissingComponent("patch")));
+        assert_eq!(validate("1.2.3.4"), Err(Problem::TooManyComponents(4)));
+        assert_eq!(validate("1..3"), Err(Problem::EmptyComponent("minor")));
+        assert_eq!(validate("1.x.3"), Err(Problem::NotANumber("minor", "x".to_string())));
+        assert_eq!(validate("01.2.3"), Err(Problem::LeadingZero("01".to_string())));
+        assert_eq!(validate("1.2.3-alpha.01"), Err(Problem::LeadingZero("01".to_string())));
+        assert_eq!(validate("1.2.3-alpha..1"), Err(Problem::EmptyIdentifier("pre-release")));
+        assert_eq!(validate("1.2.3-"), Err(Problem::EmptyIdentifier("pre-release")));
+        assert_eq!(validate("1.2.3+build_7"), Err(Problem::BadCharacter("build", '_')));
+        assert_eq!(validate("99999999999999999999.0.0"), Err(Problem::TooLarge("major")));
+    }
+
+    #[test]
+    fn normalizing() {
+        for (input, lowercase, expected) in [
+            ("1.2.3", false, "1.2.3"),
+            ("\u{feff} v1.2.3\r\n", false, "1.2.3"),
+            ("V01.002.0003", false, "1.2.3"),
+            ("1.2", false, "1.2.0"),
+            ("v2", false, "2.0.0"),
+            ("1.2-RC.01", false, "1.2.0-RC.1"),
+            ("1.2-RC.01", true, "1.2.0-rc.1"),
+            ("1.2.3-0a.00+007.Build", true, "1.2.3-0a.0+007.Build"),
+        ] {
+            let version = normalize(input, lowercase).expect("we expected the version to normalize");
+            assert_eq!(version.to_string(), expected, "normalizing {input:?}");
+        }
+        assert_eq!(normalize("", false), Err(Problem::Empty));
+        assert_eq!(normalize("1.2.3.4", false), Err(Problem::TooManyComponents(4)));
+        assert_eq!(normalize("1..3", false), Err(Problem::EmptyComponent("minor")));
+        assert_eq!(normalize("1.2 .3", false), Err(Problem::Whitespace));
+        assert_eq!(
+            normalize("1.2.3-beta_1", false),
+            Err(Problem::BadCharacter("pre-release", '_'))
+        );
+    }
+}